@@ -0,0 +1,189 @@
+use super::{LlmProvider, LlmUsage};
+use crate::config::Config;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use rusqlite::{Connection, OptionalExtension};
+use rust_decimal::Decimal;
+use sha2::{Digest, Sha256};
+use std::sync::Mutex;
+use tracing::{debug, warn};
+
+/// How `CachedProvider` treats the prompt store
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheMode {
+    /// Call the provider, no caching (default)
+    Passthrough,
+    /// Call the provider and store every request/response pair
+    Record,
+    /// Serve from the store only; a miss is an error. No API calls, no spend.
+    Replay,
+}
+
+impl CacheMode {
+    pub fn parse(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "" | "passthrough" | "off" => Ok(CacheMode::Passthrough),
+            "record" => Ok(CacheMode::Record),
+            "replay" => Ok(CacheMode::Replay),
+            other => anyhow::bail!("Unknown LLM_CACHE_MODE '{other}' (expected passthrough, record or replay)"),
+        }
+    }
+}
+
+/// Wraps an `LlmProvider` with a SQLite prompt cache keyed by
+/// sha256(model, max_tokens, system, user[, response schema]).
+pub struct CachedProvider {
+    inner: Box<dyn LlmProvider>,
+    mode: CacheMode,
+    conn: Mutex<Connection>,
+}
+
+impl CachedProvider {
+    pub fn new(inner: Box<dyn LlmProvider>, mode: CacheMode, path: &str) -> Result<Self> {
+        let conn = Connection::open(path).with_context(|| format!("Open LLM cache: {path}"))?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS llm_cache (
+                prompt_hash TEXT PRIMARY KEY,
+                model TEXT NOT NULL,
+                system_prompt TEXT NOT NULL,
+                user_prompt TEXT NOT NULL,
+                max_tokens INTEGER NOT NULL,
+                response TEXT NOT NULL,
+                input_tokens INTEGER NOT NULL DEFAULT 0,
+                output_tokens INTEGER NOT NULL DEFAULT 0,
+                cost TEXT NOT NULL DEFAULT '0',
+                recorded_at TEXT NOT NULL
+            );",
+        )?;
+        Ok(Self {
+            inner,
+            mode,
+            conn: Mutex::new(conn),
+        })
+    }
+
+    fn prompt_hash(&self, system: &str, user_msg: &str, max_tokens: u32, schema: Option<&serde_json::Value>) -> String {
+        let mut h = Sha256::new();
+        for part in [self.inner.name(), &max_tokens.to_string(), system, user_msg] {
+            h.update(part.as_bytes());
+            h.update([0u8]);
+        }
+        if let Some(schema) = schema {
+            h.update(schema.to_string().as_bytes());
+        }
+        hex::encode(h.finalize())
+    }
+
+    /// Serve `hash` according to the mode; `live` performs the real provider call
+    async fn serve<F>(&self, hash: String, system: &str, user_msg: &str, max_tokens: u32, live: F) -> Result<(String, LlmUsage)>
+    where
+        F: std::future::Future<Output = Result<(String, LlmUsage)>> + Send,
+    {
+        match self.mode {
+            CacheMode::Passthrough => live.await,
+            CacheMode::Replay => {
+                debug!("LLM cache replay {} {}", self.inner.name(), &hash[..12]);
+                self.lookup(&hash)?.with_context(|| {
+                    format!("LLM cache miss in replay mode ({} prompt {})", self.inner.name(), &hash[..12])
+                })
+            }
+            CacheMode::Record => {
+                let (text, usage) = live.await?;
+                if let Err(e) = self.store(&hash, system, user_msg, max_tokens, &text, &usage) {
+                    warn!("LLM cache write failed: {e}");
+                }
+                Ok((text, usage))
+            }
+        }
+    }
+
+    fn lookup(&self, hash: &str) -> Result<Option<(String, LlmUsage)>> {
+        let conn = self.conn.lock().unwrap();
+        let row = conn
+            .query_row(
+                "SELECT response, model, input_tokens, output_tokens FROM llm_cache WHERE prompt_hash = ?1",
+                [hash],
+                |row| {
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        row.get::<_, String>(1)?,
+                        row.get::<_, u32>(2)?,
+                        row.get::<_, u32>(3)?,
+                    ))
+                },
+            )
+            .optional()?;
+        // Replayed calls cost nothing: tokens are kept for reference, spend is zero
+        Ok(row.map(|(text, model, input_tokens, output_tokens)| {
+            (text, LlmUsage { model, input_tokens, output_tokens, cost: Decimal::ZERO, ..Default::default() })
+        }))
+    }
+
+    fn store(&self, hash: &str, system: &str, user_msg: &str, max_tokens: u32, text: &str, usage: &LlmUsage) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT OR REPLACE INTO llm_cache
+             (prompt_hash, model, system_prompt, user_prompt, max_tokens, response,
+              input_tokens, output_tokens, cost, recorded_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            rusqlite::params![
+                hash,
+                usage.model,
+                system,
+                user_msg,
+                max_tokens,
+                text,
+                usage.input_tokens,
+                usage.output_tokens,
+                usage.cost.to_string(),
+                chrono::Utc::now().to_rfc3339(),
+            ],
+        )?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl LlmProvider for CachedProvider {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    /// Replay needs no API key
+    fn is_configured(&self) -> bool {
+        self.mode == CacheMode::Replay || self.inner.is_configured()
+    }
+
+    async fn call(&self, system: &str, user_msg: &str, max_tokens: u32) -> Result<(String, LlmUsage)> {
+        let hash = self.prompt_hash(system, user_msg, max_tokens, None);
+        self.serve(hash, system, user_msg, max_tokens, self.inner.call(system, user_msg, max_tokens))
+            .await
+    }
+
+    async fn call_json(
+        &self,
+        system: &str,
+        user_msg: &str,
+        max_tokens: u32,
+        schema: &serde_json::Value,
+    ) -> Result<(String, LlmUsage)> {
+        let hash = self.prompt_hash(system, user_msg, max_tokens, Some(schema));
+        let live = self.inner.call_json(system, user_msg, max_tokens, schema);
+        self.serve(hash, system, user_msg, max_tokens, live).await
+    }
+}
+
+/// Wrap `inner` according to `LLM_CACHE_MODE` (`SIMULATE_AI=true` forces replay).
+/// Passthrough returns the provider untouched.
+pub fn wrap(cfg: &Config, inner: Box<dyn LlmProvider>) -> Result<Box<dyn LlmProvider>> {
+    let mode = if cfg.simulate_ai {
+        CacheMode::Replay
+    } else {
+        CacheMode::parse(&cfg.llm_cache_mode)?
+    };
+    if mode == CacheMode::Passthrough {
+        return Ok(inner);
+    }
+    let path = if cfg.llm_cache_path.is_empty() { &cfg.db_path } else { &cfg.llm_cache_path };
+    Ok(Box::new(CachedProvider::new(inner, mode, path)?))
+}
@@ -1,4 +1,7 @@
+use super::{LlmProvider, LlmUsage};
+use crate::http::HttpClient;
 use anyhow::{Context, Result};
+use async_trait::async_trait;
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use std::str::FromStr;
@@ -9,7 +12,7 @@ use tracing::debug;
 /// Role: Reject trades with <60% win chance or gambling behavior
 pub struct ClaudeClient {
     api_key: String,
-    client: reqwest::Client,
+    http: HttpClient,
 }
 
 #[allow(dead_code)]
@@ -51,28 +54,32 @@ struct Usage {
 }
 
 impl ClaudeClient {
-    pub fn new(api_key: &str) -> Self {
+    pub fn new(api_key: &str, http: HttpClient) -> Self {
         Self {
             api_key: api_key.to_string(),
-            client: reqwest::Client::builder()
-                .timeout(std::time::Duration::from_secs(90))
-                .build()
-                .expect("HTTP client"),
+            http,
         }
     }
+}
+
+#[async_trait]
+impl LlmProvider for ClaudeClient {
+    fn name(&self) -> &str {
+        "claude-sonnet-4-5"
+    }
 
-    pub fn is_configured(&self) -> bool {
+    fn is_configured(&self) -> bool {
         !self.api_key.is_empty()
     }
 
     /// Call Claude Sonnet API
     /// Returns (response_text, cost) — Sonnet pricing: $3/1M input, $15/1M output
-    pub async fn call(
+    async fn call(
         &self,
         system: &str,
         user_msg: &str,
         max_tokens: u32,
-    ) -> Result<(String, Decimal)> {
+    ) -> Result<(String, LlmUsage)> {
         let req = ClaudeRequest {
             model: "claude-sonnet-4-5-20250929".to_string(),
             max_tokens,
@@ -83,16 +90,14 @@ impl ClaudeClient {
             }],
         };
 
-        let resp = self
-            .client
+        let request = self
+            .http
             .post("https://api.anthropic.com/v1/messages")
             .header("Content-Type", "application/json")
             .header("x-api-key", &self.api_key)
             .header("anthropic-version", "2023-06-01")
-            .json(&req)
-            .send()
-            .await
-            .context("Claude API request")?;
+            .json(&req);
+        let resp = self.http.send(request).await.context("Claude API request")?;
 
         if !resp.status().is_success() {
             let status = resp.status();
@@ -130,6 +135,15 @@ impl ClaudeClient {
             usage.input_tokens, usage.output_tokens
         );
 
-        Ok((text, cost))
+        Ok((
+            text,
+            LlmUsage {
+                model: self.name().to_string(),
+                input_tokens: usage.input_tokens,
+                output_tokens: usage.output_tokens,
+                cost,
+                ..Default::default()
+            },
+        ))
     }
 }
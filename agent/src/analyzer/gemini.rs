@@ -1,4 +1,7 @@
+use super::{LlmProvider, LlmUsage};
+use crate::http::HttpClient;
 use anyhow::{Context, Result};
+use async_trait::async_trait;
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use std::str::FromStr;
@@ -7,7 +10,7 @@ use tracing::debug;
 /// Gemini Flash 2.0 API client (Paid Tier 1: $0.10/1M input, $0.40/1M output)
 pub struct GeminiClient {
     api_key: String,
-    client: reqwest::Client,
+    http: HttpClient,
 }
 
 #[derive(Serialize)]
@@ -39,6 +42,10 @@ struct GenerationConfig {
     temperature: f32,
     #[serde(rename = "maxOutputTokens")]
     max_output_tokens: u32,
+    #[serde(rename = "responseMimeType", skip_serializing_if = "Option::is_none")]
+    response_mime_type: Option<&'static str>,
+    #[serde(rename = "responseSchema", skip_serializing_if = "Option::is_none")]
+    response_schema: Option<serde_json::Value>,
 }
 
 #[derive(Deserialize)]
@@ -67,29 +74,22 @@ struct UsageMetadata {
 }
 
 impl GeminiClient {
-    pub fn new(api_key: &str) -> Self {
+    pub fn new(api_key: &str, http: HttpClient) -> Self {
         Self {
             api_key: api_key.to_string(),
-            client: reqwest::Client::builder()
-                .timeout(std::time::Duration::from_secs(60))
-                .build()
-                .expect("HTTP client"),
+            http,
         }
     }
 
-    #[allow(dead_code)]
-    pub fn is_configured(&self) -> bool {
-        !self.api_key.is_empty()
-    }
-
-    /// Call Gemini Flash 2.0 API
-    /// Returns (response_text, cost) — Paid Tier 1 pricing
-    pub async fn call(
+    /// Call Gemini Flash 2.0 API, optionally in JSON mode constrained to `schema`
+    /// Returns (response_text, usage) — Paid Tier 1 pricing
+    async fn generate(
         &self,
         system: &str,
         user_msg: &str,
         max_tokens: u32,
-    ) -> Result<(String, Decimal)> {
+        schema: Option<&serde_json::Value>,
+    ) -> Result<(String, LlmUsage)> {
         let url = format!(
             "https://generativelanguage.googleapis.com/v1beta/models/gemini-2.0-flash:generateContent?key={}",
             self.api_key
@@ -97,24 +97,32 @@ impl GeminiClient {
 
         let req = GeminiRequest {
             system_instruction: GeminiContent {
-                parts: vec![GeminiPart { text: system.to_string() }],
+                parts: vec![GeminiPart {
+                    text: system.to_string(),
+                }],
             },
             contents: vec![GeminiMessage {
                 role: "user".to_string(),
-                parts: vec![GeminiPart { text: user_msg.to_string() }],
+                parts: vec![GeminiPart {
+                    text: user_msg.to_string(),
+                }],
             }],
             generation_config: GenerationConfig {
                 temperature: 0.3,
                 max_output_tokens: max_tokens,
+                response_mime_type: schema.map(|_| "application/json"),
+                response_schema: schema.map(to_gemini_schema),
             },
         };
 
-        let resp = self
-            .client
+        let request = self
+            .http
             .post(&url)
             .header("Content-Type", "application/json")
-            .json(&req)
-            .send()
+            .json(&req);
+        let resp = self
+            .http
+            .send(request)
             .await
             .context("Gemini API request")?;
 
@@ -131,7 +139,13 @@ impl GeminiClient {
             .and_then(|c| c.into_iter().next())
             .and_then(|c| c.content)
             .and_then(|c| c.parts)
-            .map(|parts| parts.into_iter().map(|p| p.text).collect::<Vec<_>>().join(""))
+            .map(|parts| {
+                parts
+                    .into_iter()
+                    .map(|p| p.text)
+                    .collect::<Vec<_>>()
+                    .join("")
+            })
             .unwrap_or_default();
 
         if text.is_empty() {
@@ -144,8 +158,10 @@ impl GeminiClient {
         });
 
         // Gemini Flash 2.0 Paid Tier 1: $0.10/1M input, $0.40/1M output
-        let input_cost = Decimal::from(usage.prompt_token_count) * Decimal::from_str("0.0000001").unwrap();
-        let output_cost = Decimal::from(usage.candidates_token_count) * Decimal::from_str("0.0000004").unwrap();
+        let input_cost =
+            Decimal::from(usage.prompt_token_count) * Decimal::from_str("0.0000001").unwrap();
+        let output_cost =
+            Decimal::from(usage.candidates_token_count) * Decimal::from_str("0.0000004").unwrap();
         let cost = input_cost + output_cost;
 
         debug!(
@@ -153,6 +169,64 @@ impl GeminiClient {
             usage.prompt_token_count, usage.candidates_token_count
         );
 
-        Ok((text, cost))
+        Ok((
+            text,
+            LlmUsage {
+                model: self.name().to_string(),
+                input_tokens: usage.prompt_token_count,
+                output_tokens: usage.candidates_token_count,
+                cost,
+                ..Default::default()
+            },
+        ))
+    }
+}
+
+/// Gemini's responseSchema is an OpenAPI subset: upper-case type names
+fn to_gemini_schema(schema: &serde_json::Value) -> serde_json::Value {
+    use serde_json::Value;
+    match schema {
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(k, v)| match (k.as_str(), v) {
+                    ("type", Value::String(t)) => (k.clone(), Value::String(t.to_uppercase())),
+                    _ => (k.clone(), to_gemini_schema(v)),
+                })
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(items.iter().map(to_gemini_schema).collect()),
+        other => other.clone(),
+    }
+}
+
+#[async_trait]
+impl LlmProvider for GeminiClient {
+    fn name(&self) -> &str {
+        "gemini-2.0-flash"
+    }
+
+    fn is_configured(&self) -> bool {
+        !self.api_key.is_empty()
+    }
+
+    async fn call(
+        &self,
+        system: &str,
+        user_msg: &str,
+        max_tokens: u32,
+    ) -> Result<(String, LlmUsage)> {
+        self.generate(system, user_msg, max_tokens, None).await
+    }
+
+    /// JSON mode: responseMimeType=application/json + responseSchema
+    async fn call_json(
+        &self,
+        system: &str,
+        user_msg: &str,
+        max_tokens: u32,
+        schema: &serde_json::Value,
+    ) -> Result<(String, LlmUsage)> {
+        self.generate(system, user_msg, max_tokens, Some(schema))
+            .await
     }
 }
@@ -1,8 +1,14 @@
+pub mod cache;
 pub mod gemini;
 pub mod claude;
+pub mod openai;
+pub mod provider;
+pub mod structured;
+pub mod transcript;
 
 // Re-export for convenience (used by team modules)
-#[allow(unused_imports)]
 pub use gemini::GeminiClient;
-#[allow(unused_imports)]
 pub use claude::ClaudeClient;
+pub use openai::OpenAiClient;
+pub use provider::{LlmProvider, LlmUsage};
+pub use structured::{call_structured, StructuredOutput};
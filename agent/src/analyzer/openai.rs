@@ -0,0 +1,185 @@
+use super::{LlmProvider, LlmUsage};
+use crate::http::HttpClient;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+/// Client for any OpenAI-compatible `/v1/chat/completions` endpoint
+/// (llama.cpp server, vLLM, Ollama, or the hosted API).
+/// Cost is computed from the configured per-1M-token prices (0 for self-hosted).
+pub struct OpenAiClient {
+    base_url: String,
+    api_key: String,
+    model: String,
+    input_cost_per_m: Decimal,
+    output_cost_per_m: Decimal,
+    http: HttpClient,
+}
+
+#[derive(Serialize)]
+struct ChatRequest<'a> {
+    model: &'a str,
+    messages: Vec<ChatMessage<'a>>,
+    max_tokens: u32,
+    temperature: f32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    response_format: Option<serde_json::Value>,
+}
+
+#[derive(Serialize)]
+struct ChatMessage<'a> {
+    role: &'a str,
+    content: &'a str,
+}
+
+#[derive(Deserialize)]
+struct ChatResponse {
+    #[serde(default)]
+    choices: Vec<Choice>,
+    usage: Option<Usage>,
+}
+
+#[derive(Deserialize)]
+struct Choice {
+    message: ChoiceMessage,
+}
+
+#[derive(Deserialize)]
+struct ChoiceMessage {
+    content: Option<String>,
+}
+
+#[derive(Deserialize, Default)]
+struct Usage {
+    #[serde(default)]
+    prompt_tokens: u32,
+    #[serde(default)]
+    completion_tokens: u32,
+}
+
+impl OpenAiClient {
+    /// `base_url` is the server root, with or without the trailing `/v1`
+    pub fn new(
+        base_url: &str,
+        api_key: &str,
+        model: &str,
+        input_cost_per_m: Decimal,
+        output_cost_per_m: Decimal,
+        http: HttpClient,
+    ) -> Self {
+        let base = base_url.trim_end_matches('/');
+        let base = base.strip_suffix("/v1").unwrap_or(base);
+
+        Self {
+            base_url: base.to_string(),
+            api_key: api_key.to_string(),
+            model: model.to_string(),
+            input_cost_per_m,
+            output_cost_per_m,
+            http,
+        }
+    }
+
+    async fn complete(
+        &self,
+        system: &str,
+        user_msg: &str,
+        max_tokens: u32,
+        response_format: Option<serde_json::Value>,
+    ) -> Result<(String, LlmUsage)> {
+        let req = ChatRequest {
+            model: &self.model,
+            messages: vec![
+                ChatMessage { role: "system", content: system },
+                ChatMessage { role: "user", content: user_msg },
+            ],
+            max_tokens,
+            temperature: 0.3,
+            response_format,
+        };
+
+        let mut builder = self
+            .http
+            .post(format!("{}/v1/chat/completions", self.base_url))
+            .header("Content-Type", "application/json")
+            .json(&req);
+        if !self.api_key.is_empty() {
+            builder = builder.bearer_auth(&self.api_key);
+        }
+
+        let resp = self.http.send(builder).await.context("OpenAI-compatible API request")?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let body = resp.text().await.unwrap_or_default();
+            anyhow::bail!("OpenAI-compatible API {status}: {}", &body[..body.len().min(300)]);
+        }
+
+        let data: ChatResponse = resp.json().await.context("Parse chat completion response")?;
+
+        let text = data
+            .choices
+            .into_iter()
+            .next()
+            .and_then(|c| c.message.content)
+            .unwrap_or_default();
+
+        if text.is_empty() {
+            anyhow::bail!("{} returned empty response", self.model);
+        }
+
+        let usage = data.usage.unwrap_or_default();
+        let per_token = Decimal::from(1_000_000);
+        let cost = Decimal::from(usage.prompt_tokens) * self.input_cost_per_m / per_token
+            + Decimal::from(usage.completion_tokens) * self.output_cost_per_m / per_token;
+
+        debug!(
+            "{}: {} tokens in, {} tokens out, ${cost}",
+            self.model, usage.prompt_tokens, usage.completion_tokens
+        );
+
+        Ok((
+            text,
+            LlmUsage {
+                model: self.model.clone(),
+                input_tokens: usage.prompt_tokens,
+                output_tokens: usage.completion_tokens,
+                cost,
+                ..Default::default()
+            },
+        ))
+    }
+}
+
+#[async_trait]
+impl LlmProvider for OpenAiClient {
+    fn name(&self) -> &str {
+        &self.model
+    }
+
+    /// Local servers usually need no key — only a base URL and model
+    fn is_configured(&self) -> bool {
+        !self.base_url.is_empty() && !self.model.is_empty()
+    }
+
+    async fn call(&self, system: &str, user_msg: &str, max_tokens: u32) -> Result<(String, LlmUsage)> {
+        self.complete(system, user_msg, max_tokens, None).await
+    }
+
+    /// Structured outputs via `response_format: json_schema` (OpenAI, llama.cpp, vLLM)
+    async fn call_json(
+        &self,
+        system: &str,
+        user_msg: &str,
+        max_tokens: u32,
+        schema: &serde_json::Value,
+    ) -> Result<(String, LlmUsage)> {
+        let format = serde_json::json!({
+            "type": "json_schema",
+            "json_schema": { "name": "response", "schema": schema },
+        });
+        self.complete(system, user_msg, max_tokens, Some(format)).await
+    }
+}
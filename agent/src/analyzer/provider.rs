@@ -0,0 +1,73 @@
+use super::{ClaudeClient, GeminiClient, OpenAiClient};
+use crate::config::Config;
+use crate::http::HttpClient;
+use anyhow::Result;
+use async_trait::async_trait;
+use rust_decimal::Decimal;
+
+/// Token counts and USD cost of a single LLM call
+#[derive(Debug, Clone, Default)]
+pub struct LlmUsage {
+    pub model: String,
+    pub input_tokens: u32,
+    pub output_tokens: u32,
+    pub cost: Decimal,
+    /// Replies rejected by the structured-output layer (each one triggered a repair retry)
+    pub parse_failures: u32,
+}
+
+impl LlmUsage {
+    /// Fold another call's usage into this one (repair retries bill to the same logical call)
+    pub fn absorb(&mut self, other: LlmUsage) {
+        if self.model.is_empty() {
+            self.model = other.model;
+        }
+        self.input_tokens += other.input_tokens;
+        self.output_tokens += other.output_tokens;
+        self.cost += other.cost;
+        self.parse_failures += other.parse_failures;
+    }
+}
+
+/// Chat-style LLM backend used by every AI agent in the team.
+/// `call` returns (response_text, usage).
+#[async_trait]
+pub trait LlmProvider: Send + Sync {
+    /// Short label for logs, e.g. "gemini-2.0-flash"
+    fn name(&self) -> &str;
+
+    fn is_configured(&self) -> bool;
+
+    async fn call(&self, system: &str, user_msg: &str, max_tokens: u32) -> Result<(String, LlmUsage)>;
+
+    /// Like `call`, but asks the backend to constrain its reply to `schema`
+    /// (JSON Schema subset: type / properties / required / items / enum).
+    /// Backends without a JSON mode fall back to `call`; every prompt already spells out the shape.
+    async fn call_json(
+        &self,
+        system: &str,
+        user_msg: &str,
+        max_tokens: u32,
+        schema: &serde_json::Value,
+    ) -> Result<(String, LlmUsage)> {
+        let _ = schema;
+        self.call(system, user_msg, max_tokens).await
+    }
+}
+
+/// Build the team provider selected by `LLM_PROVIDER` (gemini | claude | openai)
+pub fn from_config(cfg: &Config, http: HttpClient) -> Result<Box<dyn LlmProvider>> {
+    Ok(match cfg.llm_provider.as_str() {
+        "gemini" => Box::new(GeminiClient::new(&cfg.gemini_api_key, http)),
+        "claude" => Box::new(ClaudeClient::new(&cfg.claude_api_key, http)),
+        "openai" => Box::new(OpenAiClient::new(
+            &cfg.openai_base_url,
+            &cfg.openai_api_key,
+            &cfg.openai_model,
+            cfg.openai_input_cost_per_m,
+            cfg.openai_output_cost_per_m,
+            http,
+        )),
+        other => anyhow::bail!("Unknown LLM_PROVIDER '{other}' (expected gemini, claude or openai)"),
+    })
+}
@@ -0,0 +1,142 @@
+use super::{LlmProvider, LlmUsage};
+use anyhow::Result;
+use serde::de::DeserializeOwned;
+use tracing::warn;
+
+/// Extra attempts after the first reply fails to parse or validate
+pub const MAX_REPAIRS: u32 = 2;
+
+/// A response type an agent expects back from the LLM.
+/// `schema()` is sent to backends with a JSON mode; `validate()` checks what
+/// the schema can't express (probability ranges, enum spellings).
+pub trait StructuredOutput: DeserializeOwned {
+    fn schema() -> serde_json::Value;
+
+    fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Every attempt was rejected. Carries the usage of all attempts so the
+/// caller can still bill the spend and count the failures.
+#[derive(Debug)]
+pub struct StructuredError {
+    pub usage: LlmUsage,
+    pub message: String,
+}
+
+impl std::fmt::Display for StructuredError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} (after {} attempts): {}",
+            self.usage.model, self.usage.parse_failures, self.message
+        )
+    }
+}
+
+impl std::error::Error for StructuredError {}
+
+/// Call `llm` in JSON mode and parse + validate the reply as `T`.
+/// A rejected reply is sent back with the error so the model can fix it,
+/// up to `MAX_REPAIRS` times. Returned usage covers every attempt.
+pub async fn call_structured<T: StructuredOutput>(
+    llm: &dyn LlmProvider,
+    system: &str,
+    user_msg: &str,
+    max_tokens: u32,
+) -> Result<(T, LlmUsage)> {
+    let schema = T::schema();
+    let mut usage = LlmUsage::default();
+    let mut prompt = user_msg.to_string();
+    let mut last_error = String::new();
+
+    for attempt in 0..=MAX_REPAIRS {
+        let (text, call_usage) = match llm.call_json(system, &prompt, max_tokens, &schema).await {
+            Ok(r) => r,
+            Err(e) if usage.input_tokens == 0 && usage.output_tokens == 0 => return Err(e),
+            Err(e) => {
+                last_error = format!("{last_error}; repair call failed: {e}");
+                break;
+            }
+        };
+        usage.absorb(call_usage);
+
+        match parse::<T>(&text) {
+            Ok(value) => return Ok((value, usage)),
+            Err(e) => {
+                usage.parse_failures += 1;
+                warn!("{} reply rejected (attempt {}/{}): {e}", llm.name(), attempt + 1, MAX_REPAIRS + 1);
+                prompt = format!(
+                    "{user_msg}\n\n\
+                    YOUR PREVIOUS REPLY WAS REJECTED: {e}\n\
+                    Previous reply: {}\n\n\
+                    Reply again with ONLY the corrected JSON object.",
+                    &text[..floor_char_boundary(&text, 1000)],
+                );
+                last_error = e.to_string();
+            }
+        }
+    }
+
+    Err(StructuredError { usage, message: last_error }.into())
+}
+
+fn parse<T: StructuredOutput>(text: &str) -> Result<T> {
+    let json_str = extract_json(text);
+    let value: T = serde_json::from_str(&json_str).map_err(|e| {
+        anyhow::anyhow!("JSON parse: {e} | {}", &json_str[..floor_char_boundary(&json_str, 200)])
+    })?;
+    value.validate()?;
+    Ok(value)
+}
+
+/// Pull the first balanced `{...}` object (or a ```json fenced block) out of a reply
+pub fn extract_json(text: &str) -> String {
+    if let Some(start) = text.find('{') {
+        let mut depth = 0;
+        for (i, ch) in text[start..].char_indices() {
+            match ch {
+                '{' => depth += 1,
+                '}' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return text[start..=start + i].to_string();
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    if let Some(s) = text.find("```json") {
+        let after = &text[s + 7..];
+        if let Some(e) = after.find("```") {
+            return after[..e].trim().to_string();
+        }
+    }
+    text.to_string()
+}
+
+fn floor_char_boundary(s: &str, max: usize) -> usize {
+    let mut end = s.len().min(max);
+    while !s.is_char_boundary(end) {
+        end -= 1;
+    }
+    end
+}
+
+/// `field` must be a probability in [0, 1]
+pub fn check_probability(field: &str, value: f64) -> Result<()> {
+    if !(0.0..=1.0).contains(&value) {
+        anyhow::bail!("{field} must be between 0.0 and 1.0, got {value}");
+    }
+    Ok(())
+}
+
+/// `field` must be one of `allowed` (case-insensitive)
+pub fn check_enum(field: &str, value: &str, allowed: &[&str]) -> Result<()> {
+    if !allowed.iter().any(|a| a.eq_ignore_ascii_case(value.trim())) {
+        anyhow::bail!("{field} must be one of {}, got \"{value}\"", allowed.join("|"));
+    }
+    Ok(())
+}
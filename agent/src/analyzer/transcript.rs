@@ -0,0 +1,95 @@
+use super::{LlmProvider, LlmUsage};
+use anyhow::Result;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+
+/// One prompt sent to an LLM and what came back (repair retries are separate entries)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromptRecord {
+    pub role: String,
+    pub model: String,
+    pub system: String,
+    pub user: String,
+    /// Raw reply text, or the error when the call itself failed
+    pub reply: String,
+    pub ok: bool,
+}
+
+/// Collects every prompt of one candidate's analysis, across agents
+#[derive(Default)]
+pub struct Transcript {
+    calls: Mutex<Vec<PromptRecord>>,
+}
+
+impl Transcript {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A provider that forwards to `inner` and logs each exchange under `role`
+    pub fn recorder<'a>(&'a self, role: &str, inner: &'a dyn LlmProvider) -> Recorder<'a> {
+        Recorder { transcript: self, role: role.to_string(), inner }
+    }
+
+    pub fn extend(&self, calls: Vec<PromptRecord>) {
+        self.calls.lock().unwrap().extend(calls);
+    }
+
+    /// Everything recorded so far, in call order
+    pub fn calls(&self) -> Vec<PromptRecord> {
+        self.calls.lock().unwrap().clone()
+    }
+}
+
+pub struct Recorder<'a> {
+    transcript: &'a Transcript,
+    role: String,
+    inner: &'a dyn LlmProvider,
+}
+
+impl Recorder<'_> {
+    fn log(&self, system: &str, user_msg: &str, result: &Result<(String, LlmUsage)>) {
+        let (reply, ok) = match result {
+            Ok((text, _)) => (text.clone(), true),
+            Err(e) => (e.to_string(), false),
+        };
+        self.transcript.calls.lock().unwrap().push(PromptRecord {
+            role: self.role.clone(),
+            model: self.inner.name().to_string(),
+            system: system.to_string(),
+            user: user_msg.to_string(),
+            reply,
+            ok,
+        });
+    }
+}
+
+#[async_trait]
+impl LlmProvider for Recorder<'_> {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn is_configured(&self) -> bool {
+        self.inner.is_configured()
+    }
+
+    async fn call(&self, system: &str, user_msg: &str, max_tokens: u32) -> Result<(String, LlmUsage)> {
+        let result = self.inner.call(system, user_msg, max_tokens).await;
+        self.log(system, user_msg, &result);
+        result
+    }
+
+    async fn call_json(
+        &self,
+        system: &str,
+        user_msg: &str,
+        max_tokens: u32,
+        schema: &serde_json::Value,
+    ) -> Result<(String, LlmUsage)> {
+        let result = self.inner.call_json(system, user_msg, max_tokens, schema).await;
+        self.log(system, user_msg, &result);
+        result
+    }
+}
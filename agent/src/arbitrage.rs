@@ -0,0 +1,598 @@
+use crate::config::Config;
+use crate::db::StateStore;
+use crate::live::clob::OrderBookSummary;
+use crate::live::ClobClient;
+use crate::strategy::{Capital, Cycle, Strategy, TradeIntent};
+use crate::team::events::{EventKey, Relation};
+use crate::telegram::TelegramAlert;
+use crate::types::{Direction, ExitReason, Market, Trade};
+use crate::venue::ExecutionVenue;
+use async_trait::async_trait;
+use futures::future::join_all;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+use tracing::{debug, info, warn};
+
+/// `trade_mode` of every arbitrage leg (held to resolution, see `exit_signal`)
+pub const ARB_MODE: &str = "ARB";
+
+/// Strategy name arbitrage legs are attributed to
+pub const NAME: &str = "arb";
+
+/// Gamma YES prices of a "who wins" group must sum to 1 within this much;
+/// a sibling missing from the scan shows up as a gap and the set is not exhaustive
+const EXHAUSTIVE_TOLERANCE: Decimal = dec!(0.02);
+
+/// A leg filling fewer than (1 - this) of the set's shares breaks the set
+const FILL_TOLERANCE: Decimal = dec!(0.02);
+
+/// Which set of tokens is guaranteed to pay exactly $1
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArbKind {
+    /// YES + NO of one binary market
+    Complement,
+    /// Every outcome of one categorical market
+    Categorical,
+    /// YES of every market in a negRisk "who wins" event
+    EventSet,
+}
+
+impl std::fmt::Display for ArbKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ArbKind::Complement => write!(f, "COMPLEMENT"),
+            ArbKind::Categorical => write!(f, "CATEGORICAL"),
+            ArbKind::EventSet => write!(f, "EVENT_SET"),
+        }
+    }
+}
+
+/// How an execution ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArbStatus {
+    /// Every leg filled: the set is held to resolution
+    Filled,
+    /// A leg failed and every filled leg was sold back
+    Unwound,
+    /// The first leg failed, nothing was bought
+    Failed,
+    /// A leg failed and some filled legs could not be sold: they stay open unhedged
+    Partial,
+}
+
+impl std::fmt::Display for ArbStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ArbStatus::Filled => write!(f, "FILLED"),
+            ArbStatus::Unwound => write!(f, "UNWOUND"),
+            ArbStatus::Failed => write!(f, "FAILED"),
+            ArbStatus::Partial => write!(f, "PARTIAL"),
+        }
+    }
+}
+
+/// One token of a set, priced from its order book
+#[derive(Debug, Clone)]
+pub struct Leg {
+    /// Parent market (binary, or categorical for outcome legs)
+    pub market: Market,
+    /// Side bought in a binary market; outcome legs buy YES of the outcome view
+    pub direction: Direction,
+    pub outcome: Option<String>,
+    pub token_id: String,
+    pub ask: Decimal,
+    pub ask_size: Decimal,
+    pub bid: Decimal,
+}
+
+impl Leg {
+    fn new(market: &Market, direction: Direction, outcome: Option<String>, token_id: &str) -> Self {
+        Self {
+            market: market.clone(),
+            direction,
+            outcome,
+            token_id: token_id.to_string(),
+            ask: Decimal::ONE,
+            ask_size: Decimal::ZERO,
+            bid: Decimal::ZERO,
+        }
+    }
+
+    fn priced(mut self, book: &OrderBookSummary) -> Self {
+        self.ask = book.best_ask;
+        self.ask_size = book.best_ask_size;
+        self.bid = book.best_bid;
+        self
+    }
+
+    /// Short name for logs: side, outcome, or the member market's question
+    pub fn label(&self) -> String {
+        match (&self.outcome, self.market.is_binary()) {
+            (Some(outcome), _) => outcome.clone(),
+            (None, true) if self.direction == Direction::No => "NO".to_string(),
+            (None, _) => format!("YES {}", &self.market.question[..self.market.question.len().min(40)]),
+        }
+    }
+
+    /// Market as the venue sees it: the bought side priced at this leg's ask
+    /// (the paper venue fills at the view's price, the live venue at the book's ask)
+    fn venue_view(&self) -> Option<Market> {
+        let mut view = match &self.outcome {
+            Some(outcome) => self.market.outcome_view(outcome)?,
+            None => self.market.clone(),
+        };
+        view.yes_price = match self.direction {
+            Direction::No => Decimal::ONE - self.ask,
+            _ => self.ask,
+        };
+        view.no_price = Decimal::ONE - view.yes_price;
+        Some(view)
+    }
+}
+
+/// A set of tokens that pays exactly $1 at resolution, before its books are priced
+#[derive(Debug, Clone)]
+pub struct ArbSet {
+    pub kind: ArbKind,
+    /// Market id, or the event key for event sets
+    pub event: String,
+    pub legs: Vec<Leg>,
+}
+
+/// A set whose asks add up to less than the $1 it pays, sized and costed
+#[derive(Debug, Clone)]
+pub struct Opportunity {
+    pub kind: ArbKind,
+    /// Market id, or the event key for event sets
+    pub event: String,
+    pub legs: Vec<Leg>,
+    /// Shares bought of every leg (each complete set pays $1)
+    pub sets: Decimal,
+    /// Total outlay: asks plus fees plus gas
+    pub cost: Decimal,
+    pub expected_profit: Decimal,
+}
+
+/// One leg as persisted in `arb_executions.legs`
+#[derive(Debug, Clone, Serialize)]
+pub struct LegFill {
+    pub market_id: String,
+    pub label: String,
+    pub token_id: String,
+    pub ask: Decimal,
+    pub trade_id: Option<String>,
+    pub shares: Option<Decimal>,
+}
+
+/// Outcome of one arbitrage attempt
+#[derive(Debug, Clone)]
+pub struct ArbExecution {
+    pub kind: ArbKind,
+    pub event: String,
+    pub legs: Vec<LegFill>,
+    pub sets: Decimal,
+    pub cost: Decimal,
+    pub expected_profit: Decimal,
+    pub status: ArbStatus,
+}
+
+/// Every exhaustive set among `markets`, cheapest by Gamma price first:
+/// YES + NO of each binary market, all outcomes of each categorical market,
+/// and the YES legs of each mutually exclusive group whose prices sum to about 1.
+pub fn candidate_sets(markets: &[Market]) -> Vec<ArbSet> {
+    let mut sets: Vec<(Decimal, ArbSet)> = Vec::new();
+    let mut groups: HashMap<String, Vec<&Market>> = HashMap::new();
+
+    for market in markets {
+        if market.is_binary() {
+            if let (Some(yes), Some(no)) = (market.side_token(Direction::Yes), market.side_token(Direction::No)) {
+                let legs = vec![
+                    Leg::new(market, Direction::Yes, None, &yes.token_id),
+                    Leg::new(market, Direction::No, None, &no.token_id),
+                ];
+                sets.push((yes.price + no.price, ArbSet { kind: ArbKind::Complement, event: market.id.clone(), legs }));
+            }
+            let event = EventKey::of(market);
+            if event.relation == Relation::Exclusive {
+                groups.entry(event.key).or_default().push(market);
+            }
+        } else if market.tokens.len() >= 2 {
+            let legs = market
+                .tokens
+                .iter()
+                .map(|t| Leg::new(market, Direction::Yes, Some(t.outcome.clone()), &t.token_id))
+                .collect();
+            let gamma_sum = market.tokens.iter().map(|t| t.price).sum();
+            sets.push((gamma_sum, ArbSet { kind: ArbKind::Categorical, event: market.id.clone(), legs }));
+        }
+    }
+
+    for (key, members) in groups {
+        if members.len() < 2 {
+            continue;
+        }
+        let gamma_sum: Decimal = members.iter().map(|m| m.yes_price).sum();
+        if (gamma_sum - Decimal::ONE).abs() > EXHAUSTIVE_TOLERANCE {
+            debug!("ARB: event '{key}' not exhaustive (YES sum {gamma_sum}, {} markets)", members.len());
+            continue;
+        }
+        let legs: Option<Vec<Leg>> = members
+            .iter()
+            .map(|m| m.side_token(Direction::Yes).map(|t| Leg::new(m, Direction::Yes, None, &t.token_id)))
+            .collect();
+        if let Some(legs) = legs {
+            sets.push((gamma_sum, ArbSet { kind: ArbKind::EventSet, event: key, legs }));
+        }
+    }
+
+    sets.sort_by_key(|s| s.0);
+    sets.into_iter().map(|(_, set)| set).collect()
+}
+
+/// Size and cost a priced set. Every set pays $1; each leg costs its ask plus
+/// `fee_pct`, and each order pays `gas_per_leg`. Size is capped by the thinnest
+/// top-of-book ask and by `budget`. None unless the net profit clears `min_profit_pct` of cost.
+pub fn price_set(
+    set: ArbSet,
+    fee_pct: Decimal,
+    gas_per_leg: Decimal,
+    budget: Decimal,
+    min_profit_pct: Decimal,
+) -> Option<Opportunity> {
+    let ArbSet { kind, event, legs } = set;
+    if legs.iter().any(|l| l.ask <= Decimal::ZERO || l.ask >= Decimal::ONE) {
+        return None;
+    }
+    let per_set: Decimal = legs.iter().map(|l| l.ask).sum::<Decimal>() * (Decimal::ONE + fee_pct);
+    if per_set >= Decimal::ONE {
+        return None;
+    }
+
+    let gas = gas_per_leg * Decimal::from(legs.len());
+    let depth = legs.iter().map(|l| l.ask_size).min()?;
+    let affordable = ((budget - gas) / per_set).max(Decimal::ZERO);
+    let sets = depth.min(affordable).round_dp_with_strategy(2, rust_decimal::RoundingStrategy::ToZero);
+    if sets <= Decimal::ZERO {
+        return None;
+    }
+
+    let cost = sets * per_set + gas;
+    let expected_profit = sets - cost;
+    if cost < Decimal::ONE || expected_profit < cost * min_profit_pct {
+        return None;
+    }
+
+    Some(Opportunity { kind, event, legs, sets, cost, expected_profit })
+}
+
+/// Fetch books for the candidate sets in order (at most `ARB_MAX_BOOKS`) and return
+/// the profitable ones. Event sets are only kept if every leg trades on the negRisk
+/// exchange, where the group's outcomes are mutually exclusive by construction.
+pub async fn scan(
+    sets: Vec<ArbSet>,
+    clob: &ClobClient,
+    cfg: &Config,
+    budget: Decimal,
+) -> Vec<Opportunity> {
+    let mut books: HashMap<String, Option<OrderBookSummary>> = HashMap::new();
+    let mut found = Vec::new();
+
+    for ArbSet { kind, event, legs } in sets {
+        let missing: Vec<&str> = legs
+            .iter()
+            .map(|l| l.token_id.as_str())
+            .filter(|t| !books.contains_key(*t))
+            .collect();
+        if books.len() + missing.len() > cfg.arb_max_books {
+            break;
+        }
+
+        let fetched = join_all(missing.iter().map(|token_id| async move {
+            match clob.get_order_book(token_id).await {
+                Ok(book) => Some(book),
+                Err(e) => {
+                    debug!("ARB: book for {token_id} unavailable: {e}");
+                    None
+                }
+            }
+        }))
+        .await;
+        for (token_id, book) in missing.iter().zip(fetched) {
+            books.insert(token_id.to_string(), book);
+        }
+
+        let priced: Option<Vec<Leg>> = legs
+            .into_iter()
+            .map(|l| books.get(&l.token_id).cloned().flatten().map(|b| l.priced(&b)))
+            .collect();
+        let Some(priced) = priced else { continue };
+
+        let set = ArbSet { kind, event, legs: priced };
+        let Some(opp) = price_set(set, cfg.arb_fee_pct, cfg.arb_gas_per_leg, budget, cfg.arb_min_profit_pct) else {
+            continue;
+        };
+
+        if kind == ArbKind::EventSet {
+            let neg_risk = join_all(opp.legs.iter().map(|l| clob.get_neg_risk(&l.token_id))).await;
+            if !neg_risk.iter().all(|r| matches!(r, Ok(true))) {
+                debug!("ARB: event '{}' is not a negRisk set, skipping", opp.event);
+                continue;
+            }
+        }
+
+        info!(
+            "ARB FOUND [{}] {}: {} legs, {} sets, cost ${:.2}, profit ${:.2}",
+            opp.kind, opp.event, opp.legs.len(), opp.sets, opp.cost, opp.expected_profit
+        );
+        found.push(opp);
+    }
+
+    debug!("ARB: {} books fetched, {} opportunities", books.len(), found.len());
+    found
+}
+
+/// Buy every leg of `opp`, thinnest book first so the leg most likely to fail
+/// goes before anything is committed. A leg that fails (or fills short of the
+/// set size) unwinds every leg already bought at its best bid.
+/// Returns the attempt and the legs left open.
+pub async fn execute(
+    opp: &Opportunity,
+    venue: &dyn ExecutionVenue,
+    store: &StateStore,
+    telegram: &TelegramAlert,
+) -> (ArbExecution, Vec<Trade>) {
+    let mut order: Vec<&Leg> = opp.legs.iter().collect();
+    order.sort_by_key(|l| l.ask_size);
+
+    let per_set_margin = Decimal::ONE - opp.legs.iter().map(|l| l.ask).sum::<Decimal>();
+    let mut filled: Vec<(&Leg, Trade)> = Vec::new();
+    let mut broken = false;
+
+    for leg in order {
+        let Some(view) = leg.venue_view() else {
+            broken = true;
+            break;
+        };
+        let trade = venue
+            .open_position(&view, leg.direction, view.yes_price, per_set_margin, opp.sets * leg.ask)
+            .await;
+        let Some(mut trade) = trade else {
+            warn!("ARB [{}] {}: leg {} did not fill", opp.kind, opp.event, leg.label());
+            broken = true;
+            break;
+        };
+
+        trade.trade_mode = Some(ARB_MODE.to_string());
+        trade.specialist_desk = Some(ARB_MODE.to_string());
+        trade.strategy = Some(NAME.to_string());
+        trade.category = Some(leg.market.category.clone());
+        trade.token_id = Some(leg.token_id.clone());
+        trade.outcome = leg.outcome.clone();
+        venue.ledger().update_trade(&trade);
+        if let Err(e) = store.save_trade(&trade) {
+            warn!("Failed to save arbitrage leg {}: {e}", trade.id);
+        }
+
+        let short = trade.shares < opp.sets * (Decimal::ONE - FILL_TOLERANCE);
+        if short {
+            warn!("ARB [{}] {}: leg {} filled {} of {} shares", opp.kind, opp.event, leg.label(), trade.shares, opp.sets);
+        }
+        filled.push((leg, trade));
+        if short {
+            broken = true;
+            break;
+        }
+    }
+
+    let status = if !broken {
+        info!(
+            "ARB FILLED [{}] {}: {} legs x {} sets | cost ${:.2} | locked ${:.2}",
+            opp.kind, opp.event, filled.len(), opp.sets, opp.cost, opp.expected_profit
+        );
+        telegram
+            .send_message(&format!(
+                "<b>[ARB] {} FILLED</b>\n{}\n{} legs x {} sets | Cost: ${:.2} | Locked: ${:.2}",
+                opp.kind, opp.event, filled.len(), opp.sets, opp.cost, opp.expected_profit
+            ))
+            .await
+            .ok();
+        ArbStatus::Filled
+    } else if filled.is_empty() {
+        ArbStatus::Failed
+    } else {
+        unwind(opp, &filled, venue, store, telegram).await
+    };
+
+    let legs = opp
+        .legs
+        .iter()
+        .map(|l| {
+            let trade = filled.iter().find(|(f, _)| f.token_id == l.token_id).map(|(_, t)| t);
+            LegFill {
+                market_id: l.market.id.clone(),
+                label: l.label(),
+                token_id: l.token_id.clone(),
+                ask: l.ask,
+                trade_id: trade.map(|t| t.id.clone()),
+                shares: trade.map(|t| t.shares),
+            }
+        })
+        .collect();
+
+    let execution = ArbExecution {
+        kind: opp.kind,
+        event: opp.event.clone(),
+        legs,
+        sets: opp.sets,
+        cost: opp.cost,
+        expected_profit: opp.expected_profit,
+        status,
+    };
+    let open = match status {
+        ArbStatus::Filled | ArbStatus::Partial => filled
+            .into_iter()
+            .map(|(_, t)| t)
+            .filter(|t| venue.open_positions().iter().any(|o| o.id == t.id))
+            .collect(),
+        ArbStatus::Unwound | ArbStatus::Failed => Vec::new(),
+    };
+    (execution, open)
+}
+
+/// Sell back every filled leg of a broken set
+async fn unwind(
+    opp: &Opportunity,
+    filled: &[(&Leg, Trade)],
+    venue: &dyn ExecutionVenue,
+    store: &StateStore,
+    telegram: &TelegramAlert,
+) -> ArbStatus {
+    let mut stuck = Vec::new();
+    for (leg, trade) in filled {
+        match venue.close_position(trade, leg.bid, ExitReason::ArbUnwind).await {
+            Some(closed) => {
+                store.save_trade(&closed).ok();
+                telegram.send_trade_closed_alert(&closed).await.ok();
+            }
+            None => stuck.push(leg.label()),
+        }
+    }
+    for trade in venue.ledger().take_unsaved() {
+        store.save_trade(&trade).ok();
+    }
+
+    if stuck.is_empty() {
+        warn!("ARB UNWOUND [{}] {}: {} leg(s) sold back", opp.kind, opp.event, filled.len());
+        ArbStatus::Unwound
+    } else {
+        let msg = format!(
+            "ARB [{}] {}: could not unwind {} leg(s) ({}); held unhedged to resolution",
+            opp.kind, opp.event, stuck.len(), stuck.join(", ")
+        );
+        warn!("{msg}");
+        telegram.send_critical_alert(&msg).await.ok();
+        ArbStatus::Partial
+    }
+}
+
+/// Risk-free sets: buys every token of a set that pays $1 when the asks add
+/// up to less. Markets that already hold an arbitrage position are skipped (the
+/// paper venue never depletes a book, so the same quote would otherwise be
+/// bought every cycle).
+#[derive(Default)]
+pub struct ArbStrategy {
+    /// Opportunities priced by `decide`, executed by `execute`
+    found: Mutex<Vec<Opportunity>>,
+}
+
+#[async_trait(?Send)]
+impl Strategy for ArbStrategy {
+    type Candidate = ArbSet;
+
+    fn name(&self) -> &'static str {
+        NAME
+    }
+
+    async fn propose(&self, cycle: &Cycle<'_>) -> Vec<ArbSet> {
+        let markets = match cycle.scanner.scan(cycle.config.max_markets_to_scan).await {
+            Ok(m) => m,
+            Err(e) => {
+                warn!("ARB: market scan failed: {e}");
+                return Vec::new();
+            }
+        };
+
+        let held = held_markets(cycle.venue);
+        candidate_sets(&markets)
+            .into_iter()
+            .filter(|set| !set.legs.iter().any(|l| held.contains(&l.market.id)))
+            .collect()
+    }
+
+    async fn decide(&self, cycle: &Cycle<'_>, candidates: Vec<ArbSet>) -> Vec<TradeIntent> {
+        let budget = budget(cycle.config, cycle.venue);
+        let found = scan(candidates, cycle.clob, cycle.config, budget).await;
+
+        let intents = found
+            .iter()
+            .flat_map(|opp| {
+                let margin = Decimal::ONE - opp.legs.iter().map(|l| l.ask).sum::<Decimal>();
+                opp.legs.iter().map(move |leg| TradeIntent {
+                    market_id: leg.market.id.clone(),
+                    question: leg.market.question.clone(),
+                    direction: leg.direction,
+                    outcome: leg.outcome.clone(),
+                    probability: leg.ask.to_f64().unwrap_or(0.0),
+                    confidence: 1.0,
+                    edge: margin,
+                })
+            })
+            .collect();
+        *self.found.lock().unwrap() = found;
+        intents
+    }
+
+    async fn execute(&self, cycle: &Cycle<'_>, _intents: Vec<TradeIntent>, capital: &Capital) -> Vec<Trade> {
+        let cfg = cycle.config;
+        let found = std::mem::take(&mut *self.found.lock().unwrap());
+        let mut held = held_markets(cycle.venue);
+        let mut opened = Vec::new();
+
+        for opp in found {
+            if opp.legs.iter().any(|l| held.contains(&l.market.id)) {
+                continue;
+            }
+            // Earlier executions this cycle spent part of the balance and allocation
+            let limit = budget(cfg, cycle.venue).min(capital.remaining());
+            let opp = if opp.cost > limit {
+                let set = ArbSet { kind: opp.kind, event: opp.event.clone(), legs: opp.legs.clone() };
+                match price_set(set, cfg.arb_fee_pct, cfg.arb_gas_per_leg, limit, cfg.arb_min_profit_pct) {
+                    Some(resized) => resized,
+                    None => {
+                        debug!("ARB: {} no longer affordable (${:.2} left)", opp.event, limit);
+                        continue;
+                    }
+                }
+            } else {
+                opp
+            };
+            let reserved = capital.reserve(opp.cost);
+
+            let (execution, trades) = execute(&opp, cycle.venue, cycle.store, cycle.telegram).await;
+            match execution.status {
+                ArbStatus::Filled | ArbStatus::Partial => {
+                    held.extend(opp.legs.iter().map(|l| l.market.id.clone()));
+                }
+                ArbStatus::Unwound | ArbStatus::Failed => capital.release(reserved),
+            }
+            if let Err(e) = cycle.store.log_arb_execution(cycle.number, &execution) {
+                warn!("arb_executions write failed: {e}");
+            }
+            opened.extend(trades);
+        }
+
+        opened
+    }
+}
+
+/// Markets with an open arbitrage leg
+fn held_markets(venue: &dyn ExecutionVenue) -> HashSet<String> {
+    venue
+        .open_positions()
+        .into_iter()
+        .filter(|t| t.trade_mode.as_deref() == Some(ARB_MODE))
+        .map(|t| t.market_id)
+        .collect()
+}
+
+/// Cash one arbitrage may spend: `ARB_MAX_POSITION_PCT` of the balance, never
+/// dipping into the `BALANCE_RESERVE_PCT` reserve
+fn budget(cfg: &Config, venue: &dyn ExecutionVenue) -> Decimal {
+    let balance = venue.balance();
+    let available = balance - cfg.initial_balance * cfg.balance_reserve_pct;
+    (balance * cfg.arb_max_position_pct).min(available).max(Decimal::ZERO)
+}
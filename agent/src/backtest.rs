@@ -0,0 +1,684 @@
+use crate::config::Config;
+use crate::db::StateStore;
+use crate::live::clob::OrderBookSummary;
+use crate::paper::portfolio::{exit_signal, position_price};
+use crate::market_making;
+use crate::paper::{Portfolio, PortfolioStats, RestingOrder, SimConfig, TimeInForce};
+use crate::team::executor::{apply_plan, entry_order};
+use crate::team::types::DevilsVerdict;
+use crate::team::events::EventBudget;
+use crate::team::{risk_manager, scout, strategist};
+use crate::types::{Direction, ExitReason, Market, TokenInfo, Trade};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use rust_decimal::prelude::*;
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::Path;
+use tracing::{info, warn};
+
+/// price_log has no volume/liquidity columns. Rows only exist for markets that already
+/// passed the live scout, so replayed markets get a nominal volume that clears its filter.
+const PRICE_LOG_NOMINAL_VOLUME: i64 = 1_000;
+
+/// All markets observed at one point in simulated time
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    pub at: DateTime<Utc>,
+    pub markets: Vec<Market>,
+}
+
+#[derive(Debug, Clone)]
+pub struct EquityPoint {
+    pub at: DateTime<Utc>,
+    pub equity: Decimal,
+    pub balance: Decimal,
+    pub open_positions: usize,
+}
+
+pub struct BacktestReport {
+    pub equity_curve: Vec<EquityPoint>,
+    pub trades: Vec<Trade>,
+    pub stats: PortfolioStats,
+}
+
+/// Verdicts for one market, each visible from its timestamp (None = from the start)
+type VerdictHistory = Vec<(Option<DateTime<Utc>>, DevilsVerdict)>;
+
+/// Stand-in for the LLM team: verdicts per market, looked up as of the simulated time
+#[derive(Default)]
+pub struct VerdictBook {
+    by_market: HashMap<String, VerdictHistory>,
+}
+
+/// Verdict fixture entry (`--verdicts file.json` is an array of these)
+#[derive(Debug, Deserialize)]
+struct StubVerdict {
+    market_id: String,
+    /// Verdict becomes visible at this time; omitted = known from the start
+    #[serde(default)]
+    at: Option<DateTime<Utc>>,
+    fair_value_yes: f64,
+    confidence: f64,
+    direction: String,
+    #[serde(default)]
+    reasoning: String,
+}
+
+impl VerdictBook {
+    /// Verdicts recorded by the live team in the `analyses` table
+    pub fn from_store(store: &StateStore) -> Result<Self> {
+        let mut book = Self::default();
+        for (at, a) in store.load_analyses()? {
+            let fair_value_yes = a.fair_value_yes.to_f64().unwrap_or(0.5);
+            let confidence = a.confidence.to_f64().unwrap_or(0.0);
+            book.insert(Some(at), DevilsVerdict {
+                market_id: a.market_id,
+                fair_value_yes,
+                confidence,
+                direction: a.direction.to_string(),
+                reasoning: a.reasoning,
+                bull_flaws: String::new(),
+                bear_flaws: String::new(),
+                raw_fair_value_yes: fair_value_yes,
+                raw_confidence: confidence,
+            });
+        }
+        Ok(book)
+    }
+
+    /// Stubbed verdicts from a JSON fixture
+    pub fn from_file(path: &str) -> Result<Self> {
+        let raw = std::fs::read_to_string(path).with_context(|| format!("Read verdicts {path}"))?;
+        let stubs: Vec<StubVerdict> = serde_json::from_str(&raw).context("Parse verdicts JSON")?;
+
+        let mut book = Self::default();
+        for v in stubs {
+            book.insert(v.at, DevilsVerdict {
+                market_id: v.market_id,
+                fair_value_yes: v.fair_value_yes,
+                confidence: v.confidence,
+                direction: v.direction,
+                reasoning: v.reasoning,
+                bull_flaws: String::new(),
+                bear_flaws: String::new(),
+                raw_fair_value_yes: v.fair_value_yes,
+                raw_confidence: v.confidence,
+            });
+        }
+        Ok(book)
+    }
+
+    fn insert(&mut self, at: Option<DateTime<Utc>>, verdict: DevilsVerdict) {
+        let entries = self.by_market.entry(verdict.market_id.clone()).or_default();
+        entries.push((at, verdict));
+        entries.sort_by_key(|(t, _)| *t);
+    }
+
+    /// Latest verdict visible at `now` (no look-ahead)
+    fn lookup(&self, market_id: &str, now: DateTime<Utc>) -> Option<&DevilsVerdict> {
+        self.by_market
+            .get(market_id)?
+            .iter()
+            .rev()
+            .find(|(t, _)| t.is_none_or(|t| t <= now))
+            .map(|(_, v)| v)
+    }
+
+    pub fn len(&self) -> usize {
+        self.by_market.values().map(|v| v.len()).sum()
+    }
+}
+
+/// Group price_log rows into per-minute snapshots (one cycle or price check each)
+pub fn snapshots_from_price_log(store: &StateStore) -> Result<Vec<Snapshot>> {
+    let mut snapshots: Vec<Snapshot> = Vec::new();
+
+    for row in store.load_price_log()? {
+        let bucket = row.timestamp.format("%Y-%m-%dT%H:%M").to_string();
+        let yes = row.mid;
+        let market = Market {
+            id: row.market_id.clone(),
+            question: row.question.unwrap_or_else(|| row.market_id.clone()),
+            description: String::new(),
+            category: String::new(),
+            end_date: String::new(),
+            yes_price: yes,
+            // Carry the logged spread so the strategist's spread check still bites
+            no_price: Decimal::ONE - yes + row.spread,
+            volume: Decimal::from(PRICE_LOG_NOMINAL_VOLUME),
+            liquidity: Decimal::from(PRICE_LOG_NOMINAL_VOLUME),
+            tokens: row
+                .token_id
+                .map(|t| vec![TokenInfo { token_id: t, outcome: "Yes".into(), price: yes }])
+                .unwrap_or_default(),
+            slug: String::new(),
+            fetched_at: row.timestamp,
+        };
+
+        match snapshots.last_mut() {
+            Some(s) if s.at.format("%Y-%m-%dT%H:%M").to_string() == bucket => {
+                // Later rows in the same bucket overwrite earlier quotes for that market
+                s.markets.retain(|m| m.id != market.id);
+                s.markets.push(market);
+            }
+            _ => snapshots.push(Snapshot { at: row.timestamp, markets: vec![market] }),
+        }
+    }
+
+    Ok(snapshots)
+}
+
+/// Fixture market: only id, question and yes_price are required
+#[derive(Debug, Deserialize)]
+struct FixtureMarket {
+    id: String,
+    question: String,
+    #[serde(default)]
+    category: String,
+    #[serde(default)]
+    end_date: String,
+    yes_price: Decimal,
+    #[serde(default)]
+    volume: Decimal,
+    #[serde(default)]
+    liquidity: Decimal,
+    #[serde(default)]
+    tokens: Vec<TokenInfo>,
+}
+
+#[derive(Debug, Deserialize)]
+struct FixtureSnapshot {
+    at: DateTime<Utc>,
+    markets: Vec<FixtureMarket>,
+}
+
+impl FixtureMarket {
+    fn into_market(self, at: DateTime<Utc>) -> Market {
+        Market {
+            slug: self.id.clone(),
+            id: self.id,
+            question: self.question,
+            description: String::new(),
+            category: self.category,
+            end_date: self.end_date,
+            no_price: Decimal::ONE - self.yes_price,
+            yes_price: self.yes_price,
+            volume: self.volume,
+            liquidity: self.liquidity,
+            tokens: self.tokens,
+            fetched_at: at,
+        }
+    }
+}
+
+/// Load every `*.json` (one snapshot, or an array of snapshots) and `*.csv` file in `dir`.
+/// CSV header: timestamp,market_id,question,category,end_date,yes_price,volume,liquidity
+pub fn snapshots_from_fixtures(dir: &str) -> Result<Vec<Snapshot>> {
+    let mut by_time: HashMap<DateTime<Utc>, Vec<Market>> = HashMap::new();
+
+    let mut paths: Vec<_> = std::fs::read_dir(dir)
+        .with_context(|| format!("Read fixture dir {dir}"))?
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .collect();
+    paths.sort();
+
+    for path in paths {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("json") => {
+                let raw = std::fs::read_to_string(&path)?;
+                let snaps: Vec<FixtureSnapshot> = if raw.trim_start().starts_with('[') {
+                    serde_json::from_str(&raw)
+                } else {
+                    serde_json::from_str(&raw).map(|s| vec![s])
+                }
+                .with_context(|| format!("Parse {}", path.display()))?;
+
+                for snap in snaps {
+                    let markets = by_time.entry(snap.at).or_default();
+                    markets.extend(snap.markets.into_iter().map(|m| m.into_market(snap.at)));
+                }
+            }
+            Some("csv") => {
+                for (at, market) in parse_csv_fixture(&path)? {
+                    by_time.entry(at).or_default().push(market);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let mut snapshots: Vec<Snapshot> = by_time
+        .into_iter()
+        .map(|(at, markets)| Snapshot { at, markets })
+        .collect();
+    snapshots.sort_by_key(|s| s.at);
+    Ok(snapshots)
+}
+
+fn parse_csv_fixture(path: &Path) -> Result<Vec<(DateTime<Utc>, Market)>> {
+    let raw = std::fs::read_to_string(path)?;
+    let mut lines = raw.lines().filter(|l| !l.trim().is_empty());
+
+    let header: Vec<String> = match lines.next() {
+        Some(h) => split_csv_line(h).into_iter().map(|c| c.trim().to_lowercase()).collect(),
+        None => return Ok(Vec::new()),
+    };
+    let col = |name: &str| header.iter().position(|h| h == name);
+    let (Some(ts_i), Some(id_i), Some(yes_i)) = (col("timestamp"), col("market_id"), col("yes_price")) else {
+        anyhow::bail!("{}: CSV needs timestamp, market_id and yes_price columns", path.display());
+    };
+
+    let mut out = Vec::new();
+    for (n, line) in lines.enumerate() {
+        let cells = split_csv_line(line);
+        let get = |i: Option<usize>| i.and_then(|i| cells.get(i)).map(|s| s.trim().to_string()).unwrap_or_default();
+        let dec = |i: Option<usize>| Decimal::from_str(&get(i)).unwrap_or(Decimal::ZERO);
+
+        let at = match parse_time(&get(Some(ts_i))) {
+            Some(t) => t,
+            None => {
+                warn!("{}:{}: bad timestamp, skipping row", path.display(), n + 2);
+                continue;
+            }
+        };
+
+        let market = FixtureMarket {
+            id: get(Some(id_i)),
+            question: get(col("question")),
+            category: get(col("category")),
+            end_date: get(col("end_date")),
+            yes_price: dec(Some(yes_i)),
+            volume: dec(col("volume")),
+            liquidity: dec(col("liquidity")),
+            tokens: Vec::new(),
+        };
+        out.push((at, market.into_market(at)));
+    }
+
+    Ok(out)
+}
+
+/// Minimal CSV splitter: commas, double-quoted fields, "" escapes
+fn split_csv_line(line: &str) -> Vec<String> {
+    let mut cells = Vec::new();
+    let mut cur = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                cur.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => cells.push(std::mem::take(&mut cur)),
+            _ => cur.push(c),
+        }
+    }
+    cells.push(cur);
+    cells
+}
+
+/// RFC3339 or plain YYYY-MM-DD (midnight UTC)
+pub fn parse_time(s: &str) -> Option<DateTime<Utc>> {
+    if let Ok(dt) = DateTime::parse_from_rfc3339(s) {
+        return Some(dt.with_timezone(&Utc));
+    }
+    chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d")
+        .ok()
+        .and_then(|d| d.and_hms_opt(0, 0, 0))
+        .map(|d| d.and_utc())
+}
+
+/// Replay snapshots through Scout -> Risk Manager -> Strategist -> paper fills,
+/// with exits evaluated against each snapshot's prices on the simulated clock.
+/// A market that never appears again is treated as resolved at its last price.
+/// With a limit `ENTRY_ORDER_TYPE` entries are sent as limit orders against the
+/// replayed book; resting ones fill from the next snapshot on, once prices reach them.
+pub fn run(cfg: &Config, snapshots: &[Snapshot], verdicts: &VerdictBook) -> BacktestReport {
+    let portfolio = Portfolio::new(cfg.initial_balance, SimConfig::from_config(cfg));
+    let category_filter = scout::CategoryFilter::parse(&cfg.category_filter);
+    let entry_tif = TimeInForce::parse(&cfg.entry_order_type);
+    let mut equity_curve = Vec::new();
+    let mut last_seen: HashMap<String, Market> = HashMap::new();
+    let last_appearance = last_appearances(snapshots);
+
+    for snap in snapshots {
+        let now = snap.at;
+        for m in &snap.markets {
+            last_seen.insert(m.id.clone(), m.clone());
+        }
+
+        // ── Fills of working entries, then exits ──
+        for market in &snap.markets {
+            portfolio.match_resting(&market.id, &replay_book(market), now);
+        }
+        close_exits(&portfolio, cfg, snap, &last_seen, &last_appearance);
+
+        // ── Entries (working orders count against the position limit) ──
+        let committed = || portfolio.open_position_count() + portfolio.resting_orders().len();
+        if committed() < cfg.max_open_positions {
+            let report = scout::select_candidates(snap.markets.clone(), &category_filter, cfg.max_candidates, now);
+            let budget = EventBudget::new(&portfolio.open_trades(), portfolio.balance(), cfg.max_event_exposure_pct);
+
+            for candidate in &report.candidates {
+                if committed() >= cfg.max_open_positions {
+                    break;
+                }
+                let market = &candidate.market;
+                if portfolio.open_trades().iter().any(|t| t.market_id == market.id)
+                    || portfolio.resting_orders().iter().any(|o| o.market_id == market.id)
+                {
+                    continue;
+                }
+
+                let Some(verdict) = verdicts.lookup(&market.id, now) else { continue };
+                if verdict.direction_enum() == Direction::Skip {
+                    continue;
+                }
+
+                let risk = risk_manager::check(verdict, &portfolio, cfg, cfg.max_position_pct, market.yes_price);
+                if !risk.approved {
+                    continue;
+                }
+
+                let mut plan = strategist::plan_at(verdict, &risk, market, now);
+                if plan.stop_loss_pct > Decimal::ZERO && plan.edge < plan.stop_loss_pct {
+                    continue;
+                }
+                plan.bet_size = budget.reserve(&candidate.event.key, plan.bet_size);
+                if plan.bet_size < Decimal::ONE {
+                    continue;
+                }
+
+                if let Some(tif) = entry_tif.filter(|_| market.is_binary()) {
+                    let book = replay_book(market);
+                    let placed = entry_order(&plan, &book, tif, cfg.entry_improve, cfg.entry_order_ttl_minutes, now)
+                        .and_then(|order| portfolio.submit_limit(order, &book, now));
+                    if placed.is_none() {
+                        budget.release(&candidate.event.key, plan.bet_size);
+                    }
+                } else if let Some(mut trade) = portfolio.execute_trade(
+                    &market.id,
+                    &market.question,
+                    plan.direction,
+                    market.yes_price,
+                    plan.fair_value_yes,
+                    plan.edge,
+                    plan.bet_size,
+                    market.volume,
+                ) {
+                    trade.timestamp = now;
+                    apply_plan(&mut trade, &plan, now);
+                    portfolio.update_trade(&trade);
+                } else {
+                    budget.release(&candidate.event.key, plan.bet_size);
+                }
+            }
+        }
+
+        equity_curve.push(equity_point(&portfolio, &last_seen, now));
+    }
+
+    finish(cfg, portfolio, snapshots, &last_seen, equity_curve)
+}
+
+/// Replay the market-making quoter. At each snapshot resting quotes fill if the
+/// replayed book trades through them, then up to `MM_MAX_MARKETS` markets are
+/// requoted around the verdict visible at that time (or the mid). Quotes placed
+/// at a snapshot can only fill from the next one on.
+pub fn run_market_making(cfg: &Config, snapshots: &[Snapshot], verdicts: &VerdictBook) -> BacktestReport {
+    let portfolio = Portfolio::new(cfg.initial_balance, SimConfig::from_config(cfg));
+    let params = market_making::QuoteParams::from_config(cfg);
+    let mut equity_curve = Vec::new();
+    let mut last_seen: HashMap<String, Market> = HashMap::new();
+    let last_appearance = last_appearances(snapshots);
+
+    for snap in snapshots {
+        let now = snap.at;
+        for m in &snap.markets {
+            last_seen.insert(m.id.clone(), m.clone());
+        }
+
+        // ── Fills, then exits (market-making fills hold to resolution) ──
+        for market in &snap.markets {
+            portfolio.match_resting(&market.id, &replay_book(market), now);
+        }
+        close_exits(&portfolio, cfg, snap, &last_seen, &last_appearance);
+
+        // ── Quotes: markets already quoted or held first ──
+        let listed: Vec<&str> = snap.markets.iter().map(|m| m.id.as_str()).collect();
+        for order in portfolio.resting_orders() {
+            if !listed.contains(&order.market_id.as_str()) {
+                portfolio.cancel_resting(&order.id);
+            }
+        }
+        let open = portfolio.open_trades();
+        let active = |id: &str| {
+            portfolio.resting_orders().iter().any(|o| o.market_id == id) || open.iter().any(|t| t.market_id == id)
+        };
+        let mut quoted: Vec<&Market> = snap.markets.iter().filter(|m| m.tokens.len() <= 2).collect();
+        quoted.sort_by_key(|m| !active(&m.id));
+        quoted.truncate(cfg.mm_max_markets.max(quoted.iter().filter(|m| active(&m.id)).count()));
+
+        for order in portfolio.resting_orders() {
+            if !quoted.iter().any(|m| m.id == order.market_id) {
+                portfolio.cancel_resting(&order.id);
+            }
+        }
+        for market in quoted {
+            let book = replay_book(market);
+            let fair_value = verdicts
+                .lookup(&market.id, now)
+                .and_then(|v| Decimal::from_f64(v.fair_value_yes))
+                .unwrap_or(market.yes_price);
+            let held = market_making::inventory(&open, &market.id);
+            let quote = market_making::quote(fair_value, held, book.best_bid, book.best_ask, &params);
+
+            for direction in [Direction::Yes, Direction::No] {
+                let side: Vec<RestingOrder> = portfolio
+                    .resting_orders()
+                    .into_iter()
+                    .filter(|o| o.market_id == market.id && o.direction == direction)
+                    .collect();
+                let (cancel, place) = market_making::reconcile(&side, quote.order_for(direction), params.requote_threshold);
+                for id in cancel {
+                    portfolio.cancel_resting(&id);
+                }
+                if let Some((price, shares)) = place {
+                    let mut order = RestingOrder::new(market, direction, price, shares, fair_value);
+                    order.trade_mode = Some(market_making::MM_MODE.to_string());
+                    order.strategy = Some(market_making::NAME.to_string());
+                    portfolio.submit_limit(order, &book, now);
+                }
+            }
+        }
+
+        equity_curve.push(equity_point(&portfolio, &last_seen, now));
+    }
+
+    finish(cfg, portfolio, snapshots, &last_seen, equity_curve)
+}
+
+/// Replayed YES book: the mid plus or minus half the recorded spread, at least a
+/// tick apart (fixtures carry no spread). Level sizes are not recorded, so paper
+/// orders fill on prices alone.
+fn replay_book(market: &Market) -> OrderBookSummary {
+    let spread = (market.yes_price + market.no_price - Decimal::ONE).max(Decimal::ZERO);
+    let half = (spread / Decimal::TWO).max(Decimal::new(1, 2));
+    OrderBookSummary {
+        best_bid: market.yes_price - half,
+        best_ask: market.yes_price + half,
+        spread: half * Decimal::TWO,
+        bid_depth: Decimal::ZERO,
+        ask_depth: Decimal::ZERO,
+        best_bid_size: Decimal::ZERO,
+        best_ask_size: Decimal::ZERO,
+    }
+}
+
+/// Last snapshot each market appears in (after it, the market counts as resolved)
+fn last_appearances(snapshots: &[Snapshot]) -> HashMap<&str, DateTime<Utc>> {
+    let mut last_appearance = HashMap::new();
+    for snap in snapshots {
+        for m in &snap.markets {
+            last_appearance.insert(m.id.as_str(), snap.at);
+        }
+    }
+    last_appearance
+}
+
+/// Close open trades whose exit rule triggers at this snapshot's prices, and settle
+/// trades in markets that have disappeared for good
+fn close_exits(
+    portfolio: &Portfolio,
+    cfg: &Config,
+    snap: &Snapshot,
+    last_seen: &HashMap<String, Market>,
+    last_appearance: &HashMap<&str, DateTime<Utc>>,
+) {
+    let now = snap.at;
+    for trade in portfolio.open_trades() {
+        if let Some(market) = snap.markets.iter().find(|m| m.id == trade.market_id) {
+            let price = position_price(&trade, market);
+            if let Some(reason) = exit_signal(&trade, price, cfg.exit_tp_pct, cfg.exit_sl_pct, now) {
+                portfolio.close_trade_at(&trade.id, price, reason, now);
+            }
+        } else if last_appearance.get(trade.market_id.as_str()).is_some_and(|t| *t < now) {
+            let last = last_seen
+                .get(&trade.market_id)
+                .map(|m| position_price(&trade, m))
+                .unwrap_or(trade.entry_price);
+            portfolio.close_trade_at(&trade.id, settlement_price(last), ExitReason::MarketResolved, now);
+        }
+    }
+}
+
+/// Equity = cash + open positions marked to their last seen price
+fn equity_point(portfolio: &Portfolio, last_seen: &HashMap<String, Market>, now: DateTime<Utc>) -> EquityPoint {
+    let marks: Vec<Market> = last_seen.values().cloned().collect();
+    let stats = portfolio.stats_with_markets(&marks);
+    EquityPoint {
+        at: now,
+        equity: stats.balance + stats.locked_balance + stats.unrealized_pnl,
+        balance: stats.balance,
+        open_positions: stats.open_positions,
+    }
+}
+
+/// Close out the replay and build its report
+fn finish(
+    cfg: &Config,
+    portfolio: Portfolio,
+    snapshots: &[Snapshot],
+    last_seen: &HashMap<String, Market>,
+    equity_curve: Vec<EquityPoint>,
+) -> BacktestReport {
+    // Mark anything still open to market at the end of the period
+    if let Some(end) = snapshots.last().map(|s| s.at) {
+        for trade in portfolio.open_trades() {
+            let price = last_seen
+                .get(&trade.market_id)
+                .map(|m| position_price(&trade, m))
+                .unwrap_or(trade.entry_price);
+            portfolio.close_trade_at(&trade.id, price, ExitReason::ManualStop, end);
+        }
+    }
+
+    let mut stats = portfolio.stats();
+    if let (Some(first), Some(last)) = (snapshots.first(), snapshots.last()) {
+        stats.elapsed_hours = (last.at - first.at).num_minutes() as f64 / 60.0;
+    }
+    let trades = portfolio.closed_trades();
+
+    info!(
+        "Backtest: {} snapshots, {} trades, balance ${} -> ${}",
+        snapshots.len(),
+        trades.len(),
+        cfg.initial_balance,
+        stats.balance
+    );
+
+    BacktestReport {
+        equity_curve,
+        trades,
+        stats,
+    }
+}
+
+/// Delisted market: snap near-certain prices to the binary payout
+fn settlement_price(last: Decimal) -> Decimal {
+    if last >= Decimal::new(95, 2) {
+        Decimal::ONE
+    } else if last <= Decimal::new(5, 2) {
+        Decimal::ZERO
+    } else {
+        last
+    }
+}
+
+impl BacktestReport {
+    pub fn print(&self) {
+        println!("\n{}", self.stats);
+
+        println!("\n  TRADES ({})", self.trades.len());
+        for t in &self.trades {
+            println!(
+                "  {} {:>4} {:<40} entry={:.3} exit={:.3} pnl=${:.4} [{}] {}",
+                t.timestamp.format("%Y-%m-%d %H:%M"),
+                t.direction.to_string(),
+                &t.question[..t.question.len().min(40)],
+                t.entry_price,
+                t.exit_price.unwrap_or(Decimal::ZERO),
+                t.pnl,
+                t.trade_mode.as_deref().unwrap_or("?"),
+                t.exit_reason.map(|r| r.to_string()).unwrap_or_default(),
+            );
+        }
+
+        if let (Some(first), Some(last)) = (self.equity_curve.first(), self.equity_curve.last()) {
+            let min = self.equity_curve.iter().map(|p| p.equity).min().unwrap_or(first.equity);
+            let max = self.equity_curve.iter().map(|p| p.equity).max().unwrap_or(first.equity);
+            println!(
+                "\n  EQUITY: {} points | start ${:.2} end ${:.2} | low ${:.2} high ${:.2}",
+                self.equity_curve.len(), first.equity, last.equity, min, max
+            );
+        }
+    }
+
+    /// Write equity.csv and trades.csv into `dir`
+    pub fn write_csv(&self, dir: &str) -> Result<()> {
+        std::fs::create_dir_all(dir)?;
+
+        let mut equity = String::from("timestamp,equity,balance,open_positions\n");
+        for p in &self.equity_curve {
+            equity.push_str(&format!("{},{},{},{}\n", p.at.to_rfc3339(), p.equity.round_dp(4), p.balance.round_dp(4), p.open_positions));
+        }
+        std::fs::write(Path::new(dir).join("equity.csv"), equity)?;
+
+        let mut trades = String::from("id,timestamp,market_id,question,direction,mode,entry_price,exit_price,bet_size,pnl,exit_reason,hold_hours\n");
+        for t in &self.trades {
+            trades.push_str(&format!(
+                "{},{},{},\"{}\",{},{},{},{},{},{},{},{:.1}\n",
+                t.id,
+                t.timestamp.to_rfc3339(),
+                t.market_id,
+                t.question.replace('"', "\"\""),
+                t.direction,
+                t.trade_mode.as_deref().unwrap_or(""),
+                t.entry_price.round_dp(4),
+                t.exit_price.unwrap_or(Decimal::ZERO).round_dp(4),
+                t.bet_size.round_dp(4),
+                t.pnl.round_dp(4),
+                t.exit_reason.map(|r| r.to_string()).unwrap_or_default(),
+                t.hold_duration_hours.unwrap_or(0.0),
+            ));
+        }
+        std::fs::write(Path::new(dir).join("trades.csv"), trades)?;
+
+        Ok(())
+    }
+}
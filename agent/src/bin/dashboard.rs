@@ -146,6 +146,19 @@ struct AgentInfo {
     price_check_interval: u64,
     judge_model: String, // "gemini" or "sonnet" (default gemini)
     open_position: Option<OpenPosition>,
+    http_health: Vec<HostHealth>,
+}
+
+/// Per-host circuit breaker snapshot written by the agent (`agent_status.http_health`)
+#[derive(Serialize, Deserialize, Default)]
+struct HostHealth {
+    host: String,
+    state: String,
+    consecutive_failures: u32,
+    retry_in_secs: Option<u64>,
+    last_error: Option<String>,
+    requests: u64,
+    failures: u64,
 }
 
 #[derive(Serialize)]
@@ -244,6 +257,62 @@ struct CalendarResponse {
     stats: Vec<DailyStats>,
 }
 
+/// Candidate funnel over the last 7 days: how many reached each stage and why they dropped
+#[derive(Serialize, Default)]
+struct FunnelResponse {
+    candidates: i64,
+    stages: Vec<FunnelStage>,
+    rejections: Vec<FunnelRejection>,
+}
+
+#[derive(Serialize)]
+struct FunnelStage {
+    stage: String,
+    reached: i64,
+    avg_latency_ms: f64,
+}
+
+#[derive(Serialize)]
+struct FunnelRejection {
+    reason: String,
+    count: i64,
+    avg_edge: Option<f64>,
+    avg_confidence: Option<f64>,
+}
+
+/// Brier / log-loss / reliability buckets for one agent role (+ desk, model; "*" = all)
+#[derive(Serialize)]
+struct ForecastScore {
+    role: String,
+    desk: String,
+    model: String,
+    count: usize,
+    brier: f64,
+    log_loss: f64,
+    buckets: Vec<ReliabilityBucket>,
+}
+
+/// Trades and realized P&L attributed to one strategy
+#[derive(Serialize)]
+struct StrategyStats {
+    strategy: String,
+    trades: i64,
+    open: i64,
+    wins: i64,
+    losses: i64,
+    staked: f64,
+    realized_pnl: f64,
+}
+
+#[derive(Serialize, Default, Clone)]
+struct ReliabilityBucket {
+    lower: f64,
+    upper: f64,
+    count: usize,
+    mean_forecast: f64,
+    observed_yes: f64,
+}
+
 struct ChildAgent {
     process: tokio::process::Child,
     #[allow(dead_code)]
@@ -541,6 +610,7 @@ fn read_agent_metrics(agent_id: &str, initial_bal: f64, interval: u64) -> AgentI
         price_check_interval: 180, // default 3 minutes
         judge_model: "gemini".to_string(), // default to gemini
         open_position: None,
+        http_health: Vec::new(),
     };
 
     let Some(conn) = open_db_for(agent_id) else {
@@ -602,6 +672,32 @@ fn read_agent_metrics(agent_id: &str, initial_bal: f64, interval: u64) -> AgentI
         info.phase = phase;
         info.phase_detail = detail;
     }
+    // AI budget degradation (column absent on databases from older agents)
+    if let Ok(level) = conn.query_row(
+        "SELECT budget_level FROM agent_status WHERE id = 'current'",
+        [],
+        |row| row.get::<_, Option<String>>(0),
+    ) {
+        if let Some(level) = level.filter(|l| l != "NORMAL") {
+            info.phase_detail = format!("[AI budget: {level}] {}", info.phase_detail);
+        }
+    }
+
+    // Upstream circuit breakers (column absent on databases from older agents)
+    if let Ok(Some(json)) = conn.query_row(
+        "SELECT http_health FROM agent_status WHERE id = 'current'",
+        [],
+        |row| row.get::<_, Option<String>>(0),
+    ) {
+        info.http_health = serde_json::from_str(&json).unwrap_or_default();
+        let open: Vec<String> = info.http_health.iter()
+            .filter(|h| h.state != "CLOSED")
+            .map(|h| format!("{} {}", h.host, h.state))
+            .collect();
+        if !open.is_empty() {
+            info.phase_detail = format!("[HTTP: {}] {}", open.join(", "), info.phase_detail);
+        }
+    }
 
     // Load price_check_interval and judge_model from config
     let config_path = format!("configs/{}.env", agent_id);
@@ -908,6 +1004,10 @@ async fn main() {
         .route("/api/agent/:id", get(api_agent_detail))
         .route("/api/activity", get(api_activity))
         .route("/api/calendar", get(api_calendar))
+        .route("/api/forecast-scores/:id", get(api_forecast_scores))
+        .route("/api/funnel/:id", get(api_funnel))
+        .route("/api/strategies/:id", get(api_strategies))
+        .route("/api/dossier/:id/:trade_id", get(api_dossier))
         .route("/api/start", post(api_start))
         .route("/api/stop", post(api_stop_all))
         .route("/api/stop/:id", post(api_stop_one))
@@ -1308,6 +1408,22 @@ async fn api_calendar() -> Json<CalendarResponse> {
     Json(CalendarResponse { stats })
 }
 
+async fn api_dossier(AxumPath((id, trade_id)): AxumPath<(String, String)>) -> Json<serde_json::Value> {
+    Json(read_trade_dossier(&id, &trade_id).unwrap_or(serde_json::Value::Null))
+}
+
+async fn api_funnel(AxumPath(id): AxumPath<String>) -> Json<FunnelResponse> {
+    Json(read_funnel(&id))
+}
+
+async fn api_strategies(AxumPath(id): AxumPath<String>) -> Json<Vec<StrategyStats>> {
+    Json(read_strategy_stats(&id))
+}
+
+async fn api_forecast_scores(AxumPath(id): AxumPath<String>) -> Json<Vec<ForecastScore>> {
+    Json(read_forecast_scores(&id))
+}
+
 async fn api_start(
     State(state): State<SharedState>,
     Json(req): Json<StartRequest>,
@@ -1572,6 +1688,191 @@ fn parse_hours_since(timestamp: &str) -> f64 {
     if diff > 0 { diff as f64 / 3600.0 } else { 0.0 }
 }
 
+/// The recorded debate behind one trade, each artefact decoded from its JSON column
+fn read_trade_dossier(agent_id: &str, trade_id: &str) -> Option<serde_json::Value> {
+    const COLUMNS: [&str; 7] = ["research", "desk_report", "bull_case", "bear_case", "verdict", "final_verdict", "prompts"];
+
+    let conn = open_db_for(agent_id)?;
+    // Filed under the trade, or under the resting order a patient entry filled from
+    conn.query_row(
+        &format!(
+            "SELECT {} FROM trade_dossiers
+             WHERE trade_id = ?1 OR trade_id = (SELECT order_id FROM trades WHERE id = ?1)
+             ORDER BY trade_id = ?1 DESC LIMIT 1",
+            COLUMNS.join(", ")
+        ),
+        [trade_id],
+        |row| {
+            let mut out = serde_json::Map::new();
+            for (i, col) in COLUMNS.iter().enumerate() {
+                let raw: Option<String> = row.get(i)?;
+                let value = raw
+                    .and_then(|r| serde_json::from_str(&r).ok())
+                    .unwrap_or(serde_json::Value::Null);
+                out.insert(col.to_string(), value);
+            }
+            Ok(serde_json::Value::Object(out))
+        },
+    )
+    .ok()
+}
+
+/// Aggregate `candidate_outcomes` from the last 7 days into a stage funnel
+fn read_funnel(agent_id: &str) -> FunnelResponse {
+    // Pipeline order, as written by the agent
+    const STAGES: [&str; 10] = [
+        "SCOUT", "RESEARCH", "DESK", "DEBATE", "JUDGE", "EVENT", "RISK", "STRATEGIST", "VALIDATOR", "EXECUTE",
+    ];
+
+    let Some(conn) = open_db_for(agent_id) else { return FunnelResponse::default() };
+    let since = (chrono::Utc::now() - chrono::Duration::days(7)).to_rfc3339();
+
+    let rows: Vec<(String, String)> = conn
+        .prepare("SELECT stage, latency_ms FROM candidate_outcomes WHERE timestamp >= ?1")
+        .and_then(|mut stmt| {
+            stmt.query_map([&since], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))
+                .map(|rows| rows.flatten().collect())
+        })
+        .unwrap_or_default();
+
+    let rank = |stage: &str| STAGES.iter().position(|s| *s == stage).unwrap_or(0);
+    let mut latency: HashMap<String, (f64, i64)> = HashMap::new();
+    for (_, json) in &rows {
+        if let Ok(serde_json::Value::Object(map)) = serde_json::from_str::<serde_json::Value>(json) {
+            for (stage, ms) in map {
+                let entry = latency.entry(stage).or_insert((0.0, 0));
+                entry.0 += ms.as_f64().unwrap_or(0.0);
+                entry.1 += 1;
+            }
+        }
+    }
+
+    let stages = STAGES
+        .iter()
+        .enumerate()
+        .map(|(i, stage)| FunnelStage {
+            stage: stage.to_string(),
+            reached: rows.iter().filter(|(s, _)| rank(s) >= i).count() as i64,
+            avg_latency_ms: latency.get(*stage).map(|(sum, n)| sum / *n as f64).unwrap_or(0.0),
+        })
+        .collect();
+
+    let rejections = conn
+        .prepare(
+            "SELECT reject_reason, COUNT(*), AVG(edge), AVG(confidence) FROM candidate_outcomes \
+             WHERE timestamp >= ?1 AND reject_reason IS NOT NULL \
+             GROUP BY reject_reason ORDER BY COUNT(*) DESC",
+        )
+        .and_then(|mut stmt| {
+            stmt.query_map([&since], |row| {
+                Ok(FunnelRejection {
+                    reason: row.get(0)?,
+                    count: row.get(1)?,
+                    avg_edge: row.get(2)?,
+                    avg_confidence: row.get(3)?,
+                })
+            })
+            .map(|rows| rows.flatten().collect())
+        })
+        .unwrap_or_default();
+
+    FunnelResponse { candidates: rows.len() as i64, stages, rejections }
+}
+
+/// Score every settled forecast in the agent's DB, per (role, desk, model) and per role
+fn read_forecast_scores(agent_id: &str) -> Vec<ForecastScore> {
+    const BUCKETS: usize = 10;
+    const EPS: f64 = 1e-4;
+
+    let Some(conn) = open_db_for(agent_id) else { return Vec::new() };
+
+    let rows: Vec<(String, String, String, f64, bool)> = conn
+        .prepare("SELECT role, desk, model, probability_yes, outcome_yes FROM forecasts WHERE outcome_yes IS NOT NULL")
+        .and_then(|mut stmt| {
+            stmt.query_map([], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, f64>(3)?,
+                    row.get::<_, i64>(4)? != 0,
+                ))
+            })
+            .map(|rows| rows.flatten().collect())
+        })
+        .unwrap_or_default();
+
+    /// (count, brier sum, log-loss sum, per-bucket (count, sum p, sum y))
+    type Acc = (usize, f64, f64, [(usize, f64, f64); BUCKETS]);
+    let mut acc: std::collections::BTreeMap<(String, String, String), Acc> = std::collections::BTreeMap::new();
+    for (role, desk, model, p, yes) in &rows {
+        let y = if *yes { 1.0 } else { 0.0 };
+        let q = p.clamp(EPS, 1.0 - EPS);
+        for key in [(role.clone(), desk.clone(), model.clone()), (role.clone(), "*".into(), "*".into())] {
+            let a = acc.entry(key).or_insert((0, 0.0, 0.0, [(0, 0.0, 0.0); BUCKETS]));
+            a.0 += 1;
+            a.1 += (p - y).powi(2);
+            a.2 -= y * q.ln() + (1.0 - y) * (1.0 - q).ln();
+            let b = &mut a.3[((p * BUCKETS as f64) as usize).min(BUCKETS - 1)];
+            b.0 += 1;
+            b.1 += p;
+            b.2 += y;
+        }
+    }
+
+    acc.into_iter()
+        .map(|((role, desk, model), (n, brier, log_loss, buckets))| ForecastScore {
+            role,
+            desk,
+            model,
+            count: n,
+            brier: brier / n as f64,
+            log_loss: log_loss / n as f64,
+            buckets: buckets
+                .iter()
+                .enumerate()
+                .map(|(i, &(count, sum_p, sum_y))| ReliabilityBucket {
+                    lower: i as f64 / BUCKETS as f64,
+                    upper: (i + 1) as f64 / BUCKETS as f64,
+                    count,
+                    mean_forecast: if count > 0 { sum_p / count as f64 } else { 0.0 },
+                    observed_yes: if count > 0 { sum_y / count as f64 } else { 0.0 },
+                })
+                .collect(),
+        })
+        .collect()
+}
+
+/// Per-strategy breakdown of the trades table (rows from before attribution are the team's)
+fn read_strategy_stats(agent_id: &str) -> Vec<StrategyStats> {
+    let Some(conn) = open_db_for(agent_id) else { return Vec::new() };
+
+    conn.prepare(
+        "SELECT COALESCE(strategy, 'team') AS s, COUNT(*), \
+            SUM(CASE WHEN status = 'Open' THEN 1 ELSE 0 END), \
+            SUM(CASE WHEN status = 'Won' THEN 1 ELSE 0 END), \
+            SUM(CASE WHEN status = 'Lost' THEN 1 ELSE 0 END), \
+            COALESCE(SUM(CAST(bet_size AS REAL)), 0), \
+            COALESCE(SUM(CASE WHEN status IN ('Won', 'Lost') THEN CAST(pnl AS REAL) ELSE 0 END), 0) \
+         FROM trades GROUP BY s ORDER BY s",
+    )
+    .and_then(|mut stmt| {
+        stmt.query_map([], |row| {
+            Ok(StrategyStats {
+                strategy: row.get(0)?,
+                trades: row.get(1)?,
+                open: row.get(2)?,
+                wins: row.get(3)?,
+                losses: row.get(4)?,
+                staked: row.get(5)?,
+                realized_pnl: row.get(6)?,
+            })
+        })
+        .map(|rows| rows.flatten().collect())
+    })
+    .unwrap_or_default()
+}
+
 fn read_performance_stats(agent_id: &str) -> PerformanceStats {
     let Some(conn) = open_db_for(agent_id) else { return PerformanceStats::default() };
 
@@ -2017,6 +2318,7 @@ body { background: #0a0e17; color: #c9d1d9; font-family: 'Cascadia Code', 'Fira
             <option value="politics">Politics</option>
             <option value="sports">Sports</option>
             <option value="weather">Weather</option>
+            <option value="no_politics">All except Politics</option>
         </select>
     </div>
     <div class="control-group">
@@ -2595,7 +2897,7 @@ function startAgents() {
     // Build confirmation detail
     const modeLabel = isLive ? '<span class="warn">LIVE TRADING (REAL MONEY)</span>' : '<span class="safe">PAPER TRADING (Simulated)</span>';
     const tpslLabels = { fast: '3%/3%', normal: '5%/5%', patient: '10%/7%', wide: '15%/10%' };
-    const catLabels = { all: 'All Topics', crypto: 'Crypto', politics: 'Politics', sports: 'Sports', weather: 'Weather' };
+    const catLabels = { all: 'All Topics', crypto: 'Crypto', politics: 'Politics', sports: 'Sports', weather: 'Weather', no_politics: 'All except Politics' };
 
     let detail = '';
     detail += 'Mode: ' + modeLabel + '<br>';
@@ -3054,7 +3356,7 @@ function renderModalTrades(trades) {
         const netF = parseFloat(t.net_pnl) || 0;
         const netColor = netF > 0 ? 'color:#3fb950' : netF < 0 ? 'color:#f85149' : '';
         const netDisplay = t.status === 'Open' ? '--' : (netF >= 0 ? '+' : '') + netF.toFixed(4);
-        html += '<tr>' +
+        html += '<tr style="cursor:pointer" title="Show the reasoning behind this trade" onclick="showDossier(\'' + escHtml(t.id) + '\')">' +
             '<td>' + truncate(t.timestamp, 16) + '</td>' +
             '<td title="' + escHtml(t.question) + '">' + truncate(t.question, 22) + '</td>' +
             '<td>' + t.direction + '</td>' +
@@ -3069,7 +3371,52 @@ function renderModalTrades(trades) {
             '<td><span class="status-badge ' + statusCls + '">' + t.status + '</span></td>' +
             '</tr>';
     });
-    html += '</tbody></table>';
+    html += '</tbody></table><div id="modal-dossier"></div>';
+    wrap.innerHTML = html;
+}
+
+// Full chain of reasoning for one trade: research -> desk -> bull/bear -> judge -> validator -> prompts
+async function showDossier(tradeId) {
+    const wrap = document.getElementById('modal-dossier');
+    wrap.innerHTML = '<div style="color:#8b949e;font-size:11px;padding:8px 0">Loading dossier...</div>';
+    let d;
+    try {
+        d = await fetchJSON('/api/dossier/' + currentModalAgent + '/' + encodeURIComponent(tradeId));
+    } catch (e) {
+        wrap.innerHTML = '<div class="empty-state" style="color:#f85149">Failed to load dossier: ' + escHtml(e.message) + '</div>';
+        return;
+    }
+    if (!d) {
+        wrap.innerHTML = '<div class="empty-state"><p>No dossier recorded for this trade</p></div>';
+        return;
+    }
+    const pct = v => (v * 100).toFixed(0) + '%';
+    const list = items => (items || []).map(i => '<li>' + escHtml(i) + '</li>').join('');
+    const section = (title, body) =>
+        '<div style="margin-top:10px"><div style="color:#58a6ff;font-weight:600;font-size:12px">' + title + '</div>' +
+        '<div style="font-size:11px;color:#c9d1d9;margin-top:4px">' + body + '</div></div>';
+
+    const r = d.research, k = d.desk_report, bull = d.bull_case, bear = d.bear_case, v = d.verdict, f = d.final_verdict;
+    let html = '<div style="border-top:1px solid #30363d;margin-top:12px;padding-top:8px">';
+    html += section('Research (base rate ' + pct(r.base_rate) + ')',
+        escHtml(r.news_relevance) + '<br>' + escHtml(r.fact_check) + '<br><i>' + escHtml(r.counter_arguments) + '</i><ul>' + list(r.key_factors) + '</ul>');
+    html += section(escHtml(k.desk) + ' desk: ' + pct(k.specialist_probability) + ' YES',
+        escHtml(k.data_summary) + '<br>' + escHtml(k.risk_assessment) + '<ul>' + list(k.key_factors) + '</ul>');
+    html += section('Bull: ' + pct(bull.probability_yes) + ' YES (' + escHtml(bull.case_strength) + ')',
+        escHtml(bull.reasoning) + '<ul>' + list(bull.arguments) + list(bull.evidence) + '</ul>');
+    html += section('Bear: ' + pct(bear.probability_no) + ' NO (' + escHtml(bear.case_strength) + ')',
+        escHtml(bear.reasoning) + '<ul>' + list(bear.arguments) + list(bear.evidence) + '</ul>');
+    html += section('Judge: ' + escHtml(v.direction) + ' fair ' + v.fair_value_yes.toFixed(2) + ' (raw ' + v.raw_fair_value_yes.toFixed(2) +
+        ') conf ' + v.confidence.toFixed(2),
+        escHtml(v.reasoning) + '<br><b>Bull flaws:</b> ' + escHtml(v.bull_flaws) + '<br><b>Bear flaws:</b> ' + escHtml(v.bear_flaws));
+    html += section('Final validator',
+        f ? (f.approved ? 'APPROVED' : 'REJECTED') + ' | win ' + pct(f.win_probability) + ' | ' + escHtml(f.risk_level) + '<br>' + escHtml(f.reasoning)
+          : 'not run');
+    html += section('Prompts (' + (d.prompts || []).length + ' LLM calls)', (d.prompts || []).map(p =>
+        '<details><summary>' + escHtml(p.role) + ' · ' + escHtml(p.model) + (p.ok ? '' : ' · FAILED') + '</summary>' +
+        '<pre style="white-space:pre-wrap;font-size:10px;color:#8b949e">' + escHtml(p.system) + '\n\n' + escHtml(p.user) +
+        '\n\n── reply ──\n' + escHtml(p.reply) + '</pre></details>').join(''));
+    html += '</div>';
     wrap.innerHTML = html;
 }
 
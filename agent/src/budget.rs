@@ -0,0 +1,195 @@
+use crate::config::Config;
+use crate::db::StateStore;
+use anyhow::Result;
+use chrono::{DateTime, Datelike, TimeZone, Utc};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+/// Share of the tightest cap at which the Claude final validator is dropped
+const DROP_VALIDATOR_AT: Decimal = dec!(0.70);
+/// Share at which deep analysis is halved
+const REDUCE_ANALYSIS_AT: Decimal = dec!(0.85);
+/// Share at which the agent stops calling the LLM and only monitors positions
+const MONITOR_ONLY_AT: Decimal = Decimal::ONE;
+
+/// Degradation step, ordered from full service to monitoring only
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum BudgetLevel {
+    /// Full pipeline
+    Normal,
+    /// Skip the Claude final validator (most expensive call per trade)
+    NoValidator,
+    /// Validator off and `max_deep_analysis` halved
+    ReducedAnalysis,
+    /// No LLM calls: resolve/exit open positions only
+    MonitorOnly,
+}
+
+impl std::fmt::Display for BudgetLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BudgetLevel::Normal => write!(f, "NORMAL"),
+            BudgetLevel::NoValidator => write!(f, "NO_VALIDATOR"),
+            BudgetLevel::ReducedAnalysis => write!(f, "REDUCED_ANALYSIS"),
+            BudgetLevel::MonitorOnly => write!(f, "MONITOR_ONLY"),
+        }
+    }
+}
+
+impl BudgetLevel {
+    fn for_usage(share: Decimal) -> Self {
+        if share >= MONITOR_ONLY_AT {
+            BudgetLevel::MonitorOnly
+        } else if share >= REDUCE_ANALYSIS_AT {
+            BudgetLevel::ReducedAnalysis
+        } else if share >= DROP_VALIDATOR_AT {
+            BudgetLevel::NoValidator
+        } else {
+            BudgetLevel::Normal
+        }
+    }
+
+    pub fn validator_enabled(self) -> bool {
+        self < BudgetLevel::NoValidator
+    }
+
+    /// Deep-analysis limit for this level (halved from ReducedAnalysis on, min 1)
+    pub fn max_deep_analysis(self, configured: usize) -> usize {
+        if self >= BudgetLevel::ReducedAnalysis {
+            (configured / 2).max(1)
+        } else {
+            configured
+        }
+    }
+}
+
+/// USD caps on AI spend. Zero = no cap. Provider caps are keyed by the model label
+/// the analyzer clients report (e.g. `gemini-2.0-flash`, `claude-sonnet-4-5`).
+#[derive(Debug, Clone, Default)]
+pub struct BudgetCaps {
+    pub daily: Decimal,
+    pub monthly: Decimal,
+    pub provider_daily: HashMap<String, Decimal>,
+    pub provider_monthly: HashMap<String, Decimal>,
+}
+
+impl BudgetCaps {
+    pub fn from_config(cfg: &Config) -> Self {
+        Self {
+            daily: cfg.ai_budget_daily_usd,
+            monthly: cfg.ai_budget_monthly_usd,
+            provider_daily: parse_provider_caps(&cfg.ai_budget_provider_daily),
+            provider_monthly: parse_provider_caps(&cfg.ai_budget_provider_monthly),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.daily > Decimal::ZERO
+            || self.monthly > Decimal::ZERO
+            || !self.provider_daily.is_empty()
+            || !self.provider_monthly.is_empty()
+    }
+}
+
+/// Parse `model=usd,model=usd` (invalid or non-positive entries are ignored)
+fn parse_provider_caps(spec: &str) -> HashMap<String, Decimal> {
+    spec.split(',')
+        .filter_map(|entry| {
+            let (model, cap) = entry.split_once('=')?;
+            let cap = Decimal::from_str(cap.trim()).ok()?;
+            (cap > Decimal::ZERO).then(|| (model.trim().to_string(), cap))
+        })
+        .collect()
+}
+
+/// Spend vs caps at one point in time
+#[derive(Debug, Clone)]
+pub struct BudgetStatus {
+    pub level: BudgetLevel,
+    pub daily_spend: Decimal,
+    pub monthly_spend: Decimal,
+    /// Which cap drives the level, e.g. "daily $0.92/$1.00"
+    pub binding: String,
+}
+
+impl std::fmt::Display for BudgetStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} | today ${} | month ${}",
+            self.level,
+            self.daily_spend.round_dp(4),
+            self.monthly_spend.round_dp(4)
+        )?;
+        if !self.binding.is_empty() {
+            write!(f, " | {}", self.binding)?;
+        }
+        Ok(())
+    }
+}
+
+/// Evaluate spend recorded in `llm_usage` against the caps.
+/// Agent caps and the team model's caps drive the full ladder; the validator
+/// model's own caps can only switch the validator off.
+pub fn evaluate(
+    store: &StateStore,
+    caps: &BudgetCaps,
+    team_model: &str,
+    validator_model: &str,
+    now: DateTime<Utc>,
+) -> Result<BudgetStatus> {
+    let day_start = Utc
+        .with_ymd_and_hms(now.year(), now.month(), now.day(), 0, 0, 0)
+        .single()
+        .unwrap_or(now);
+    let month_start = Utc
+        .with_ymd_and_hms(now.year(), now.month(), 1, 0, 0, 0)
+        .single()
+        .unwrap_or(now);
+
+    let daily_spend = store.llm_spend_since(day_start, None)?;
+    let monthly_spend = store.llm_spend_since(month_start, None)?;
+
+    // (share of cap, label) for every configured cap
+    let mut team_shares: Vec<(Decimal, String)> = Vec::new();
+    let share = |spend: Decimal, cap: Decimal, label: String| (spend / cap, format!("{label} ${}/${cap}", spend.round_dp(4)));
+
+    if caps.daily > Decimal::ZERO {
+        team_shares.push(share(daily_spend, caps.daily, "daily".into()));
+    }
+    if caps.monthly > Decimal::ZERO {
+        team_shares.push(share(monthly_spend, caps.monthly, "monthly".into()));
+    }
+
+    let mut validator_shares = Vec::new();
+    for (model, target) in [(team_model, &mut team_shares), (validator_model, &mut validator_shares)] {
+        if let Some(cap) = caps.provider_daily.get(model) {
+            let spend = store.llm_spend_since(day_start, Some(model))?;
+            target.push(share(spend, *cap, format!("{model} daily")));
+        }
+        if let Some(cap) = caps.provider_monthly.get(model) {
+            let spend = store.llm_spend_since(month_start, Some(model))?;
+            target.push(share(spend, *cap, format!("{model} monthly")));
+        }
+    }
+
+    let tightest = team_shares.into_iter().max_by(|a, b| a.0.cmp(&b.0));
+    let mut level = tightest.as_ref().map_or(BudgetLevel::Normal, |(s, _)| BudgetLevel::for_usage(*s));
+    let mut binding = tightest.map(|(_, label)| label).unwrap_or_default();
+
+    if let Some((_, label)) = validator_shares.into_iter().find(|(s, _)| *s >= MONITOR_ONLY_AT) {
+        if level < BudgetLevel::NoValidator {
+            level = BudgetLevel::NoValidator;
+            binding = label;
+        }
+    }
+
+    Ok(BudgetStatus {
+        level,
+        daily_spend,
+        monthly_spend,
+        binding,
+    })
+}
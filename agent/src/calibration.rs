@@ -0,0 +1,225 @@
+use crate::db::{CalibrationFitRow, CalibrationSample, StateStore};
+use crate::team::types::DevilsVerdict;
+use crate::types::Direction;
+use anyhow::Result;
+use std::collections::HashMap;
+use tracing::info;
+
+/// Wildcard scope: any desk / any model
+const ANY: &str = "*";
+
+/// What a curve maps
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Target {
+    /// Judge fair_value_yes -> realised P(YES), from trades held to resolution
+    FairValue,
+    /// Judge confidence -> realised win rate, from every closed trade
+    Confidence,
+}
+
+impl std::fmt::Display for Target {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Target::FairValue => write!(f, "fair_value"),
+            Target::Confidence => write!(f, "confidence"),
+        }
+    }
+}
+
+/// Monotone, piecewise-linear map fitted with pool-adjacent-violators (isotonic regression)
+#[derive(Debug, Clone)]
+pub struct IsotonicFit {
+    xs: Vec<f64>,
+    ys: Vec<f64>,
+    samples: usize,
+}
+
+impl IsotonicFit {
+    /// Fit (prediction, outcome in 0/1) pairs
+    pub fn fit(mut pairs: Vec<(f64, f64)>) -> Self {
+        pairs.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        // Equal predictions form one starting block of (sum_x, sum_y, count)
+        let mut groups: Vec<(f64, f64, f64)> = Vec::with_capacity(pairs.len());
+        for &(x, y) in &pairs {
+            match groups.last_mut() {
+                Some(last) if (last.0 / last.2 - x).abs() < 1e-9 => {
+                    last.0 += x;
+                    last.1 += y;
+                    last.2 += 1.0;
+                }
+                _ => groups.push((x, y, 1.0)),
+            }
+        }
+
+        // Pool neighbours while their outcome means fail to increase
+        let mut blocks: Vec<(f64, f64, f64)> = Vec::with_capacity(groups.len());
+        for group in groups {
+            blocks.push(group);
+            while blocks.len() >= 2 {
+                let b = blocks[blocks.len() - 1];
+                let a = blocks[blocks.len() - 2];
+                if a.1 / a.2 < b.1 / b.2 {
+                    break;
+                }
+                blocks.truncate(blocks.len() - 2);
+                blocks.push((a.0 + b.0, a.1 + b.1, a.2 + b.2));
+            }
+        }
+
+        Self {
+            xs: blocks.iter().map(|b| b.0 / b.2).collect(),
+            ys: blocks.iter().map(|b| b.1 / b.2).collect(),
+            samples: pairs.len(),
+        }
+    }
+
+    /// Interpolate between block means; flat beyond the ends
+    pub fn apply(&self, x: f64) -> f64 {
+        let (Some(&first), Some(&last)) = (self.xs.first(), self.xs.last()) else {
+            return x;
+        };
+        if x <= first {
+            return self.ys[0];
+        }
+        if x >= last {
+            return self.ys[self.ys.len() - 1];
+        }
+        let i = self.xs.partition_point(|&k| k <= x);
+        let (x0, x1) = (self.xs[i - 1], self.xs[i]);
+        let (y0, y1) = (self.ys[i - 1], self.ys[i]);
+        // xs[i - 1] <= x < xs[i], so the span is never zero
+        y0 + (y1 - y0) * (x - x0) / (x1 - x0)
+    }
+
+    fn knots_json(&self) -> String {
+        let knots: Vec<[f64; 2]> = self
+            .xs
+            .iter()
+            .zip(&self.ys)
+            .map(|(x, y)| [(x * 1000.0).round() / 1000.0, (y * 1000.0).round() / 1000.0])
+            .collect();
+        serde_json::to_string(&knots).unwrap_or_default()
+    }
+}
+
+/// Calibration curves per (desk, model), with desk-only, model-only and global fallbacks.
+/// A scope is used only once it has `min_samples` pairs, and its output is shrunk toward
+/// the raw value by n / (n + min_samples) so a thin history can't swing sizing.
+pub struct Calibrator {
+    min_samples: usize,
+    fits: HashMap<(Target, String, String), IsotonicFit>,
+}
+
+impl Calibrator {
+    pub fn fit(samples: &[CalibrationSample], min_samples: usize) -> Self {
+        let mut pairs: HashMap<(Target, String, String), Vec<(f64, f64)>> = HashMap::new();
+
+        for s in samples {
+            let desk = s.desk.to_uppercase();
+            let scopes = [
+                (desk.clone(), s.model.clone()),
+                (desk, ANY.to_string()),
+                (ANY.to_string(), s.model.clone()),
+                (ANY.to_string(), ANY.to_string()),
+            ];
+
+            let won = if s.won { 1.0 } else { 0.0 };
+            // Only a resolution tells us what the market actually settled at
+            let resolved_yes = s.resolved.then(|| {
+                if (s.direction == Direction::Yes) == s.won { 1.0 } else { 0.0 }
+            });
+
+            for (desk, model) in scopes {
+                pairs
+                    .entry((Target::Confidence, desk.clone(), model.clone()))
+                    .or_default()
+                    .push((s.confidence, won));
+                if let Some(yes) = resolved_yes {
+                    pairs.entry((Target::FairValue, desk, model)).or_default().push((s.fair_value, yes));
+                }
+            }
+        }
+
+        let fits = pairs
+            .into_iter()
+            .filter(|(_, p)| p.len() >= min_samples.max(1))
+            .map(|(key, p)| (key, IsotonicFit::fit(p)))
+            .collect();
+
+        Self { min_samples, fits }
+    }
+
+    /// Fit from every closed trade in the store and persist the curves
+    pub fn from_store(store: &StateStore, min_samples: usize) -> Result<Self> {
+        let calibrator = Self::fit(&store.calibration_samples()?, min_samples);
+        store.save_calibration_fits(&calibrator.rows())?;
+        Ok(calibrator)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fits.is_empty()
+    }
+
+    fn lookup(&self, target: Target, desk: &str, model: &str) -> Option<&IsotonicFit> {
+        let desk = desk.to_uppercase();
+        let scopes = [(desk.as_str(), model), (desk.as_str(), ANY), (ANY, model), (ANY, ANY)];
+        let fit = scopes
+            .into_iter()
+            .find_map(|(d, m)| self.fits.get(&(target, d.to_string(), m.to_string())));
+        fit
+    }
+
+    fn calibrate(&self, target: Target, desk: &str, model: &str, raw: f64) -> f64 {
+        match self.lookup(target, desk, model) {
+            Some(fit) => {
+                let n = fit.samples as f64;
+                let weight = n / (n + self.min_samples as f64);
+                weight * fit.apply(raw) + (1.0 - weight) * raw
+            }
+            None => raw,
+        }
+    }
+
+    /// Replace the verdict's fair value and confidence with calibrated values
+    /// (keeping the raw ones) and drop the direction to SKIP if the edge is gone.
+    pub fn apply(&self, verdict: &mut DevilsVerdict, desk: &str, model: &str, market_yes: f64) {
+        verdict.raw_fair_value_yes = verdict.fair_value_yes;
+        verdict.raw_confidence = verdict.confidence;
+        verdict.fair_value_yes =
+            self.calibrate(Target::FairValue, desk, model, verdict.fair_value_yes).clamp(0.02, 0.98);
+        verdict.confidence = self.calibrate(Target::Confidence, desk, model, verdict.confidence).clamp(0.0, 1.0);
+
+        let edge = verdict.fair_value_yes - market_yes;
+        let direction = verdict.direction_enum();
+        if (direction == Direction::Yes && edge < 0.03) || (direction == Direction::No && edge > -0.03) {
+            verdict.direction = "SKIP".to_string();
+        }
+
+        if (verdict.fair_value_yes - verdict.raw_fair_value_yes).abs() > 0.005
+            || (verdict.confidence - verdict.raw_confidence).abs() > 0.005
+        {
+            info!(
+                "  Calibration[{desk}/{model}]: fair {:.2} -> {:.2} conf {:.2} -> {:.2} -> {}",
+                verdict.raw_fair_value_yes, verdict.fair_value_yes,
+                verdict.raw_confidence, verdict.confidence, verdict.direction
+            );
+        }
+    }
+
+    pub fn rows(&self) -> Vec<CalibrationFitRow> {
+        let mut rows: Vec<_> = self
+            .fits
+            .iter()
+            .map(|((target, desk, model), fit)| CalibrationFitRow {
+                target: target.to_string(),
+                desk: desk.clone(),
+                model: model.clone(),
+                samples: fit.samples,
+                knots: fit.knots_json(),
+            })
+            .collect();
+        rows.sort_by(|a, b| (&a.target, &a.desk, &a.model).cmp(&(&b.target, &b.desk, &b.model)));
+        rows
+    }
+}
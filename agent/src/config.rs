@@ -25,12 +25,11 @@ pub struct Config {
     #[allow(dead_code)]
     pub polymarket_host: String,
     pub wallet_private_key: String,
-    #[allow(dead_code)]
     pub poly_api_key: String,
-    #[allow(dead_code)]
     pub poly_secret: String,
-    #[allow(dead_code)]
     pub poly_passphrase: String,
+    pub poly_signature_type: u8,
+    pub poly_funder_address: String,
     pub smtp_host: String,
     pub smtp_port: u16,
     pub smtp_user: String,
@@ -41,16 +40,33 @@ pub struct Config {
     pub telegram_chat_id: String,
     pub db_path: String,
     pub paper_trading: bool,
-    #[allow(dead_code)]
-    pub simulate_ai: bool,
+    pub simulate_ai: bool, // forces LLM_CACHE_MODE=replay
     // v0.3 Genetic algorithm fields
     pub gemini_api_key: String,
+    // LLM provider for the team agents: gemini | claude | openai (any /v1/chat/completions server)
+    pub llm_provider: String,
+    pub openai_base_url: String,
+    pub openai_api_key: String,
+    pub openai_model: String,
+    pub openai_input_cost_per_m: Decimal,  // USD per 1M prompt tokens (0 for self-hosted)
+    pub openai_output_cost_per_m: Decimal, // USD per 1M completion tokens
+    // LLM prompt cache: passthrough | record | replay
+    pub llm_cache_mode: String,
+    pub llm_cache_path: String, // empty = agent DB
+    // AI budget governor (0 / empty = no cap)
+    pub ai_budget_daily_usd: Decimal,
+    pub ai_budget_monthly_usd: Decimal,
+    pub ai_budget_provider_daily: String,   // "gemini-2.0-flash=1.00,claude-sonnet-4-5=0.50"
+    pub ai_budget_provider_monthly: String, // same format, monthly caps
     #[allow(dead_code)]
     pub screen_model: String,  // haiku, sonnet, gemini, simulated
     #[allow(dead_code)]
     pub deep_model: String,    // haiku, sonnet, gemini, cached
     pub min_confidence: Decimal,
-    pub category_filter: String, // "all", "crypto", "weather", "sports", "politics", "no_politics", etc.
+    // Probability calibration learned from resolved trades
+    pub calibration_enabled: bool,
+    pub calibration_min_samples: usize, // pairs needed before a desk/model curve is used
+    pub category_filter: String, // "all", "crypto", "crypto,weather", "no_politics", "sports,!nba" (see scout::CategoryFilter)
     pub exit_tp_pct: Decimal,    // take-profit threshold (0 = disabled)
     pub exit_sl_pct: Decimal,    // stop-loss threshold (0 = disabled)
     pub price_check_secs: u64,   // fast price-check interval between full cycles (0 = disabled)
@@ -60,10 +76,56 @@ pub struct Config {
     // v1.0 Team fields
     pub max_candidates: usize,     // Scout output limit (default 10)
     pub max_deep_analysis: usize,  // Bull/Bear/Devil treatment limit (default 5)
+    pub research_concurrency: usize, // researcher LLM calls in flight at once
+    pub data_concurrency: usize,     // data analyst candidates fetched at once
+    pub llm_timeout_secs: u64,       // per LLM request
+    pub data_timeout_secs: u64,      // per enrichment / order-book request
+    // Per-host request rates (per minute, 0 = unlimited)
+    pub rate_limit_llm_per_min: u32,
+    pub rate_limit_coingecko_per_min: u32,
+    pub rate_limit_clob_per_min: u32,
+    pub rate_limit_gamma_per_min: u32,
+    pub rate_limit_telegram_per_min: u32,
+    // Shared HTTP layer: retries on 429/5xx and per-host circuit breakers
+    pub http_max_retries: u32,
+    pub http_backoff_base_ms: u64,
+    pub http_backoff_max_secs: u64,
+    pub http_breaker_threshold: u32,
+    pub http_breaker_cooldown_secs: u64,
     // v2.0 Paper trading fields
     pub max_open_positions: usize, // max concurrent open positions (default 8)
+    pub max_event_exposure_pct: Decimal, // max stake per parent event, share of capital (0 = off)
+    pub strategy_allocations: String, // "team=0.85,arb=0.15": share of capital per strategy (unlisted = all)
+    pub entry_order_type: String,     // MARKET | GTC | GTD | FOK | IOC for team entries
+    pub entry_improve: Decimal,       // resting entries bid this far above the best bid
+    pub entry_order_ttl_minutes: u64, // GTD entries are withdrawn after this
     pub report_interval_hours: u64, // periodic email report interval (default 12)
     pub max_spread: Decimal,       // max acceptable bid-ask spread (default 0.05)
+    // Price history
+    pub price_log_sample_secs: u64,     // min seconds between price_log rows per market (0 = off)
+    pub price_log_retention_hours: u64, // raw price_log rows older than this are compacted
+    pub price_log_bar_minutes: u64,     // OHLC bar width for compacted history
+    // Outcome tracking for judged markets (traded or not)
+    pub resolution_check_hours: u64,    // recheck an unsettled market at most this often (0 = off)
+    pub resolution_check_batch: usize,  // Gamma lookups per check
+    // Order-book arbitrage (no AI)
+    pub arb_enabled: bool,
+    pub arb_min_profit_pct: Decimal,   // net profit per set as a share of its cost
+    pub arb_fee_pct: Decimal,          // taker fee charged on each leg's cost
+    pub arb_gas_per_leg: Decimal,      // USDC gas allowance per leg
+    pub arb_max_position_pct: Decimal, // max cost of one arbitrage, share of capital
+    pub arb_max_books: usize,          // order books fetched per cycle
+    // Market making (two-sided resting quotes)
+    pub mm_enabled: bool,
+    pub mm_max_markets: usize,               // markets quoted at once
+    pub mm_half_spread: Decimal,             // quote distance from the skewed fair value
+    pub mm_quote_size: Decimal,              // shares per side
+    pub mm_max_inventory: Decimal,           // max net YES-minus-NO shares held per market
+    pub mm_inventory_skew: Decimal,          // fair value shift at full inventory
+    pub mm_requote_threshold: Decimal,       // price move that cancels and replaces a quote
+    pub mm_reward_max_spread: Decimal,       // keep quotes this close to the midpoint (0 = off)
+    pub mm_reward_min_size: Decimal,         // min shares per quote for rewards (0 = off)
+    pub mm_fair_value_max_age_hours: u64,    // judge verdicts older than this fall back to the mid
     // Paper Trading Plus — Realistic Simulation
     pub sim_fees_enabled: bool,
     pub sim_slippage_enabled: bool,
@@ -129,6 +191,8 @@ impl Config {
             poly_api_key: env("POLY_API_KEY", ""),
             poly_secret: env("POLY_SECRET", ""),
             poly_passphrase: env("POLY_PASSPHRASE", ""),
+            poly_signature_type: env("POLY_SIGNATURE_TYPE", "0").parse().unwrap_or(0),
+            poly_funder_address: env("POLY_FUNDER_ADDRESS", ""),
             smtp_host: env("SMTP_HOST", "smtp.gmail.com"),
             smtp_port: env("SMTP_PORT", "587").parse().unwrap_or(587),
             smtp_user: env("SMTP_USER", ""),
@@ -142,9 +206,23 @@ impl Config {
             simulate_ai: env("SIMULATE_AI", "false") == "true",
             // v0.3 Genetic algorithm fields
             gemini_api_key: env("GEMINI_API_KEY", ""),
+            llm_provider: env("LLM_PROVIDER", "gemini").to_lowercase(),
+            openai_base_url: env("OPENAI_BASE_URL", "http://localhost:8080/v1"),
+            openai_api_key: env("OPENAI_API_KEY", ""),
+            openai_model: env("OPENAI_MODEL", "local-model"),
+            openai_input_cost_per_m: env_decimal("OPENAI_INPUT_COST_PER_M", "0")?,
+            openai_output_cost_per_m: env_decimal("OPENAI_OUTPUT_COST_PER_M", "0")?,
+            llm_cache_mode: env("LLM_CACHE_MODE", "passthrough").to_lowercase(),
+            llm_cache_path: env("LLM_CACHE_PATH", ""),
+            ai_budget_daily_usd: env_decimal("AI_BUDGET_DAILY_USD", "0")?,
+            ai_budget_monthly_usd: env_decimal("AI_BUDGET_MONTHLY_USD", "0")?,
+            ai_budget_provider_daily: env("AI_BUDGET_PROVIDER_DAILY", ""),
+            ai_budget_provider_monthly: env("AI_BUDGET_PROVIDER_MONTHLY", ""),
             screen_model: env("SCREEN_MODEL", "gemini"),
             deep_model: env("DEEP_MODEL", "gemini"),
             min_confidence: env_decimal("MIN_CONFIDENCE", "0.60")?,
+            calibration_enabled: env("CALIBRATION_ENABLED", "true") == "true",
+            calibration_min_samples: env("CALIBRATION_MIN_SAMPLES", "30").parse().unwrap_or(30),
             category_filter: env("CATEGORY_FILTER", "all"),
             exit_tp_pct: env_decimal("EXIT_TP_PCT", "0")?,
             exit_sl_pct: env_decimal("EXIT_SL_PCT", "0")?,
@@ -155,10 +233,54 @@ impl Config {
             // v1.0 Team fields
             max_candidates: env("MAX_CANDIDATES", "20").parse().unwrap_or(20),
             max_deep_analysis: env("MAX_DEEP_ANALYSIS", "10").parse().unwrap_or(10),
+            research_concurrency: env("RESEARCH_CONCURRENCY", "4").parse().unwrap_or(4),
+            data_concurrency: env("DATA_CONCURRENCY", "6").parse().unwrap_or(6),
+            llm_timeout_secs: env("LLM_TIMEOUT_SECS", "90").parse().unwrap_or(90),
+            data_timeout_secs: env("DATA_TIMEOUT_SECS", "20").parse().unwrap_or(20),
+            rate_limit_llm_per_min: env("RATE_LIMIT_LLM_PER_MIN", "60").parse().unwrap_or(60),
+            rate_limit_coingecko_per_min: env("RATE_LIMIT_COINGECKO_PER_MIN", "25").parse().unwrap_or(25),
+            rate_limit_clob_per_min: env("RATE_LIMIT_CLOB_PER_MIN", "300").parse().unwrap_or(300),
+            rate_limit_gamma_per_min: env("RATE_LIMIT_GAMMA_PER_MIN", "120").parse().unwrap_or(120),
+            rate_limit_telegram_per_min: env("RATE_LIMIT_TELEGRAM_PER_MIN", "30").parse().unwrap_or(30),
+            http_max_retries: env("HTTP_MAX_RETRIES", "3").parse().unwrap_or(3),
+            http_backoff_base_ms: env("HTTP_BACKOFF_BASE_MS", "500").parse().unwrap_or(500),
+            http_backoff_max_secs: env("HTTP_BACKOFF_MAX_SECS", "30").parse().unwrap_or(30),
+            http_breaker_threshold: env("HTTP_BREAKER_THRESHOLD", "5").parse().unwrap_or(5),
+            http_breaker_cooldown_secs: env("HTTP_BREAKER_COOLDOWN_SECS", "60").parse().unwrap_or(60),
             // v2.0 Paper trading fields
             max_open_positions: env("MAX_OPEN_POSITIONS", "8").parse().unwrap_or(8),
+            max_event_exposure_pct: env_decimal("MAX_EVENT_EXPOSURE_PCT", "0.15")?,
+            strategy_allocations: env("STRATEGY_ALLOCATIONS", ""),
+            entry_order_type: env("ENTRY_ORDER_TYPE", "MARKET"),
+            entry_improve: env_decimal("ENTRY_IMPROVE", "0.00")?,
+            entry_order_ttl_minutes: env("ENTRY_ORDER_TTL_MINUTES", "60").parse().unwrap_or(60),
             report_interval_hours: env("REPORT_INTERVAL_HOURS", "12").parse().unwrap_or(12),
             max_spread: env_decimal("MAX_SPREAD", "0.05")?,
+            // Price history
+            price_log_sample_secs: env("PRICE_LOG_SAMPLE_SECS", "60").parse().unwrap_or(60),
+            price_log_retention_hours: env("PRICE_LOG_RETENTION_HOURS", "72").parse().unwrap_or(72),
+            price_log_bar_minutes: env("PRICE_LOG_BAR_MINUTES", "60").parse().unwrap_or(60),
+            // Outcome tracking
+            resolution_check_hours: env("RESOLUTION_CHECK_HOURS", "6").parse().unwrap_or(6),
+            resolution_check_batch: env("RESOLUTION_CHECK_BATCH", "50").parse().unwrap_or(50),
+            // Order-book arbitrage
+            arb_enabled: env("ARB_ENABLED", "false") == "true",
+            arb_min_profit_pct: env_decimal("ARB_MIN_PROFIT_PCT", "0.01")?,
+            arb_fee_pct: env_decimal("ARB_FEE_PCT", "0.00")?,
+            arb_gas_per_leg: env_decimal("ARB_GAS_PER_LEG", "0.05")?,
+            arb_max_position_pct: env_decimal("ARB_MAX_POSITION_PCT", "0.10")?,
+            arb_max_books: env("ARB_MAX_BOOKS", "120").parse().unwrap_or(120),
+            // Market making
+            mm_enabled: env("MM_ENABLED", "false") == "true",
+            mm_max_markets: env("MM_MAX_MARKETS", "5").parse().unwrap_or(5),
+            mm_half_spread: env_decimal("MM_HALF_SPREAD", "0.02")?,
+            mm_quote_size: env_decimal("MM_QUOTE_SIZE", "25")?,
+            mm_max_inventory: env_decimal("MM_MAX_INVENTORY", "100")?,
+            mm_inventory_skew: env_decimal("MM_INVENTORY_SKEW", "0.02")?,
+            mm_requote_threshold: env_decimal("MM_REQUOTE_THRESHOLD", "0.01")?,
+            mm_reward_max_spread: env_decimal("MM_REWARD_MAX_SPREAD", "0")?,
+            mm_reward_min_size: env_decimal("MM_REWARD_MIN_SIZE", "0")?,
+            mm_fair_value_max_age_hours: env("MM_FAIR_VALUE_MAX_AGE_HOURS", "24").parse().unwrap_or(24),
             // Paper Trading Plus — Realistic Simulation
             sim_fees_enabled: env("SIM_FEES_ENABLED", "true") == "true",
             sim_slippage_enabled: env("SIM_SLIPPAGE_ENABLED", "true") == "true",
@@ -1,15 +1,134 @@
-use crate::paper::PortfolioStats;
-use crate::types::{Analysis, Trade};
+use crate::analyzer::LlmUsage;
+use crate::arbitrage::ArbExecution;
+use crate::live::clob::OrderBookSummary;
+use crate::paper::{PortfolioStats, RestingOrder, TimeInForce};
+use crate::team::events::Signal;
+use crate::team::types::{CandidateOutcome, TradeDossier};
+use crate::types::{Analysis, Direction, ExitReason, Market, Trade, TradeStatus};
 use anyhow::{Context, Result};
 use chrono::{Datelike, Timelike};
 use rust_decimal::Decimal;
 use rust_decimal::prelude::ToPrimitive;
-use rusqlite::Connection;
+use rusqlite::{Connection, OptionalExtension};
 use std::str::FromStr;
 
 pub struct StateStore {
     pub(crate) conn: Connection,
-    json_log_path: String,
+    /// Append-only JSON copy of every saved trade (None for an in-memory store)
+    json_log_path: Option<String>,
+}
+
+/// One `price_log` observation (mid/spread of the market's YES token)
+#[derive(Debug, Clone)]
+pub struct PriceLogRow {
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+    pub market_id: String,
+    pub token_id: Option<String>,
+    pub mid: Decimal,
+    pub spread: Decimal,
+    pub question: Option<String>,
+}
+
+/// Aggregated `llm_usage` for one agent role + model
+#[derive(Debug, Clone)]
+pub struct LlmCostRow {
+    pub role: String,
+    pub model: String,
+    pub calls: i64,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub cost: Decimal,
+    /// Replies rejected by schema/range validation
+    pub parse_failures: i64,
+}
+
+/// One closed trade as a (judge prediction, realised outcome) calibration pair
+#[derive(Debug, Clone)]
+pub struct CalibrationSample {
+    pub desk: String,
+    pub model: String,
+    /// Raw judge fair value (P(YES)) and confidence, before calibration
+    pub fair_value: f64,
+    pub confidence: f64,
+    pub direction: Direction,
+    pub won: bool,
+    /// Held until the market settled to 0 or 1, so `won` + `direction` give the outcome
+    pub resolved: bool,
+}
+
+/// Persisted summary of one fitted calibration curve
+#[derive(Debug, Clone)]
+pub struct CalibrationFitRow {
+    pub target: String,
+    pub desk: String,
+    pub model: String,
+    pub samples: usize,
+    /// JSON `[[x, y], ...]` knots of the fitted curve
+    pub knots: String,
+}
+
+/// One settled agent forecast
+#[derive(Debug, Clone)]
+pub struct ForecastRow {
+    pub role: String,
+    pub desk: String,
+    pub model: String,
+    pub probability_yes: f64,
+    pub outcome_yes: bool,
+}
+
+/// A judged market's last decision and, once settled, its outcome
+#[derive(Debug, Clone)]
+pub struct MarketResolutionRow {
+    pub decision: String,
+    /// Side the judge leaned toward (YES/NO), even when the decision was to stay out
+    pub lean: Direction,
+    /// YES price when the decision was made
+    pub decision_price: f64,
+    pub outcome_yes: Option<bool>,
+}
+
+/// OHLC accumulator for one market/bucket during price_log compaction
+struct PriceBar {
+    market_id: String,
+    token_id: Option<String>,
+    bar_start: chrono::DateTime<chrono::Utc>,
+    open: Decimal,
+    high: Decimal,
+    low: Decimal,
+    close: Decimal,
+    spread_sum: Decimal,
+    samples: i64,
+}
+
+impl PriceBar {
+    fn new(
+        market_id: String,
+        token_id: Option<String>,
+        bar_start: chrono::DateTime<chrono::Utc>,
+        mid: Decimal,
+        spread: Decimal,
+    ) -> Self {
+        Self {
+            market_id,
+            token_id,
+            bar_start,
+            open: mid,
+            high: mid,
+            low: mid,
+            close: mid,
+            spread_sum: spread,
+            samples: 1,
+        }
+    }
+
+    fn push(&mut self, mid: Decimal, spread: Decimal) {
+        self.high = self.high.max(mid);
+        self.low = self.low.min(mid);
+        self.close = mid;
+        self.spread_sum += spread;
+        self.samples += 1;
+    }
 }
 
 impl StateStore {
@@ -56,7 +175,11 @@ impl StateStore {
                 entry_slippage TEXT DEFAULT '0',
                 exit_slippage TEXT DEFAULT '0',
                 platform_fee TEXT DEFAULT '0',
-                maker_taker_fee TEXT DEFAULT '0'
+                maker_taker_fee TEXT DEFAULT '0',
+                calibrated_fair_value REAL,
+                calibrated_confidence REAL,
+                outcome TEXT,
+                strategy TEXT
             );
 
             CREATE TABLE IF NOT EXISTS analyses (
@@ -118,7 +241,24 @@ impl StateStore {
                 ask TEXT NOT NULL,
                 mid TEXT NOT NULL,
                 spread TEXT NOT NULL,
-                source TEXT DEFAULT 'clob.polymarket.com'
+                source TEXT DEFAULT 'clob.polymarket.com',
+                bid_depth TEXT DEFAULT '0',
+                ask_depth TEXT DEFAULT '0'
+            );
+
+            -- Downsampled price_log history (mid OHLC per market, one row per bar)
+            CREATE TABLE IF NOT EXISTS price_bars (
+                market_id TEXT NOT NULL,
+                token_id TEXT,
+                bar_start TEXT NOT NULL,
+                bar_secs INTEGER NOT NULL,
+                open TEXT NOT NULL,
+                high TEXT NOT NULL,
+                low TEXT NOT NULL,
+                close TEXT NOT NULL,
+                avg_spread TEXT NOT NULL,
+                samples INTEGER NOT NULL,
+                PRIMARY KEY (market_id, bar_start, bar_secs)
             );
 
             CREATE TABLE IF NOT EXISTS cycle_log (
@@ -135,6 +275,138 @@ impl StateStore {
                 duration_secs REAL
             );
 
+            -- Token usage + cost of every LLM call, per agent role and model
+            CREATE TABLE IF NOT EXISTS llm_usage (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                timestamp TEXT NOT NULL,
+                role TEXT NOT NULL,
+                model TEXT NOT NULL,
+                market_id TEXT,
+                input_tokens INTEGER NOT NULL,
+                output_tokens INTEGER NOT NULL,
+                cost TEXT NOT NULL,
+                parse_failures INTEGER NOT NULL DEFAULT 0
+            );
+
+            -- Calibration curves fitted from resolved trades, per target/desk/model ('*' = any)
+            CREATE TABLE IF NOT EXISTS calibration_fits (
+                target TEXT NOT NULL,
+                desk TEXT NOT NULL,
+                model TEXT NOT NULL,
+                samples INTEGER NOT NULL,
+                knots TEXT NOT NULL,
+                fitted_at TEXT NOT NULL,
+                PRIMARY KEY (target, desk, model)
+            );
+
+            -- Every probability an agent produced for an analysed market (P(YES)),
+            -- scored against the outcome once the market settles
+            CREATE TABLE IF NOT EXISTS forecasts (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                timestamp TEXT NOT NULL,
+                market_id TEXT NOT NULL,
+                role TEXT NOT NULL,
+                desk TEXT NOT NULL,
+                model TEXT NOT NULL,
+                probability_yes REAL NOT NULL,
+                outcome_yes INTEGER,
+                resolved_at TEXT
+            );
+
+            -- Last pipeline decision on every judged market, and how the market finally settled
+            CREATE TABLE IF NOT EXISTS market_resolutions (
+                market_id TEXT PRIMARY KEY,
+                question TEXT NOT NULL,
+                end_date TEXT,
+                decided_at TEXT NOT NULL,
+                decision TEXT NOT NULL,
+                lean TEXT NOT NULL,
+                decision_price REAL NOT NULL,
+                fair_value REAL NOT NULL,
+                outcome_yes INTEGER,
+                resolved_at TEXT,
+                last_checked TEXT
+            );
+
+            -- Where each Scout candidate left the pipeline, per cycle (reject_reason NULL = traded)
+            CREATE TABLE IF NOT EXISTS candidate_outcomes (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                timestamp TEXT NOT NULL,
+                cycle_number INTEGER NOT NULL,
+                market_id TEXT NOT NULL,
+                question TEXT NOT NULL,
+                desk TEXT,
+                stage TEXT NOT NULL,
+                reject_reason TEXT,
+                edge REAL,
+                confidence REAL,
+                bet_size TEXT,
+                latency_ms TEXT NOT NULL
+            );
+
+            -- Cross-market inconsistencies inside one parent event (judge adjustments, mispricings)
+            CREATE TABLE IF NOT EXISTS event_signals (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                timestamp TEXT NOT NULL,
+                cycle_number INTEGER NOT NULL,
+                event_key TEXT NOT NULL,
+                relation TEXT NOT NULL,
+                kind TEXT NOT NULL,
+                market_id TEXT NOT NULL,
+                magnitude REAL NOT NULL,
+                detail TEXT NOT NULL
+            );
+
+            -- Order-book arbitrage attempts (legs JSON: market, token, ask, trade id, shares)
+            CREATE TABLE IF NOT EXISTS arb_executions (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                timestamp TEXT NOT NULL,
+                cycle_number INTEGER NOT NULL,
+                kind TEXT NOT NULL,
+                event_key TEXT NOT NULL,
+                legs TEXT NOT NULL,
+                sets TEXT NOT NULL,
+                cost TEXT NOT NULL,
+                expected_profit TEXT NOT NULL,
+                status TEXT NOT NULL
+            );
+
+            -- Resting limit orders (market-making quotes) and how each ended
+            CREATE TABLE IF NOT EXISTS resting_orders (
+                order_id TEXT PRIMARY KEY,
+                placed_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL,
+                market_id TEXT NOT NULL,
+                token_id TEXT NOT NULL,
+                direction TEXT NOT NULL,
+                price TEXT NOT NULL,
+                shares TEXT NOT NULL,
+                filled TEXT NOT NULL,
+                strategy TEXT,
+                status TEXT NOT NULL
+            );
+
+            -- Full debate behind each trade (JSON per artefact) for post-mortems
+            CREATE TABLE IF NOT EXISTS trade_dossiers (
+                trade_id TEXT PRIMARY KEY,
+                market_id TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                research TEXT NOT NULL,
+                desk_report TEXT NOT NULL,
+                bull_case TEXT NOT NULL,
+                bear_case TEXT NOT NULL,
+                verdict TEXT NOT NULL,
+                final_verdict TEXT,
+                prompts TEXT NOT NULL,
+                outcome_verdict TEXT
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_llm_usage_role ON llm_usage(role, model);
+            CREATE INDEX IF NOT EXISTS idx_candidate_outcomes_time ON candidate_outcomes(timestamp);
+            CREATE INDEX IF NOT EXISTS idx_event_signals_time ON event_signals(timestamp);
+            CREATE INDEX IF NOT EXISTS idx_arb_executions_time ON arb_executions(timestamp);
+            CREATE INDEX IF NOT EXISTS idx_resting_orders_status ON resting_orders(status);
+            CREATE INDEX IF NOT EXISTS idx_forecasts_market ON forecasts(market_id, outcome_yes);
             CREATE INDEX IF NOT EXISTS idx_price_log_market ON price_log(market_id, timestamp);
             CREATE INDEX IF NOT EXISTS idx_cycle_log_time ON cycle_log(timestamp);
 
@@ -142,7 +414,10 @@ impl StateStore {
                 id TEXT PRIMARY KEY CHECK (id = 'current'),
                 phase TEXT NOT NULL,
                 details TEXT,
-                updated_at TEXT NOT NULL
+                updated_at TEXT NOT NULL,
+                budget_level TEXT DEFAULT 'NORMAL',
+                budget_details TEXT,
+                http_health TEXT
             );
 
             -- ═══ KNOWLEDGE COLLECTION TABLES ═══
@@ -229,8 +504,13 @@ impl StateStore {
 
         // Migrate simulation columns for existing DBs
         migrate_simulation_columns(&conn);
+        migrate_price_log_columns(&conn);
+        migrate_budget_columns(&conn);
+        migrate_http_health_column(&conn);
+        migrate_dossier_outcome_column(&conn);
+        migrate_llm_usage_columns(&conn);
 
-        let json_log_path = db_path.replace(".db", "_trades.jsonl");
+        let json_log_path = (db_path != ":memory:").then(|| db_path.replace(".db", "_trades.jsonl"));
 
         Ok(Self { conn, json_log_path })
     }
@@ -238,8 +518,10 @@ impl StateStore {
     /// Update the agent's current status/phase
     pub fn update_status(&self, phase: &str, details: &str) -> Result<()> {
         self.conn.execute(
-            "INSERT OR REPLACE INTO agent_status (id, phase, details, updated_at)
-             VALUES ('current', ?1, ?2, ?3)",
+            "INSERT INTO agent_status (id, phase, details, updated_at)
+             VALUES ('current', ?1, ?2, ?3)
+             ON CONFLICT(id) DO UPDATE SET
+                phase = excluded.phase, details = excluded.details, updated_at = excluded.updated_at",
             rusqlite::params![
                 phase,
                 details,
@@ -249,6 +531,29 @@ impl StateStore {
         Ok(())
     }
 
+    /// Record the AI budget degradation level alongside the phase status
+    pub fn update_budget_status(&self, level: &str, details: &str) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO agent_status (id, phase, details, updated_at, budget_level, budget_details)
+             VALUES ('current', 'STARTING', '', ?3, ?1, ?2)
+             ON CONFLICT(id) DO UPDATE SET
+                budget_level = excluded.budget_level, budget_details = excluded.budget_details",
+            rusqlite::params![level, details, chrono::Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    /// Per-host circuit breaker snapshot (JSON array of `http::HostHealth`)
+    pub fn update_http_health(&self, health_json: &str) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO agent_status (id, phase, details, updated_at, http_health)
+             VALUES ('current', 'STARTING', '', ?2, ?1)
+             ON CONFLICT(id) DO UPDATE SET http_health = excluded.http_health",
+            rusqlite::params![health_json, chrono::Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
     /// Check if a market was recently analyzed (within `hours` hours)
     pub fn was_recently_analyzed(&self, market_id: &str, hours: i64) -> bool {
         let cutoff = (chrono::Utc::now() - chrono::Duration::hours(hours))
@@ -290,10 +595,11 @@ impl StateStore {
              bull_probability, bear_probability, judge_fair_value, judge_confidence, judge_model,
              exit_reason, hold_duration_hours, token_id,
              raw_entry_price, raw_exit_price, entry_gas_fee, exit_gas_fee,
-             entry_slippage, exit_slippage, platform_fee, maker_taker_fee)
+             entry_slippage, exit_slippage, platform_fee, maker_taker_fee,
+             calibrated_fair_value, calibrated_confidence, outcome, strategy)
              VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15,
                      ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23, ?24, ?25, ?26, ?27, ?28, ?29,
-                     ?30, ?31, ?32, ?33, ?34, ?35, ?36, ?37)",
+                     ?30, ?31, ?32, ?33, ?34, ?35, ?36, ?37, ?38, ?39, ?40, ?41)",
             rusqlite::params![
                 trade.id,
                 trade.timestamp.to_rfc3339(),
@@ -332,16 +638,20 @@ impl StateStore {
                 trade.exit_slippage.to_string(),
                 trade.platform_fee.to_string(),
                 trade.maker_taker_fee.to_string(),
+                trade.calibrated_fair_value,
+                trade.calibrated_confidence,
+                trade.outcome,
+                trade.strategy,
             ],
         )?;
 
         // Append to JSON log
-        if let Ok(json) = serde_json::to_string(trade) {
+        if let (Some(path), Ok(json)) = (&self.json_log_path, serde_json::to_string(trade)) {
             use std::io::Write;
             if let Ok(mut f) = std::fs::OpenOptions::new()
                 .create(true)
                 .append(true)
-                .open(&self.json_log_path)
+                .open(path)
             {
                 let _ = writeln!(f, "{json}");
             }
@@ -350,6 +660,93 @@ impl StateStore {
         Ok(())
     }
 
+    /// Load every persisted trade in save order (oldest first).
+    /// `save_trade` uses INSERT OR REPLACE, so a trade's rowid moves to the end each
+    /// time it is re-saved (e.g. on close) and the last row carries the latest balance.
+    pub fn load_trades(&self) -> Result<Vec<Trade>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, timestamp, market_id, question, direction,
+             entry_price, fair_value, edge, bet_size, shares, status, exit_price, pnl, balance_after, order_id,
+             trade_mode, take_profit, stop_loss, max_hold_until, category, specialist_desk,
+             bull_probability, bear_probability, judge_fair_value, judge_confidence, judge_model,
+             exit_reason, hold_duration_hours, token_id,
+             raw_entry_price, raw_exit_price, entry_gas_fee, exit_gas_fee,
+             entry_slippage, exit_slippage, platform_fee, maker_taker_fee,
+             calibrated_fair_value, calibrated_confidence, outcome, strategy
+             FROM trades ORDER BY rowid ASC",
+        )?;
+
+        let rows = stmt.query_map([], |row| {
+            let dec = |idx: usize| -> rusqlite::Result<Decimal> {
+                let s: Option<String> = row.get(idx)?;
+                Ok(s.and_then(|v| Decimal::from_str(&v).ok()).unwrap_or(Decimal::ZERO))
+            };
+            let opt_dec = |idx: usize| -> rusqlite::Result<Option<Decimal>> {
+                let s: Option<String> = row.get(idx)?;
+                Ok(s.and_then(|v| Decimal::from_str(&v).ok()))
+            };
+            let opt_time = |idx: usize| -> rusqlite::Result<Option<chrono::DateTime<chrono::Utc>>> {
+                let s: Option<String> = row.get(idx)?;
+                Ok(s.and_then(|v| chrono::DateTime::parse_from_rfc3339(&v).ok())
+                    .map(|d| d.with_timezone(&chrono::Utc)))
+            };
+
+            let direction: String = row.get(4)?;
+            let status: String = row.get(10)?;
+            let exit_reason: Option<String> = row.get(26)?;
+
+            Ok(Trade {
+                id: row.get(0)?,
+                timestamp: opt_time(1)?.unwrap_or_else(chrono::Utc::now),
+                market_id: row.get(2)?,
+                question: row.get(3)?,
+                direction: direction.parse().unwrap_or(Direction::Skip),
+                entry_price: dec(5)?,
+                fair_value: dec(6)?,
+                edge: dec(7)?,
+                bet_size: dec(8)?,
+                shares: dec(9)?,
+                status: status.parse().unwrap_or(TradeStatus::Cancelled),
+                exit_price: opt_dec(11)?,
+                pnl: dec(12)?,
+                balance_after: dec(13)?,
+                order_id: row.get(14)?,
+                trade_mode: row.get(15)?,
+                take_profit: opt_dec(16)?,
+                stop_loss: opt_dec(17)?,
+                max_hold_until: opt_time(18)?,
+                category: row.get(19)?,
+                specialist_desk: row.get(20)?,
+                bull_probability: row.get(21)?,
+                bear_probability: row.get(22)?,
+                judge_fair_value: row.get(23)?,
+                judge_confidence: row.get(24)?,
+                judge_model: row.get(25)?,
+                exit_reason: exit_reason.and_then(|r| r.parse::<ExitReason>().ok()),
+                hold_duration_hours: row.get(27)?,
+                token_id: row.get(28)?,
+                raw_entry_price: opt_dec(29)?,
+                raw_exit_price: opt_dec(30)?,
+                entry_gas_fee: dec(31)?,
+                exit_gas_fee: dec(32)?,
+                entry_slippage: dec(33)?,
+                exit_slippage: dec(34)?,
+                platform_fee: dec(35)?,
+                maker_taker_fee: dec(36)?,
+                calibrated_fair_value: row.get(37)?,
+                calibrated_confidence: row.get(38)?,
+                outcome: row.get(39)?,
+                strategy: row.get(40)?,
+            })
+        })?;
+
+        let mut trades = Vec::new();
+        for row in rows {
+            trades.push(row.context("Read trade row")?);
+        }
+        Ok(trades)
+    }
+
     pub fn save_analysis(&self, a: &Analysis) -> Result<()> {
         self.conn.execute(
             "INSERT INTO analyses (timestamp, market_id, question, current_price, fair_value,
@@ -401,33 +798,703 @@ impl StateStore {
         Ok(())
     }
 
-    /// Log a price observation for audit trail
-    #[allow(dead_code)]
+    /// Price history joined with the last known question per market, oldest first.
+    /// Compacted history contributes one row per OHLC bar, stamped at the bar's end:
+    /// its close is only known once the bar is over, so replays must not see it earlier.
+    pub fn load_price_log(&self) -> Result<Vec<PriceLogRow>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT p.timestamp, p.market_id, p.token_id, p.mid, p.spread, mc.question, p.span
+             FROM (
+                 SELECT bar_start AS timestamp, market_id, token_id, close AS mid, avg_spread AS spread,
+                        bar_secs AS span, 0 AS seq
+                 FROM price_bars
+                 UNION ALL
+                 SELECT timestamp, market_id, token_id, mid, spread, 0 AS span, id AS seq FROM price_log
+             ) p
+             LEFT JOIN market_cache mc ON mc.market_id = p.market_id
+             ORDER BY p.timestamp ASC, p.seq ASC",
+        )?;
+
+        let rows = stmt.query_map([], |row| {
+            let ts: String = row.get(0)?;
+            let mid: String = row.get(3)?;
+            let spread: String = row.get(4)?;
+            let span: i64 = row.get(6)?;
+            let start = chrono::DateTime::parse_from_rfc3339(&ts)
+                .map(|d| d.with_timezone(&chrono::Utc))
+                .unwrap_or_else(|_| chrono::Utc::now());
+            Ok(PriceLogRow {
+                timestamp: start + chrono::Duration::seconds(span),
+                market_id: row.get(1)?,
+                token_id: row.get(2)?,
+                mid: Decimal::from_str(&mid).unwrap_or(Decimal::ZERO),
+                spread: Decimal::from_str(&spread).unwrap_or(Decimal::ZERO),
+                question: row.get(5)?,
+            })
+        })?;
+
+        let mut rows: Vec<PriceLogRow> = rows.filter_map(|r| r.ok()).collect();
+        // Bars moved to their end time; the sort is stable, so same-time rows keep log order
+        rows.sort_by_key(|r| r.timestamp);
+        Ok(rows)
+    }
+
+    /// Recorded analyst verdicts (analyses table) with their timestamps, oldest first
+    pub fn load_analyses(&self) -> Result<Vec<(chrono::DateTime<chrono::Utc>, Analysis)>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT timestamp, market_id, question, current_price, fair_value, edge, direction,
+             should_trade, reasoning, model, api_cost, confidence
+             FROM analyses ORDER BY timestamp ASC, id ASC",
+        )?;
+
+        let rows = stmt.query_map([], |row| {
+            let dec = |idx: usize| -> rusqlite::Result<Decimal> {
+                let s: Option<String> = row.get(idx)?;
+                Ok(s.and_then(|v| Decimal::from_str(&v).ok()).unwrap_or(Decimal::ZERO))
+            };
+            let ts: String = row.get(0)?;
+            let direction: String = row.get(6)?;
+            let should_trade: i32 = row.get(7)?;
+
+            Ok((
+                chrono::DateTime::parse_from_rfc3339(&ts)
+                    .map(|d| d.with_timezone(&chrono::Utc))
+                    .unwrap_or_else(|_| chrono::Utc::now()),
+                Analysis {
+                    market_id: row.get(1)?,
+                    question: row.get(2)?,
+                    current_yes_price: dec(3)?,
+                    fair_value_yes: dec(4)?,
+                    edge: dec(5)?,
+                    confidence: dec(11)?,
+                    direction: direction.parse().unwrap_or(Direction::Skip),
+                    should_trade: should_trade != 0,
+                    reasoning: row.get::<_, Option<String>>(8)?.unwrap_or_default(),
+                    api_cost_usd: dec(10)?,
+                    model_used: row.get::<_, Option<String>>(9)?.unwrap_or_default(),
+                    enrichment_data: None,
+                },
+            ))
+        })?;
+
+        Ok(rows.filter_map(|r| r.ok()).collect())
+    }
+
+    /// Log an order-book observation. Skipped (returns false) if this market was
+    /// already sampled within the last `sample_secs`, or if the book is empty or
+    /// one-sided (no mid to record); `sample_secs = 0` disables logging.
     pub fn log_price(
         &self,
         market_id: &str,
         token_id: Option<&str>,
-        bid: Decimal,
-        ask: Decimal,
-        mid: Decimal,
-        spread: Decimal,
-    ) -> Result<()> {
+        book: &OrderBookSummary,
+        sample_secs: u64,
+    ) -> Result<bool> {
+        if sample_secs == 0 || book.bid_depth <= Decimal::ZERO || book.ask_depth <= Decimal::ZERO {
+            return Ok(false);
+        }
+
+        let now = chrono::Utc::now();
+        let cutoff = (now - chrono::Duration::seconds(sample_secs as i64)).to_rfc3339();
+        let recent: i64 = self.conn.query_row(
+            "SELECT COUNT(*) FROM price_log WHERE market_id = ?1 AND timestamp > ?2",
+            rusqlite::params![market_id, cutoff],
+            |row| row.get(0),
+        )?;
+        if recent > 0 {
+            return Ok(false);
+        }
+
+        let mid = (book.best_bid + book.best_ask) / Decimal::TWO;
         self.conn.execute(
-            "INSERT INTO price_log (timestamp, market_id, token_id, bid, ask, mid, spread)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            "INSERT INTO price_log (timestamp, market_id, token_id, bid, ask, mid, spread, bid_depth, ask_depth)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
             rusqlite::params![
-                chrono::Utc::now().to_rfc3339(),
+                now.to_rfc3339(),
                 market_id,
                 token_id,
-                bid.to_string(),
-                ask.to_string(),
+                book.best_bid.to_string(),
+                book.best_ask.to_string(),
                 mid.to_string(),
-                spread.to_string(),
+                book.spread.to_string(),
+                book.bid_depth.to_string(),
+                book.ask_depth.to_string(),
             ],
         )?;
+        Ok(true)
+    }
+
+    /// Compact raw price_log rows older than `cutoff` into OHLC bars of `bar_secs`
+    /// (merged into any bar already written for the same bucket), then delete them.
+    /// Returns the number of raw rows removed.
+    pub fn compact_price_log(&self, cutoff: chrono::DateTime<chrono::Utc>, bar_secs: i64) -> Result<usize> {
+        let bar_secs = bar_secs.max(60);
+        let cutoff = cutoff.to_rfc3339();
+        let tx = self.conn.unchecked_transaction()?;
+
+        let rows: Vec<(String, String, Option<String>, String, String)> = {
+            let mut stmt = tx.prepare(
+                "SELECT market_id, timestamp, token_id, mid, spread FROM price_log
+                 WHERE timestamp < ?1 ORDER BY market_id, timestamp, id",
+            )?;
+            let mapped = stmt.query_map([&cutoff], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?))
+            })?;
+            mapped.filter_map(|r| r.ok()).collect()
+        };
+        if rows.is_empty() {
+            return Ok(0);
+        }
+
+        let mut bars: Vec<PriceBar> = Vec::new();
+        for (market_id, ts, token_id, mid, spread) in rows {
+            let Ok(ts) = chrono::DateTime::parse_from_rfc3339(&ts) else { continue };
+            let secs = ts.timestamp();
+            let bar_start = chrono::DateTime::from_timestamp(secs - secs.rem_euclid(bar_secs), 0)
+                .unwrap_or_default();
+            let mid = Decimal::from_str(&mid).unwrap_or(Decimal::ZERO);
+            let spread = Decimal::from_str(&spread).unwrap_or(Decimal::ZERO);
+
+            match bars.last_mut() {
+                Some(bar) if bar.market_id == market_id && bar.bar_start == bar_start => bar.push(mid, spread),
+                _ => bars.push(PriceBar::new(market_id, token_id, bar_start, mid, spread)),
+            }
+        }
+
+        for bar in &mut bars {
+            let bar_start = bar.bar_start.to_rfc3339();
+            let existing = tx.query_row(
+                "SELECT open, high, low, avg_spread, samples FROM price_bars
+                 WHERE market_id = ?1 AND bar_start = ?2 AND bar_secs = ?3",
+                rusqlite::params![bar.market_id, bar_start, bar_secs],
+                |row| Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, String>(3)?,
+                    row.get::<_, i64>(4)?,
+                )),
+            );
+            // Earlier compaction already covered the start of this bucket
+            if let Ok((open, high, low, avg_spread, samples)) = existing {
+                let dec = |v: &str| Decimal::from_str(v).unwrap_or(Decimal::ZERO);
+                bar.open = dec(&open);
+                bar.high = bar.high.max(dec(&high));
+                bar.low = bar.low.min(dec(&low));
+                bar.spread_sum += dec(&avg_spread) * Decimal::from(samples);
+                bar.samples += samples;
+            }
+
+            tx.execute(
+                "INSERT OR REPLACE INTO price_bars
+                 (market_id, token_id, bar_start, bar_secs, open, high, low, close, avg_spread, samples)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+                rusqlite::params![
+                    bar.market_id,
+                    bar.token_id,
+                    bar_start,
+                    bar_secs,
+                    bar.open.to_string(),
+                    bar.high.to_string(),
+                    bar.low.to_string(),
+                    bar.close.to_string(),
+                    (bar.spread_sum / Decimal::from(bar.samples)).round_dp(6).to_string(),
+                    bar.samples,
+                ],
+            )?;
+        }
+
+        let deleted = tx.execute("DELETE FROM price_log WHERE timestamp < ?1", [&cutoff])?;
+        tx.commit()?;
+        Ok(deleted)
+    }
+
+    /// Closed trades carrying a judge prediction, for fitting calibration curves
+    pub fn calibration_samples(&self) -> Result<Vec<CalibrationSample>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT specialist_desk, judge_model, judge_fair_value, judge_confidence,
+                    direction, status, exit_reason, raw_exit_price
+             FROM trades
+             WHERE status IN ('Won', 'Lost') AND judge_fair_value IS NOT NULL",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            let direction: String = row.get(4)?;
+            let status: String = row.get(5)?;
+            let exit_reason: Option<String> = row.get(6)?;
+            let payout: Option<String> = row.get(7)?;
+            Ok((
+                CalibrationSample {
+                    desk: row.get::<_, Option<String>>(0)?.unwrap_or_else(|| "GENERAL".to_string()),
+                    model: row.get::<_, Option<String>>(1)?.unwrap_or_else(|| "?".to_string()),
+                    fair_value: row.get(2)?,
+                    confidence: row.get::<_, Option<f64>>(3)?.unwrap_or(0.5),
+                    direction: direction.parse().unwrap_or(Direction::Skip),
+                    won: status == "Won",
+                    resolved: false,
+                },
+                exit_reason.as_deref() == Some("RESOLVED"),
+                payout.and_then(|p| Decimal::from_str(&p).ok()),
+            ))
+        })?;
+
+        // A RESOLVED exit only says the market left the scan: it is an outcome when the
+        // held side settled to 0 or 1, noise (closed near entry) otherwise. The raw exit
+        // price is that payout; exit_price has slippage taken off it.
+        let mut samples = Vec::new();
+        for (mut sample, resolved, payout) in rows.filter_map(|r| r.ok()) {
+            if resolved {
+                let Some(side_won) = payout.and_then(crate::forecast::settled_yes) else { continue };
+                sample.won = side_won;
+                sample.resolved = true;
+            }
+            samples.push(sample);
+        }
+        Ok(samples)
+    }
+
+    /// Replace the stored calibration curves with a fresh fit
+    pub fn save_calibration_fits(&self, fits: &[CalibrationFitRow]) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute("DELETE FROM calibration_fits", [])?;
+        let now = chrono::Utc::now().to_rfc3339();
+        for f in fits {
+            tx.execute(
+                "INSERT INTO calibration_fits (target, desk, model, samples, knots, fitted_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                rusqlite::params![f.target, f.desk, f.model, f.samples as i64, f.knots, now],
+            )?;
+        }
+        tx.commit()?;
         Ok(())
     }
 
+    /// Store one agent's P(YES) for a market
+    pub fn log_forecast(&self, market_id: &str, role: &str, desk: &str, model: &str, probability_yes: f64) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO forecasts (timestamp, market_id, role, desk, model, probability_yes)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            rusqlite::params![
+                chrono::Utc::now().to_rfc3339(),
+                market_id,
+                role,
+                desk,
+                model,
+                probability_yes.clamp(0.0, 1.0),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Markets with at least one forecast still waiting for an outcome
+    pub fn unresolved_forecast_markets(&self) -> Result<std::collections::HashSet<String>> {
+        let mut stmt = self.conn.prepare("SELECT DISTINCT market_id FROM forecasts WHERE outcome_yes IS NULL")?;
+        let ids = stmt.query_map([], |row| row.get::<_, String>(0))?;
+        Ok(ids.filter_map(|r| r.ok()).collect())
+    }
+
+    /// Settle every open forecast on `market_id`; returns how many were scored
+    pub fn resolve_forecasts(&self, market_id: &str, outcome_yes: bool) -> Result<usize> {
+        let n = self.conn.execute(
+            "UPDATE forecasts SET outcome_yes = ?2, resolved_at = ?3
+             WHERE market_id = ?1 AND outcome_yes IS NULL",
+            rusqlite::params![market_id, outcome_yes as i64, chrono::Utc::now().to_rfc3339()],
+        )?;
+        Ok(n)
+    }
+
+    /// Every forecast whose market has settled
+    pub fn resolved_forecasts(&self) -> Result<Vec<ForecastRow>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT role, desk, model, probability_yes, outcome_yes
+             FROM forecasts WHERE outcome_yes IS NOT NULL",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok(ForecastRow {
+                role: row.get(0)?,
+                desk: row.get(1)?,
+                model: row.get(2)?,
+                probability_yes: row.get(3)?,
+                outcome_yes: row.get::<_, i64>(4)? != 0,
+            })
+        })?;
+        Ok(rows.filter_map(|r| r.ok()).collect())
+    }
+
+    /// Remember the pipeline's decision on a judged market so its outcome can be looked up later.
+    /// A re-analysis overwrites the decision; an already-recorded outcome is kept.
+    pub fn track_decision(&self, market: &Market, decision: &str, lean: Direction, fair_value: f64) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO market_resolutions (market_id, question, end_date, decided_at, decision, lean, decision_price, fair_value)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+             ON CONFLICT(market_id) DO UPDATE SET
+                decided_at = excluded.decided_at, decision = excluded.decision, lean = excluded.lean,
+                decision_price = excluded.decision_price, fair_value = excluded.fair_value",
+            rusqlite::params![
+                market.id,
+                market.question,
+                market.end_date,
+                chrono::Utc::now().to_rfc3339(),
+                decision,
+                lean.to_string(),
+                market.yes_price.to_f64().unwrap_or(0.5),
+                fair_value,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Unsettled tracked markets not checked since `checked_before`, least recently checked first
+    pub fn pending_resolutions(&self, checked_before: chrono::DateTime<chrono::Utc>, limit: usize) -> Result<Vec<String>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT market_id FROM market_resolutions
+             WHERE outcome_yes IS NULL AND (last_checked IS NULL OR last_checked < ?1)
+             ORDER BY COALESCE(last_checked, '') ASC LIMIT ?2",
+        )?;
+        let ids = stmt.query_map(rusqlite::params![checked_before.to_rfc3339(), limit as i64], |row| {
+            row.get::<_, String>(0)
+        })?;
+        Ok(ids.filter_map(|r| r.ok()).collect())
+    }
+
+    /// Record a resolution check; `outcome_yes` is None while the market is still open
+    pub fn record_resolution(&self, market_id: &str, outcome_yes: Option<bool>) -> Result<()> {
+        let now = chrono::Utc::now().to_rfc3339();
+        self.conn.execute(
+            "UPDATE market_resolutions SET last_checked = ?2,
+                outcome_yes = ?3, resolved_at = CASE WHEN ?3 IS NULL THEN NULL ELSE ?2 END
+             WHERE market_id = ?1",
+            rusqlite::params![market_id, now, outcome_yes.map(|y| y as i64)],
+        )?;
+        Ok(())
+    }
+
+    /// Every tracked decision, settled or not
+    pub fn market_resolutions(&self) -> Result<Vec<MarketResolutionRow>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT decision, lean, decision_price, outcome_yes FROM market_resolutions",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            let lean: String = row.get(1)?;
+            Ok(MarketResolutionRow {
+                decision: row.get(0)?,
+                lean: lean.parse().unwrap_or(Direction::Skip),
+                decision_price: row.get(2)?,
+                outcome_yes: row.get::<_, Option<i64>>(3)?.map(|y| y != 0),
+            })
+        })?;
+        Ok(rows.filter_map(|r| r.ok()).collect())
+    }
+
+    /// Record token usage + cost of one logical LLM call (repair retries included)
+    pub fn log_llm_usage(&self, role: &str, market_id: Option<&str>, usage: &LlmUsage) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO llm_usage (timestamp, role, model, market_id, input_tokens, output_tokens, cost, parse_failures)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            rusqlite::params![
+                chrono::Utc::now().to_rfc3339(),
+                role,
+                usage.model,
+                market_id,
+                usage.input_tokens,
+                usage.output_tokens,
+                usage.cost.to_string(),
+                usage.parse_failures,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Total AI spend recorded in llm_usage (restored into the portfolio on startup)
+    pub fn total_llm_cost(&self) -> Result<Decimal> {
+        let mut stmt = self.conn.prepare("SELECT cost FROM llm_usage")?;
+        let costs = stmt.query_map([], |row| row.get::<_, String>(0))?;
+        Ok(costs
+            .filter_map(|c| c.ok())
+            .filter_map(|c| Decimal::from_str(&c).ok())
+            .sum())
+    }
+
+    /// AI spend since `since`, optionally for one model only
+    pub fn llm_spend_since(&self, since: chrono::DateTime<chrono::Utc>, model: Option<&str>) -> Result<Decimal> {
+        let mut stmt = self.conn.prepare(
+            "SELECT cost FROM llm_usage WHERE timestamp >= ?1 AND (?2 IS NULL OR model = ?2)",
+        )?;
+        let costs = stmt.query_map(rusqlite::params![since.to_rfc3339(), model], |row| row.get::<_, String>(0))?;
+        Ok(costs
+            .filter_map(|c| c.ok())
+            .filter_map(|c| Decimal::from_str(&c).ok())
+            .sum())
+    }
+
+    /// AI spend per (role, model), costliest first
+    pub fn llm_cost_breakdown(&self) -> Result<Vec<LlmCostRow>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT role, model, COUNT(*), SUM(input_tokens), SUM(output_tokens), GROUP_CONCAT(cost),
+                    SUM(parse_failures)
+             FROM llm_usage GROUP BY role, model",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            let costs: String = row.get(5)?;
+            let cost: Decimal = costs.split(',').filter_map(|c| Decimal::from_str(c).ok()).sum();
+            Ok(LlmCostRow {
+                role: row.get(0)?,
+                model: row.get(1)?,
+                calls: row.get(2)?,
+                input_tokens: row.get(3)?,
+                output_tokens: row.get(4)?,
+                cost,
+                parse_failures: row.get(6)?,
+            })
+        })?;
+
+        let mut out: Vec<_> = rows.filter_map(|r| r.ok()).collect();
+        out.sort_by_key(|r| std::cmp::Reverse(r.cost));
+        Ok(out)
+    }
+
+    /// Store the debate behind a freshly placed trade
+    pub fn save_trade_dossier(&self, trade_id: &str, market_id: &str, dossier: &TradeDossier) -> Result<()> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO trade_dossiers (trade_id, market_id, created_at, research, desk_report,
+             bull_case, bear_case, verdict, final_verdict, prompts, outcome_verdict)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            rusqlite::params![
+                trade_id,
+                market_id,
+                chrono::Utc::now().to_rfc3339(),
+                serde_json::to_string(&dossier.research)?,
+                serde_json::to_string(&dossier.desk_report)?,
+                serde_json::to_string(&dossier.bull)?,
+                serde_json::to_string(&dossier.bear)?,
+                serde_json::to_string(&dossier.verdict)?,
+                dossier.final_verdict.as_ref().map(serde_json::to_string).transpose()?,
+                serde_json::to_string(&dossier.prompts)?,
+                dossier.outcome_verdict.as_ref().map(serde_json::to_string).transpose()?,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Debate recorded for `trade_id`, if the trade was placed with dossiers enabled
+    pub fn trade_dossier(&self, trade_id: &str) -> Result<Option<TradeDossier>> {
+        let row = self
+            .conn
+            .query_row(
+                "SELECT research, desk_report, bull_case, bear_case, verdict, final_verdict, prompts,
+                        outcome_verdict
+                 FROM trade_dossiers WHERE trade_id = ?1",
+                [trade_id],
+                |row| {
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        row.get::<_, String>(1)?,
+                        row.get::<_, String>(2)?,
+                        row.get::<_, String>(3)?,
+                        row.get::<_, String>(4)?,
+                        row.get::<_, Option<String>>(5)?,
+                        row.get::<_, String>(6)?,
+                        row.get::<_, Option<String>>(7)?,
+                    ))
+                },
+            )
+            .optional()?;
+
+        let Some((research, desk, bull, bear, verdict, final_verdict, prompts, outcome_verdict)) = row else {
+            return Ok(None);
+        };
+        Ok(Some(TradeDossier {
+            research: serde_json::from_str(&research).context("trade_dossiers.research")?,
+            desk_report: serde_json::from_str(&desk).context("trade_dossiers.desk_report")?,
+            bull: serde_json::from_str(&bull).context("trade_dossiers.bull_case")?,
+            bear: serde_json::from_str(&bear).context("trade_dossiers.bear_case")?,
+            verdict: serde_json::from_str(&verdict).context("trade_dossiers.verdict")?,
+            final_verdict: final_verdict
+                .map(|v| serde_json::from_str(&v))
+                .transpose()
+                .context("trade_dossiers.final_verdict")?,
+            prompts: serde_json::from_str(&prompts).context("trade_dossiers.prompts")?,
+            outcome_verdict: outcome_verdict
+                .map(|v| serde_json::from_str(&v))
+                .transpose()
+                .context("trade_dossiers.outcome_verdict")?,
+        }))
+    }
+
+    /// Persist one cycle's candidate funnel; latency is stored as `{"STAGE": ms, ...}`
+    pub fn log_candidate_outcomes(&self, cycle_number: u64, outcomes: &[CandidateOutcome]) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        let now = chrono::Utc::now().to_rfc3339();
+        for o in outcomes {
+            let latency: serde_json::Map<String, serde_json::Value> = o
+                .latency_ms
+                .iter()
+                .map(|(stage, ms)| (stage.to_string(), (*ms).into()))
+                .collect();
+            tx.execute(
+                "INSERT INTO candidate_outcomes (timestamp, cycle_number, market_id, question, desk, stage,
+                 reject_reason, edge, confidence, bet_size, latency_ms)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+                rusqlite::params![
+                    now,
+                    cycle_number,
+                    o.market_id,
+                    o.question,
+                    o.desk,
+                    o.stage.to_string(),
+                    o.reject_reason.map(|r| r.to_string()),
+                    o.edge,
+                    o.confidence,
+                    o.bet_size.map(|b| b.to_string()),
+                    serde_json::Value::Object(latency).to_string(),
+                ],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Persist one cycle's event consistency signals
+    pub fn log_event_signals(&self, cycle_number: u64, signals: &[Signal]) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        let now = chrono::Utc::now().to_rfc3339();
+        for s in signals {
+            tx.execute(
+                "INSERT INTO event_signals (timestamp, cycle_number, event_key, relation, kind, market_id, magnitude, detail)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                rusqlite::params![
+                    now,
+                    cycle_number,
+                    s.event,
+                    s.relation.to_string(),
+                    s.kind.to_string(),
+                    s.market_id,
+                    s.magnitude,
+                    s.detail,
+                ],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Persist one arbitrage attempt, whatever its outcome
+    pub fn log_arb_execution(&self, cycle_number: u64, execution: &ArbExecution) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO arb_executions (timestamp, cycle_number, kind, event_key, legs, sets, cost, expected_profit, status)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            rusqlite::params![
+                chrono::Utc::now().to_rfc3339(),
+                cycle_number,
+                execution.kind.to_string(),
+                execution.event,
+                serde_json::to_string(&execution.legs)?,
+                execution.sets.to_string(),
+                execution.cost.to_string(),
+                execution.expected_profit.to_string(),
+                execution.status.to_string(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Record a resting order's latest state (`OPEN`, `FILLED`, `CANCELLED`)
+    pub fn save_order(&self, order: &RestingOrder, status: &str) -> Result<()> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO resting_orders
+             (order_id, placed_at, updated_at, market_id, token_id, direction, price, shares, filled, strategy, status)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            rusqlite::params![
+                order.id,
+                order.placed_at.to_rfc3339(),
+                chrono::Utc::now().to_rfc3339(),
+                order.market_id,
+                order.token_id,
+                order.direction.to_string(),
+                order.price.to_string(),
+                order.shares.to_string(),
+                order.filled.to_string(),
+                order.strategy,
+                status,
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn set_order_status(&self, order_id: &str, status: &str) -> Result<()> {
+        self.conn.execute(
+            "UPDATE resting_orders SET status = ?2, updated_at = ?3 WHERE order_id = ?1",
+            rusqlite::params![order_id, status, chrono::Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    /// Orders recorded as still working (left behind by a previous run after a crash)
+    pub fn open_order_ids(&self) -> Result<Vec<String>> {
+        let mut stmt = self.conn.prepare("SELECT order_id FROM resting_orders WHERE status = 'OPEN'")?;
+        let rows = stmt.query_map([], |row| row.get(0))?;
+        Ok(rows.filter_map(|r| r.ok()).collect())
+    }
+
+    /// Orders recorded as still working, as last saved. Fields the table does not keep
+    /// are left at their defaults: no template or time-in-force beyond GTC, and a fair
+    /// value at the limit price (zero edge on fills).
+    pub fn open_orders(&self) -> Result<Vec<RestingOrder>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT o.order_id, o.placed_at, o.market_id, o.token_id, o.direction, o.price, o.shares, o.filled,
+             o.strategy,
+             COALESCE((SELECT question FROM market_cache c WHERE c.market_id = o.market_id),
+                      (SELECT question FROM trades t WHERE t.market_id = o.market_id LIMIT 1), '')
+             FROM resting_orders o WHERE o.status = 'OPEN'",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            let dec = |idx: usize| -> rusqlite::Result<Decimal> {
+                let s: String = row.get(idx)?;
+                Ok(Decimal::from_str(&s).unwrap_or(Decimal::ZERO))
+            };
+            let placed_at: String = row.get(1)?;
+            let direction: String = row.get(4)?;
+            let direction = Direction::from_str(&direction).unwrap_or(Direction::Yes);
+            let price = dec(5)?;
+            Ok(RestingOrder {
+                id: row.get(0)?,
+                market_id: row.get(2)?,
+                question: row.get(9)?,
+                token_id: row.get(3)?,
+                direction,
+                price,
+                shares: dec(6)?,
+                filled: dec(7)?,
+                placed_at: chrono::DateTime::parse_from_rfc3339(&placed_at)
+                    .map(|d| d.with_timezone(&chrono::Utc))
+                    .unwrap_or_else(|_| chrono::Utc::now()),
+                tif: TimeInForce::Gtc,
+                expires_at: None,
+                queue_ahead: None,
+                level_size: Decimal::ZERO,
+                fair_value: if direction == Direction::No { Decimal::ONE - price } else { price },
+                trade_mode: None,
+                strategy: row.get(8)?,
+                category: None,
+                template: None,
+            })
+        })?;
+        Ok(rows.filter_map(|r| r.ok()).collect())
+    }
+
+    /// Latest cached judge fair value (P(YES)) for a market, if analyzed within `hours`
+    pub fn cached_fair_value(&self, market_id: &str, hours: i64) -> Option<Decimal> {
+        let cutoff = (chrono::Utc::now() - chrono::Duration::hours(hours)).to_rfc3339();
+        self.conn
+            .query_row(
+                "SELECT fair_value FROM market_cache WHERE market_id = ?1 AND last_analyzed > ?2",
+                rusqlite::params![market_id, cutoff],
+                |row| row.get::<_, Option<String>>(0),
+            )
+            .ok()
+            .flatten()
+            .and_then(|v| Decimal::from_str(&v).ok())
+    }
+
     /// Log a full cycle with enhanced metrics
     pub fn log_cycle(
         &self,
@@ -685,6 +1752,55 @@ impl StateStore {
     }
 }
 
+/// Migrate order-book depth columns for existing price_log tables
+fn migrate_price_log_columns(conn: &Connection) {
+    for col in ["bid_depth", "ask_depth"] {
+        let exists = conn
+            .prepare(&format!("SELECT {col} FROM price_log LIMIT 0"))
+            .is_ok();
+        if !exists {
+            let sql = format!("ALTER TABLE price_log ADD COLUMN {col} TEXT DEFAULT '0'");
+            conn.execute_batch(&sql).ok();
+        }
+    }
+}
+
+/// Migrate structured-output failure counts for existing llm_usage tables
+fn migrate_llm_usage_columns(conn: &Connection) {
+    let exists = conn.prepare("SELECT parse_failures FROM llm_usage LIMIT 0").is_ok();
+    if !exists {
+        conn.execute_batch("ALTER TABLE llm_usage ADD COLUMN parse_failures INTEGER NOT NULL DEFAULT 0")
+            .ok();
+    }
+}
+
+/// Migrate AI budget columns for existing agent_status tables
+fn migrate_budget_columns(conn: &Connection) {
+    for (col, typ) in [("budget_level", "TEXT DEFAULT 'NORMAL'"), ("budget_details", "TEXT")] {
+        let exists = conn
+            .prepare(&format!("SELECT {col} FROM agent_status LIMIT 0"))
+            .is_ok();
+        if !exists {
+            let sql = format!("ALTER TABLE agent_status ADD COLUMN {col} {typ}");
+            conn.execute_batch(&sql).ok();
+        }
+    }
+}
+
+/// Migrate the HTTP health column for existing agent_status tables
+fn migrate_http_health_column(conn: &Connection) {
+    if conn.prepare("SELECT http_health FROM agent_status LIMIT 0").is_err() {
+        conn.execute_batch("ALTER TABLE agent_status ADD COLUMN http_health TEXT").ok();
+    }
+}
+
+/// Migrate the categorical-market verdict column for existing trade_dossiers tables
+fn migrate_dossier_outcome_column(conn: &Connection) {
+    if conn.prepare("SELECT outcome_verdict FROM trade_dossiers LIMIT 0").is_err() {
+        conn.execute_batch("ALTER TABLE trade_dossiers ADD COLUMN outcome_verdict TEXT").ok();
+    }
+}
+
 /// Migrate simulation columns for existing databases
 fn migrate_simulation_columns(conn: &Connection) {
     let columns = [
@@ -696,6 +1812,10 @@ fn migrate_simulation_columns(conn: &Connection) {
         ("exit_slippage", "TEXT DEFAULT '0'"),
         ("platform_fee", "TEXT DEFAULT '0'"),
         ("maker_taker_fee", "TEXT DEFAULT '0'"),
+        ("calibrated_fair_value", "REAL"),
+        ("calibrated_confidence", "REAL"),
+        ("outcome", "TEXT"),
+        ("strategy", "TEXT"),
     ];
 
     for (col, typ) in &columns {
@@ -709,3 +1829,40 @@ fn migrate_simulation_columns(conn: &Connection) {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::paper::{Portfolio, SimConfig};
+    use rust_decimal_macros::dec;
+
+    /// A judged YES trade closed at resolution, as the ledger books it
+    fn resolved_yes(store: &StateStore, raw_exit: Decimal, exit: Decimal, won: bool) {
+        let portfolio = Portfolio::new(dec!(1000), SimConfig::disabled());
+        let mut trade = portfolio
+            .execute_trade("m1", "Will it?", Direction::Yes, dec!(0.6), dec!(0.7), dec!(0.1), dec!(50), dec!(10000))
+            .unwrap();
+        trade.judge_fair_value = Some(0.7);
+        trade.judge_confidence = Some(0.8);
+        trade.status = if won { TradeStatus::Won } else { TradeStatus::Lost };
+        trade.exit_reason = Some(ExitReason::MarketResolved);
+        trade.raw_exit_price = Some(raw_exit);
+        trade.exit_price = Some(exit);
+        store.save_trade(&trade).unwrap();
+    }
+
+    #[test]
+    fn calibration_reads_the_payout_not_the_slipped_exit() {
+        let store = StateStore::new(":memory:").unwrap();
+        // Winner paid 1.0 but booked at 0.98 after exit slippage
+        resolved_yes(&store, dec!(1), dec!(0.98), true);
+        resolved_yes(&store, dec!(0), dec!(0), false);
+        // Left the scan near entry: not an outcome
+        resolved_yes(&store, dec!(0.55), dec!(0.54), false);
+
+        let samples = store.calibration_samples().unwrap();
+        assert_eq!(samples.len(), 2);
+        assert!(samples.iter().all(|s| s.resolved));
+        assert_eq!(samples.iter().filter(|s| s.won).count(), 1);
+    }
+}
@@ -0,0 +1,175 @@
+use crate::db::{ForecastRow, StateStore};
+use crate::types::{Direction, ExitReason, Trade};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::collections::{BTreeMap, HashMap};
+use tracing::{info, warn};
+
+/// Wildcard scope: any desk / any model
+pub const ANY: &str = "*";
+
+/// Equal-width reliability-diagram buckets over [0, 1]
+pub const BUCKETS: usize = 10;
+
+/// Forecasts are clamped to [EPS, 1 - EPS] so one confident miss can't make log-loss infinite
+const EPS: f64 = 1e-4;
+
+/// Store one agent's P(YES); a failed write is logged, never fatal to the cycle
+pub fn record(store: &StateStore, market_id: &str, role: &str, desk: &str, model: &str, probability_yes: f64) {
+    if let Err(e) = store.log_forecast(market_id, role, desk, model, probability_yes) {
+        warn!("forecast write failed for {role}: {e}");
+    }
+}
+
+/// A YES price this close to 0 or 1 means the market has settled
+pub fn settled_yes(yes_price: Decimal) -> Option<bool> {
+    if yes_price >= dec!(0.99) {
+        Some(true)
+    } else if yes_price <= dec!(0.01) {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+/// Settle forecasts on markets Gamma reports closed, from their winning outcome
+/// (market id -> label, as `ResolutionTracker::settled_winners` gives it).
+/// Only YES/NO markets carry forecasts.
+pub fn resolve_from_winners(store: &StateStore, winners: &HashMap<String, String>) -> usize {
+    let pending = match store.unresolved_forecast_markets() {
+        Ok(p) if !p.is_empty() => p,
+        Ok(_) => return 0,
+        Err(e) => {
+            warn!("forecast lookup failed: {e}");
+            return 0;
+        }
+    };
+
+    winners
+        .iter()
+        .filter(|(id, _)| pending.contains(*id))
+        .filter_map(|(id, winner)| match winner.to_ascii_lowercase().as_str() {
+            "yes" => Some((id, true)),
+            "no" => Some((id, false)),
+            _ => None,
+        })
+        .map(|(id, yes)| store.resolve_forecasts(id, yes).unwrap_or(0))
+        .sum()
+}
+
+/// Settle forecasts from trades closed at resolution. The raw exit price is the
+/// held side's payout, so it gives the outcome only when it's at 0 or 1.
+pub fn resolve_from_trades(store: &StateStore, trades: &[Trade]) -> usize {
+    trades
+        .iter()
+        .filter(|t| t.exit_reason == Some(ExitReason::MarketResolved))
+        .filter_map(|t| {
+            let side_won = settled_yes(t.raw_exit_price?)?;
+            match t.direction {
+                Direction::Yes => Some((t, side_won)),
+                Direction::No => Some((t, !side_won)),
+                Direction::Skip => None,
+            }
+        })
+        .map(|(t, yes)| store.resolve_forecasts(&t.market_id, yes).unwrap_or(0))
+        .sum()
+}
+
+/// Settle what we can from this round's closed markets and closed trades
+pub fn resolve(store: &StateStore, winners: &HashMap<String, String>, closed: &[Trade]) {
+    let scored = resolve_from_winners(store, winners) + resolve_from_trades(store, closed);
+    if scored > 0 {
+        info!("Forecasts: scored {scored} agent forecasts on settled markets");
+    }
+}
+
+/// One reliability-diagram bucket: how often YES happened when forecasts fell in [lower, upper)
+#[derive(Debug, Clone, Default)]
+pub struct ReliabilityBucket {
+    pub lower: f64,
+    pub upper: f64,
+    pub count: usize,
+    pub mean_forecast: f64,
+    pub observed_yes: f64,
+}
+
+/// Proper scores for one (role, desk, model) scope; lower is better for both
+#[derive(Debug, Clone)]
+pub struct ForecastScore {
+    pub role: String,
+    pub desk: String,
+    pub model: String,
+    pub count: usize,
+    pub brier: f64,
+    pub log_loss: f64,
+    pub buckets: Vec<ReliabilityBucket>,
+}
+
+#[derive(Default)]
+struct Accumulator {
+    count: usize,
+    brier: f64,
+    log_loss: f64,
+    /// (count, sum of forecasts, sum of outcomes) per bucket
+    buckets: [(usize, f64, f64); BUCKETS],
+}
+
+impl Accumulator {
+    fn push(&mut self, p: f64, yes: bool) {
+        let y = if yes { 1.0 } else { 0.0 };
+        let q = p.clamp(EPS, 1.0 - EPS);
+        self.count += 1;
+        self.brier += (p - y).powi(2);
+        self.log_loss -= y * q.ln() + (1.0 - y) * (1.0 - q).ln();
+
+        let b = &mut self.buckets[((p * BUCKETS as f64) as usize).min(BUCKETS - 1)];
+        b.0 += 1;
+        b.1 += p;
+        b.2 += y;
+    }
+
+    fn finish(self, role: String, desk: String, model: String) -> ForecastScore {
+        let n = self.count as f64;
+        let width = 1.0 / BUCKETS as f64;
+        let buckets = self
+            .buckets
+            .iter()
+            .enumerate()
+            .map(|(i, &(count, sum_p, sum_y))| ReliabilityBucket {
+                lower: i as f64 * width,
+                upper: (i + 1) as f64 * width,
+                count,
+                mean_forecast: if count > 0 { sum_p / count as f64 } else { 0.0 },
+                observed_yes: if count > 0 { sum_y / count as f64 } else { 0.0 },
+            })
+            .collect();
+
+        ForecastScore {
+            role,
+            desk,
+            model,
+            count: self.count,
+            brier: self.brier / n,
+            log_loss: self.log_loss / n,
+            buckets,
+        }
+    }
+}
+
+/// Score settled forecasts per (role, desk, model), plus a per-role rollup
+/// with desk and model set to `ANY`. Sorted by role, rollup first.
+pub fn score(rows: &[ForecastRow]) -> Vec<ForecastScore> {
+    let mut acc: BTreeMap<(String, String, String), Accumulator> = BTreeMap::new();
+
+    for r in rows {
+        for (desk, model) in [(r.desk.as_str(), r.model.as_str()), (ANY, ANY)] {
+            acc.entry((r.role.clone(), desk.to_string(), model.to_string()))
+                .or_default()
+                .push(r.probability_yes, r.outcome_yes);
+        }
+    }
+
+    acc.into_iter()
+        .map(|((role, desk, model), a)| a.finish(role, desk, model))
+        .collect()
+}
@@ -0,0 +1,387 @@
+use crate::config::Config;
+use crate::ratelimit::RateLimiter;
+use anyhow::Result;
+use rand::Rng;
+use reqwest::{IntoUrl, Method, RequestBuilder, Response, StatusCode};
+use serde::Serialize;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+/// Retry and circuit-breaker settings, shared by every host
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    /// Extra attempts after the first on 429 / 5xx / transport errors
+    pub max_retries: u32,
+    pub backoff_base: Duration,
+    pub backoff_max: Duration,
+    /// Consecutive failed requests before the breaker opens
+    pub breaker_threshold: u32,
+    /// How long an open breaker fails fast before letting one probe through
+    pub breaker_cooldown: Duration,
+}
+
+impl RetryPolicy {
+    pub fn from_config(cfg: &Config) -> Self {
+        Self {
+            max_retries: cfg.http_max_retries,
+            backoff_base: Duration::from_millis(cfg.http_backoff_base_ms),
+            backoff_max: Duration::from_secs(cfg.http_backoff_max_secs),
+            breaker_threshold: cfg.http_breaker_threshold.max(1),
+            breaker_cooldown: Duration::from_secs(cfg.http_breaker_cooldown_secs),
+        }
+    }
+
+    /// Exponential backoff with up to 25% jitter, capped at `backoff_max`
+    fn backoff(&self, attempt: u32) -> Duration {
+        let exp = self.backoff_base.saturating_mul(2u32.saturating_pow(attempt));
+        let jitter = exp.mul_f64(rand::thread_rng().gen_range(0.0..0.25));
+        (exp + jitter).min(self.backoff_max)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum BreakerState {
+    /// Requests flow normally
+    Closed,
+    /// Sustained failures: requests fail immediately until the cooldown ends
+    Open,
+    /// Cooldown over: one probe request decides whether to close or re-open
+    HalfOpen,
+}
+
+impl std::fmt::Display for BreakerState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BreakerState::Closed => write!(f, "CLOSED"),
+            BreakerState::Open => write!(f, "OPEN"),
+            BreakerState::HalfOpen => write!(f, "HALF_OPEN"),
+        }
+    }
+}
+
+struct Breaker {
+    state: BreakerState,
+    consecutive_failures: u32,
+    opened_at: Option<Instant>,
+    probe_in_flight: bool,
+    last_error: Option<String>,
+    requests: u64,
+    failures: u64,
+}
+
+/// Snapshot of one host's breaker, as written to `agent_status.http_health`
+#[derive(Debug, Clone, Serialize)]
+pub struct HostHealth {
+    pub host: String,
+    pub state: BreakerState,
+    pub consecutive_failures: u32,
+    /// Seconds until an open breaker lets a probe through
+    pub retry_in_secs: Option<u64>,
+    pub last_error: Option<String>,
+    pub requests: u64,
+    pub failures: u64,
+}
+
+/// reqwest client for one upstream host: shared token-bucket rate limit,
+/// retries with exponential backoff on 429/5xx (honouring Retry-After), and a
+/// circuit breaker that fails fast while the host is down. Clones share all three.
+#[derive(Clone)]
+pub struct HttpClient {
+    host: String,
+    client: reqwest::Client,
+    limiter: RateLimiter,
+    policy: RetryPolicy,
+    breaker: Arc<Mutex<Breaker>>,
+}
+
+impl HttpClient {
+    pub fn new(host: &str, timeout_secs: u64, limiter: RateLimiter, policy: RetryPolicy) -> Self {
+        Self {
+            host: host.to_string(),
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(timeout_secs))
+                .build()
+                .expect("HTTP client"),
+            limiter,
+            policy,
+            breaker: Arc::new(Mutex::new(Breaker {
+                state: BreakerState::Closed,
+                consecutive_failures: 0,
+                opened_at: None,
+                probe_in_flight: false,
+                last_error: None,
+                requests: 0,
+                failures: 0,
+            })),
+        }
+    }
+
+    pub fn host(&self) -> &str {
+        &self.host
+    }
+
+    pub fn get(&self, url: impl IntoUrl) -> RequestBuilder {
+        self.client.get(url)
+    }
+
+    pub fn post(&self, url: impl IntoUrl) -> RequestBuilder {
+        self.client.post(url)
+    }
+
+    pub fn request(&self, method: Method, url: impl IntoUrl) -> RequestBuilder {
+        self.client.request(method, url)
+    }
+
+    /// Send with retries. Only for requests that are safe to repeat.
+    /// A 429/5xx that survives every retry is returned as the response, so
+    /// callers keep reading the error body themselves.
+    pub async fn send(&self, req: RequestBuilder) -> Result<Response> {
+        self.execute(req, self.policy.max_retries).await
+    }
+
+    /// Send exactly once (e.g. order placement); still rate limited and breaker-guarded
+    pub async fn send_once(&self, req: RequestBuilder) -> Result<Response> {
+        self.execute(req, 0).await
+    }
+
+    async fn execute(&self, req: RequestBuilder, max_retries: u32) -> Result<Response> {
+        let _probe = self.admit()?;
+
+        let mut pending = Some(req);
+        let mut attempt = 0;
+        loop {
+            // Keep a copy for the next attempt; bodies that can't be cloned are sent once
+            let (current, retry) = match pending.take() {
+                Some(r) if attempt < max_retries => match r.try_clone() {
+                    Some(copy) => (r, Some(copy)),
+                    None => (r, None),
+                },
+                Some(r) => (r, None),
+                None => unreachable!("request consumed without retry copy"),
+            };
+
+            self.limiter.acquire().await;
+            let result = current.send().await;
+
+            let retry_after = match &result {
+                Ok(resp) if !is_retryable(resp.status()) => {
+                    self.record_success();
+                    return result.map_err(Into::into);
+                }
+                Ok(resp) => retry_after(resp),
+                Err(_) => None,
+            };
+
+            let Some(next) = retry else {
+                let error = match &result {
+                    Ok(resp) => resp.status().to_string(),
+                    Err(e) => e.to_string(),
+                };
+                self.record_failure(error);
+                return result.map_err(|e| anyhow::anyhow!("{} request failed: {e}", self.host));
+            };
+
+            let wait = retry_after.map_or_else(|| self.policy.backoff(attempt), |d| d.min(self.policy.backoff_max));
+            match &result {
+                Ok(resp) => warn!("{} returned {}, retry {}/{} in {:.1}s",
+                    self.host, resp.status(), attempt + 1, max_retries, wait.as_secs_f64()),
+                Err(e) => warn!("{} request error ({e}), retry {}/{} in {:.1}s",
+                    self.host, attempt + 1, max_retries, wait.as_secs_f64()),
+            }
+            tokio::time::sleep(wait).await;
+            pending = Some(next);
+            attempt += 1;
+        }
+    }
+
+    /// Gate a request on the breaker: fail fast while open, allow a single probe once cooled down.
+    /// The returned guard releases the probe slot even if the request future is dropped.
+    fn admit(&self) -> Result<ProbeGuard<'_>> {
+        let mut b = self.breaker.lock().unwrap();
+        b.requests += 1;
+        match b.state {
+            BreakerState::Closed => Ok(ProbeGuard { breaker: None }),
+            BreakerState::Open => {
+                let elapsed = b.opened_at.map_or(Duration::MAX, |t| t.elapsed());
+                if elapsed < self.policy.breaker_cooldown {
+                    let wait = (self.policy.breaker_cooldown - elapsed).as_secs();
+                    anyhow::bail!("{} circuit open (retry in {wait}s): {}",
+                        self.host, b.last_error.as_deref().unwrap_or("unavailable"));
+                }
+                info!("{} circuit half-open, probing", self.host);
+                b.state = BreakerState::HalfOpen;
+                b.probe_in_flight = true;
+                Ok(ProbeGuard { breaker: Some(&self.breaker) })
+            }
+            BreakerState::HalfOpen if b.probe_in_flight => {
+                anyhow::bail!("{} circuit half-open, probe in flight", self.host)
+            }
+            BreakerState::HalfOpen => {
+                b.probe_in_flight = true;
+                Ok(ProbeGuard { breaker: Some(&self.breaker) })
+            }
+        }
+    }
+
+    fn record_success(&self) {
+        let mut b = self.breaker.lock().unwrap();
+        if b.state != BreakerState::Closed {
+            info!("{} circuit closed, host recovered", self.host);
+        }
+        b.state = BreakerState::Closed;
+        b.consecutive_failures = 0;
+        b.opened_at = None;
+        b.probe_in_flight = false;
+    }
+
+    fn record_failure(&self, error: String) {
+        let mut b = self.breaker.lock().unwrap();
+        b.failures += 1;
+        b.consecutive_failures += 1;
+        b.last_error = Some(error);
+        b.probe_in_flight = false;
+
+        let trip = b.state == BreakerState::HalfOpen
+            || (b.state == BreakerState::Closed && b.consecutive_failures >= self.policy.breaker_threshold);
+        if trip {
+            warn!("{} circuit OPEN after {} consecutive failures (cooldown {}s)",
+                self.host, b.consecutive_failures, self.policy.breaker_cooldown.as_secs());
+            b.state = BreakerState::Open;
+            b.opened_at = Some(Instant::now());
+        }
+    }
+
+    pub fn health(&self) -> HostHealth {
+        let b = self.breaker.lock().unwrap();
+        let retry_in_secs = match (b.state, b.opened_at) {
+            (BreakerState::Open, Some(t)) => Some(self.policy.breaker_cooldown.saturating_sub(t.elapsed()).as_secs()),
+            _ => None,
+        };
+        HostHealth {
+            host: self.host.clone(),
+            state: b.state,
+            consecutive_failures: b.consecutive_failures,
+            retry_in_secs,
+            last_error: b.last_error.clone(),
+            requests: b.requests,
+            failures: b.failures,
+        }
+    }
+}
+
+/// Holds the half-open probe slot for one request. Dropping it frees the slot, so a
+/// probe cancelled mid-flight (e.g. by a caller's timeout) doesn't wedge the breaker
+/// in HALF_OPEN; a completed probe has already closed or re-opened it by then.
+struct ProbeGuard<'a> {
+    breaker: Option<&'a Mutex<Breaker>>,
+}
+
+impl Drop for ProbeGuard<'_> {
+    fn drop(&mut self) {
+        if let Some(breaker) = self.breaker {
+            breaker.lock().unwrap().probe_in_flight = false;
+        }
+    }
+}
+
+fn is_retryable(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+/// Retry-After in delta-seconds (the HTTP-date form falls back to backoff)
+fn retry_after(resp: &Response) -> Option<Duration> {
+    resp.headers()
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .map(Duration::from_secs)
+}
+
+/// One client per upstream host, built once at startup and shared by every caller
+#[derive(Clone)]
+pub struct Hosts {
+    pub gamma: HttpClient,
+    pub clob: HttpClient,
+    /// Team LLM provider selected by LLM_PROVIDER
+    pub llm: HttpClient,
+    /// Final validator; the same client as `llm` when LLM_PROVIDER=claude
+    pub claude: HttpClient,
+    pub telegram: HttpClient,
+    /// Enricher sources (CoinGecko, news) are paced here, outside the Enricher
+    pub coingecko: RateLimiter,
+    /// Per-request ceiling for data sources (enrichment, order books)
+    pub data_timeout: Duration,
+}
+
+impl Hosts {
+    pub fn from_config(cfg: &Config) -> Self {
+        let policy = RetryPolicy::from_config(cfg);
+        let llm = HttpClient::new(&cfg.llm_provider, cfg.llm_timeout_secs,
+            RateLimiter::new(cfg.rate_limit_llm_per_min), policy);
+        let claude = if cfg.llm_provider == "claude" {
+            llm.clone()
+        } else {
+            HttpClient::new("claude", cfg.llm_timeout_secs, RateLimiter::new(cfg.rate_limit_llm_per_min), policy)
+        };
+
+        Self {
+            gamma: HttpClient::new("gamma", cfg.data_timeout_secs,
+                RateLimiter::new(cfg.rate_limit_gamma_per_min), policy),
+            clob: HttpClient::new("clob", cfg.data_timeout_secs,
+                RateLimiter::new(cfg.rate_limit_clob_per_min), policy),
+            llm,
+            claude,
+            telegram: HttpClient::new("telegram", 10,
+                RateLimiter::new(cfg.rate_limit_telegram_per_min), policy),
+            coingecko: RateLimiter::new(cfg.rate_limit_coingecko_per_min),
+            data_timeout: Duration::from_secs(cfg.data_timeout_secs),
+        }
+    }
+
+    /// Breaker state of every host, deduplicated when two roles share a client
+    pub fn health(&self) -> Vec<HostHealth> {
+        let mut out: Vec<HostHealth> = Vec::new();
+        for client in [&self.gamma, &self.clob, &self.llm, &self.claude, &self.telegram] {
+            if !out.iter().any(|h| h.host == client.host()) {
+                out.push(client.health());
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn client() -> HttpClient {
+        let policy = RetryPolicy {
+            max_retries: 0,
+            backoff_base: Duration::ZERO,
+            backoff_max: Duration::ZERO,
+            breaker_threshold: 1,
+            breaker_cooldown: Duration::ZERO,
+        };
+        HttpClient::new("test", 1, RateLimiter::new(0), policy)
+    }
+
+    #[test]
+    fn dropped_probe_frees_half_open_slot() {
+        let http = client();
+        http.record_failure("down".into());
+        assert_eq!(http.health().state, BreakerState::Open);
+
+        let probe = http.admit().unwrap();
+        assert_eq!(http.health().state, BreakerState::HalfOpen);
+        assert!(http.admit().is_err(), "second request while the probe is out");
+
+        // Probe cancelled before it recorded an outcome
+        drop(probe);
+        assert!(http.admit().is_ok());
+    }
+}
@@ -1,24 +1,79 @@
+use super::order::{SignedOrder, POLYGON_CHAIN_ID};
+use crate::http::HttpClient;
 use anyhow::{Context, Result};
+use base64::engine::general_purpose::URL_SAFE;
+use base64::Engine;
+use ethers::abi::{encode, Token};
+use ethers::signers::{LocalWallet, Signer};
+use ethers::types::{H256, U256};
+use ethers::utils::keccak256;
+use hmac::{Hmac, Mac};
+use reqwest::{Method, RequestBuilder};
 use rust_decimal::Decimal;
 use serde::Deserialize;
+use sha2::Sha256;
 use std::str::FromStr;
-use tracing::{debug, warn};
+use tracing::{debug, info, warn};
+
+const CLOB_AUTH_DOMAIN: &str = "ClobAuthDomain";
+const CLOB_AUTH_MESSAGE: &str = "This message attests that I control the given wallet";
+const CLOB_AUTH_TYPE: &str =
+    "ClobAuth(address address,string timestamp,uint256 nonce,string message)";
 
 /// CLOB (Central Limit Order Book) client for Polymarket
 pub struct ClobClient {
     base_url: String,
-    client: reqwest::Client,
+    http: HttpClient,
+    auth: Option<L2Auth>,
+}
+
+/// L2 API credentials (POLY_API_KEY / POLY_SECRET / POLY_PASSPHRASE, or derived via L1)
+#[derive(Debug, Clone, Deserialize)]
+pub struct ApiCreds {
+    #[serde(rename = "apiKey")]
+    pub api_key: String,
+    pub secret: String,
+    pub passphrase: String,
+}
+
+impl ApiCreds {
+    pub fn new(api_key: &str, secret: &str, passphrase: &str) -> Self {
+        Self {
+            api_key: api_key.to_string(),
+            secret: secret.to_string(),
+            passphrase: passphrase.to_string(),
+        }
+    }
+
+    pub fn is_complete(&self) -> bool {
+        !self.api_key.is_empty() && !self.secret.is_empty() && !self.passphrase.is_empty()
+    }
+}
+
+struct L2Auth {
+    address: String,
+    creds: ApiCreds,
 }
 
 #[derive(Debug, Clone)]
 pub struct OrderBookSummary {
-    #[allow(dead_code)]
     pub best_bid: Decimal,
-    #[allow(dead_code)]
     pub best_ask: Decimal,
     pub spread: Decimal,
     pub bid_depth: Decimal,
     pub ask_depth: Decimal,
+    /// Shares resting at the best bid
+    pub best_bid_size: Decimal,
+    /// Shares resting at the best ask
+    pub best_ask_size: Decimal,
+}
+
+/// Exchange view of one order
+#[derive(Debug, Clone)]
+pub struct OrderState {
+    pub status: String,
+    /// Shares matched so far
+    pub size_matched: Decimal,
 }
 
 #[derive(Debug, Deserialize)]
@@ -33,60 +88,153 @@ struct ClobLevel {
     size: String,
 }
 
-#[allow(dead_code)]
 #[derive(Debug, Deserialize)]
 struct ClobPriceResponse {
     price: Option<String>,
 }
 
 impl ClobClient {
-    pub fn new(base_url: &str) -> Self {
+    pub fn new(base_url: &str, http: HttpClient) -> Self {
         Self {
             base_url: base_url.to_string(),
-            client: reqwest::Client::builder()
-                .timeout(std::time::Duration::from_secs(15))
-                .build()
-                .expect("HTTP client"),
+            http,
+            auth: None,
+        }
+    }
+
+    /// Attach L2 credentials; `address` is the signing wallet (POLY_ADDRESS)
+    pub fn with_credentials(mut self, address: &str, creds: ApiCreds) -> Self {
+        self.auth = Some(L2Auth {
+            address: address.to_string(),
+            creds,
+        });
+        self
+    }
+
+    /// Build a request to a private endpoint with L2 HMAC headers.
+    /// `body` must be the exact string sent on the wire — it is part of the signed message.
+    fn private_request(&self, method: Method, path: &str, body: Option<String>) -> Result<RequestBuilder> {
+        let auth = self
+            .auth
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("CLOB private endpoint {path} requires API credentials"))?;
+
+        let timestamp = chrono::Utc::now().timestamp().to_string();
+        let signature = build_hmac_signature(
+            &auth.creds.secret,
+            &timestamp,
+            method.as_str(),
+            path,
+            body.as_deref(),
+        )?;
+
+        let mut req = self
+            .http
+            .request(method, format!("{}{}", self.base_url, path))
+            .header("Accept", "application/json")
+            .header("POLY_ADDRESS", &auth.address)
+            .header("POLY_SIGNATURE", signature)
+            .header("POLY_TIMESTAMP", timestamp)
+            .header("POLY_API_KEY", &auth.creds.api_key)
+            .header("POLY_PASSPHRASE", &auth.creds.passphrase);
+
+        if let Some(body) = body {
+            req = req.header("Content-Type", "application/json").body(body);
+        }
+
+        Ok(req)
+    }
+
+    /// Create a new API key with an L1 (wallet EIP-712) signature
+    pub async fn create_api_key(&self, wallet: &LocalWallet, nonce: u64) -> Result<ApiCreds> {
+        let resp = self
+            .http
+            .send_once(self.l1_request(Method::POST, "/auth/api-key", wallet, nonce)?)
+            .await
+            .context("CLOB create API key")?;
+
+        if !resp.status().is_success() {
+            anyhow::bail!("CLOB create API key failed: {}", resp.status());
+        }
+
+        resp.json().await.context("Parse CLOB API key")
+    }
+
+    /// Derive the existing API key for this wallet + nonce (L1)
+    pub async fn derive_api_key(&self, wallet: &LocalWallet, nonce: u64) -> Result<ApiCreds> {
+        let resp = self
+            .http
+            .send(self.l1_request(Method::GET, "/auth/derive-api-key", wallet, nonce)?)
+            .await
+            .context("CLOB derive API key")?;
+
+        if !resp.status().is_success() {
+            anyhow::bail!("CLOB derive API key failed: {}", resp.status());
+        }
+
+        resp.json().await.context("Parse CLOB API key")
+    }
+
+    /// Create an API key, falling back to deriving it when one already exists for the nonce
+    pub async fn create_or_derive_api_key(&self, wallet: &LocalWallet, nonce: u64) -> Result<ApiCreds> {
+        match self.create_api_key(wallet, nonce).await {
+            Ok(creds) => {
+                // Key only, redacted: the secret and passphrase never reach the log
+                info!("Created new CLOB API key {}…", creds.api_key.get(..8).unwrap_or_default());
+                Ok(creds)
+            }
+            Err(e) => {
+                debug!("Create API key failed ({e}), deriving existing key");
+                self.derive_api_key(wallet, nonce).await
+            }
         }
     }
 
+    fn l1_request(&self, method: Method, path: &str, wallet: &LocalWallet, nonce: u64) -> Result<RequestBuilder> {
+        let timestamp = chrono::Utc::now().timestamp().to_string();
+        let signature = clob_auth_signature(wallet, POLYGON_CHAIN_ID, &timestamp, nonce)?;
+
+        Ok(self
+            .http
+            .request(method, format!("{}{}", self.base_url, path))
+            .header("Accept", "application/json")
+            .header("POLY_ADDRESS", format!("{:?}", wallet.address()))
+            .header("POLY_SIGNATURE", signature)
+            .header("POLY_TIMESTAMP", timestamp)
+            .header("POLY_NONCE", nonce.to_string()))
+    }
+
     /// Fetch order book for a token
     pub async fn get_order_book(&self, token_id: &str) -> Result<OrderBookSummary> {
         let url = format!("{}/book?token_id={}", self.base_url, token_id);
 
         let resp: ClobOrderBook = self
-            .client
-            .get(&url)
-            .header("Accept", "application/json")
-            .send()
+            .http
+            .send(self.http.get(&url).header("Accept", "application/json"))
             .await
             .context("CLOB order book request")?
             .json()
             .await
             .context("Parse CLOB order book")?;
 
-        let bids = resp.bids.unwrap_or_default();
-        let asks = resp.asks.unwrap_or_default();
-
-        let best_bid = bids
-            .first()
-            .and_then(|l| Decimal::from_str(&l.price).ok())
-            .unwrap_or(Decimal::ZERO);
-
-        let best_ask = asks
-            .first()
-            .and_then(|l| Decimal::from_str(&l.price).ok())
-            .unwrap_or(Decimal::ONE);
-
-        let bid_depth: Decimal = bids
-            .iter()
-            .filter_map(|l| Decimal::from_str(&l.size).ok())
-            .sum();
-
-        let ask_depth: Decimal = asks
-            .iter()
-            .filter_map(|l| Decimal::from_str(&l.size).ok())
-            .sum();
+        let parse = |levels: Option<Vec<ClobLevel>>| -> Vec<(Decimal, Decimal)> {
+            levels
+                .unwrap_or_default()
+                .iter()
+                .filter_map(|l| Some((Decimal::from_str(&l.price).ok()?, Decimal::from_str(&l.size).ok()?)))
+                .collect()
+        };
+        let bids = parse(resp.bids);
+        let asks = parse(resp.asks);
+
+        // Best levels by price, whatever order the book is listed in
+        let best_bid = bids.iter().map(|l| l.0).max().unwrap_or(Decimal::ZERO);
+        let best_ask = asks.iter().map(|l| l.0).min().unwrap_or(Decimal::ONE);
+        let best_bid_size: Decimal = bids.iter().filter(|l| l.0 == best_bid).map(|l| l.1).sum();
+        let best_ask_size: Decimal = asks.iter().filter(|l| l.0 == best_ask).map(|l| l.1).sum();
+
+        let bid_depth: Decimal = bids.iter().map(|l| l.1).sum();
+        let ask_depth: Decimal = asks.iter().map(|l| l.1).sum();
 
         let spread = best_ask - best_bid;
 
@@ -100,19 +248,18 @@ impl ClobClient {
             spread,
             bid_depth,
             ask_depth,
+            best_bid_size,
+            best_ask_size,
         })
     }
 
     /// Get mid-price for a token
-    #[allow(dead_code)]
     pub async fn get_price(&self, token_id: &str) -> Result<Decimal> {
         let url = format!("{}/price?token_id={}", self.base_url, token_id);
 
         let resp = self
-            .client
-            .get(&url)
-            .header("Accept", "application/json")
-            .send()
+            .http
+            .send(self.http.get(&url).header("Accept", "application/json"))
             .await;
 
         match resp {
@@ -133,33 +280,39 @@ impl ClobClient {
         }
     }
 
-    /// Place a limit order (returns order ID)
-    #[allow(dead_code)]
-    pub async fn place_order(
-        &self,
-        token_id: &str,
-        side: &str,
-        price: Decimal,
-        size: Decimal,
-        _api_key: &str,
-    ) -> Result<String> {
-        let url = format!("{}/order", self.base_url);
-
-        let body = serde_json::json!({
-            "tokenID": token_id,
-            "side": side,
-            "price": price.to_string(),
-            "size": size.to_string(),
-            "type": "GTC",
-        });
+    /// Whether a token trades on the neg-risk exchange (affects the EIP-712 domain)
+    pub async fn get_neg_risk(&self, token_id: &str) -> Result<bool> {
+        let url = format!("{}/neg-risk?token_id={}", self.base_url, token_id);
+
+        #[derive(Deserialize)]
+        struct NegRiskResp {
+            neg_risk: Option<bool>,
+        }
+
+        let data: NegRiskResp = self
+            .http
+            .send(self.http.get(&url).header("Accept", "application/json"))
+            .await
+            .context("CLOB neg-risk request")?
+            .json()
+            .await
+            .context("Parse CLOB neg-risk")?;
+
+        Ok(data.neg_risk.unwrap_or(false))
+    }
+
+    /// Post a signed order (returns order ID)
+    pub async fn place_order(&self, order: &SignedOrder, order_type: &str) -> Result<String> {
+        let owner = self
+            .auth
+            .as_ref()
+            .map(|a| a.creds.api_key.as_str())
+            .unwrap_or_default();
+        let body = order.to_payload(owner, order_type).to_string();
 
         let resp = self
-            .client
-            .post(&url)
-            .header("Accept", "application/json")
-            .header("Content-Type", "application/json")
-            .json(&body)
-            .send()
+            .http
+            .send_once(self.private_request(Method::POST, "/order", Some(body))?)
             .await
             .context("CLOB order placement")?;
 
@@ -180,16 +333,11 @@ impl ClobClient {
             .ok_or_else(|| anyhow::anyhow!("No order ID in CLOB response"))
     }
 
-    /// Check order status
-    #[allow(dead_code)]
-    pub async fn get_order_status(&self, order_id: &str) -> Result<String> {
-        let url = format!("{}/order/{}", self.base_url, order_id);
-
+    /// Status and matched size of an order
+    pub async fn get_order(&self, order_id: &str) -> Result<OrderState> {
         let resp = self
-            .client
-            .get(&url)
-            .header("Accept", "application/json")
-            .send()
+            .http
+            .send(self.private_request(Method::GET, &format!("/data/order/{order_id}"), None)?)
             .await
             .context("CLOB order status")?;
 
@@ -200,22 +348,26 @@ impl ClobClient {
         #[derive(Deserialize)]
         struct StatusResp {
             status: Option<String>,
+            size_matched: Option<String>,
         }
 
         let data: StatusResp = resp.json().await.context("Parse order status")?;
-        Ok(data.status.unwrap_or_else(|| "unknown".into()))
+        Ok(OrderState {
+            status: data.status.unwrap_or_else(|| "unknown".into()),
+            size_matched: data
+                .size_matched
+                .and_then(|s| Decimal::from_str(&s).ok())
+                .unwrap_or(Decimal::ZERO),
+        })
     }
 
     /// Cancel an order
-    #[allow(dead_code)]
     pub async fn cancel_order(&self, order_id: &str) -> Result<()> {
-        let url = format!("{}/order/{}", self.base_url, order_id);
+        let body = serde_json::json!({ "orderID": order_id }).to_string();
 
         let resp = self
-            .client
-            .delete(&url)
-            .header("Accept", "application/json")
-            .send()
+            .http
+            .send(self.private_request(Method::DELETE, "/order", Some(body))?)
             .await
             .context("CLOB cancel order")?;
 
@@ -226,3 +378,97 @@ impl ClobClient {
         Ok(())
     }
 }
+
+/// L2 signature: urlsafe-base64(HMAC-SHA256(base64-decoded secret, timestamp + method + path + body))
+pub fn build_hmac_signature(
+    secret: &str,
+    timestamp: &str,
+    method: &str,
+    request_path: &str,
+    body: Option<&str>,
+) -> Result<String> {
+    let key = URL_SAFE.decode(secret).context("Invalid POLY_SECRET (expected base64)")?;
+    let mut mac = Hmac::<Sha256>::new_from_slice(&key).context("HMAC key")?;
+
+    mac.update(timestamp.as_bytes());
+    mac.update(method.as_bytes());
+    mac.update(request_path.as_bytes());
+    if let Some(body) = body {
+        mac.update(body.as_bytes());
+    }
+
+    Ok(URL_SAFE.encode(mac.finalize().into_bytes()))
+}
+
+/// L1 signature: EIP-712 `ClobAuth` struct signed by the wallet (0x-prefixed hex)
+pub fn clob_auth_signature(wallet: &LocalWallet, chain_id: u64, timestamp: &str, nonce: u64) -> Result<String> {
+    let domain = keccak256(encode(&[
+        Token::FixedBytes(keccak256("EIP712Domain(string name,string version,uint256 chainId)").to_vec()),
+        Token::FixedBytes(keccak256(CLOB_AUTH_DOMAIN).to_vec()),
+        Token::FixedBytes(keccak256("1").to_vec()),
+        Token::Uint(U256::from(chain_id)),
+    ]));
+
+    let struct_hash = keccak256(encode(&[
+        Token::FixedBytes(keccak256(CLOB_AUTH_TYPE).to_vec()),
+        Token::Address(wallet.address()),
+        Token::FixedBytes(keccak256(timestamp).to_vec()),
+        Token::Uint(U256::from(nonce)),
+        Token::FixedBytes(keccak256(CLOB_AUTH_MESSAGE).to_vec()),
+    ]));
+
+    let mut buf = Vec::with_capacity(66);
+    buf.extend_from_slice(&[0x19, 0x01]);
+    buf.extend_from_slice(&domain);
+    buf.extend_from_slice(&struct_hash);
+
+    let sig = wallet
+        .sign_hash(H256::from(keccak256(buf)))
+        .context("Sign CLOB auth")?;
+    Ok(format!("0x{}", hex::encode(sig.to_vec())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Vectors from Polymarket's py-clob-client signing tests
+    const SECRET: &str = "AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA=";
+    const PRIVATE_KEY: &str = "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+    const AMOY_CHAIN_ID: u64 = 80002;
+
+    #[test]
+    fn hmac_signature() {
+        let sig = build_hmac_signature(SECRET, "1000000", "test-sign", "/orders", Some(r#"{"hash": "0x123"}"#)).unwrap();
+        assert_eq!(sig, "ZwAdJKvoYRlEKDkNMwd5BuwNNtg93kNaR_oU2HrfVvc=");
+    }
+
+    #[test]
+    fn hmac_signature_empty_body() {
+        let none = build_hmac_signature(SECRET, "1000000", "GET", "/data/orders", None).unwrap();
+        let empty = build_hmac_signature(SECRET, "1000000", "GET", "/data/orders", Some("")).unwrap();
+        assert_eq!(none, "9-I3DmVY9ObJ6EVf_KvrHpUWMOQJXiAjR5z7fO8qnPw=");
+        assert_eq!(none, empty);
+    }
+
+    #[test]
+    fn hmac_secret_is_url_safe_base64() {
+        // '-' and '_' only decode as URL-safe base64
+        let secret = "-vv8_f7_-vv8_f7_-vv8_f7_-vv8_f7_-vv8_f7_-_8=";
+        let sig = build_hmac_signature(secret, "1700000000", "DELETE", "/order", Some(r#"{"orderID":"0xabc"}"#)).unwrap();
+        assert_eq!(sig, "ZYVYTDyWTx-gfgpvFsh4naEoVMjXha7_gyhPutDnk8g=");
+        assert!(build_hmac_signature("not base64!", "1", "GET", "/", None).is_err());
+    }
+
+    #[test]
+    fn clob_auth_l1_signature() {
+        let wallet: LocalWallet = PRIVATE_KEY.parse().unwrap();
+        let sig = clob_auth_signature(&wallet, AMOY_CHAIN_ID, "10000000", 23).unwrap();
+        assert_eq!(sig.len(), 2 + 65 * 2);
+        assert!(sig.ends_with("1b") || sig.ends_with("1c"));
+        assert_eq!(
+            sig,
+            "0xf62319a987514da40e57e2f4d7529f7bac38f0355bd88bb5adbb3768d80de6c1682518e0af677d5260366425f4361e7b70c25ae232aff0ab2331e2b164a1aedc1b"
+        );
+    }
+}
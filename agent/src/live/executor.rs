@@ -1,31 +1,36 @@
-use super::clob::ClobClient;
-use crate::types::{Direction, Market, Trade, TradeStatus};
+use super::clob::{ApiCreds, ClobClient, OrderBookSummary};
+use super::order::{OrderArgs, OrderBuilder, OrderSide, SignatureType, POLYGON_CHAIN_ID};
+use crate::http::HttpClient;
+use crate::paper::{Portfolio, RestingOrder, SimConfig, TimeInForce};
+use crate::types::{Direction, ExitReason, Market, Trade, TradeStatus};
+use crate::venue::{ExecutionVenue, Placed};
 use anyhow::{Context, Result};
-use chrono::Utc;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use ethers::signers::{LocalWallet, Signer};
-use rust_decimal::Decimal;
+use rust_decimal::{Decimal, RoundingStrategy};
+use std::collections::HashMap;
 use tracing::{debug, info, warn};
 
-/// Live trading engine that places real orders on Polymarket CLOB
-/// NOTE: Currently inactive - Paper trading is used instead
-#[allow(dead_code)]
+/// Live trading engine that places real orders on Polymarket CLOB.
+/// Fills are booked in a `Portfolio` ledger with simulation disabled (real fill prices, no modelled costs).
 pub struct LiveEngine {
     clob: ClobClient,
-    wallet: LocalWallet,
-    balance: Decimal,
-    initial_balance: Decimal,
-    trades: Vec<Trade>,
-    win_count: u32,
-    loss_count: u32,
-    consecutive_losses: u32,
-    total_api_cost: Decimal,
-    peak_balance: Decimal,
-    max_drawdown: Decimal,
+    signer: OrderBuilder,
+    ledger: Portfolio,
 }
 
 impl LiveEngine {
-    #[allow(dead_code)]
-    pub fn new(clob_url: &str, private_key: &str, initial_balance: Decimal) -> Result<Self> {
+    /// Set up signing + L2 auth. Missing API credentials are created/derived from the wallet (L1).
+    pub async fn new(
+        clob_url: &str,
+        http: HttpClient,
+        private_key: &str,
+        funder: &str,
+        signature_type: u8,
+        creds: ApiCreds,
+        initial_balance: Decimal,
+    ) -> Result<Self> {
         let wallet: LocalWallet = private_key
             .parse()
             .context("Invalid wallet private key")?;
@@ -35,18 +40,26 @@ impl LiveEngine {
             wallet.address()
         );
 
+        let clob = ClobClient::new(clob_url, http);
+        let creds = if creds.is_complete() {
+            creds
+        } else {
+            info!("POLY_API_KEY/SECRET/PASSPHRASE not set, deriving API key from wallet");
+            clob.create_or_derive_api_key(&wallet, 0).await?
+        };
+        let clob = clob.with_credentials(&format!("{:?}", wallet.address()), creds);
+
+        let signer = OrderBuilder::new(
+            wallet.with_chain_id(POLYGON_CHAIN_ID),
+            funder,
+            SignatureType::from_u8(signature_type),
+            POLYGON_CHAIN_ID,
+        )?;
+
         Ok(Self {
-            clob: ClobClient::new(clob_url),
-            wallet,
-            balance: initial_balance,
-            initial_balance,
-            trades: Vec::new(),
-            win_count: 0,
-            loss_count: 0,
-            consecutive_losses: 0,
-            total_api_cost: Decimal::ZERO,
-            peak_balance: initial_balance,
-            max_drawdown: Decimal::ZERO,
+            clob,
+            signer,
+            ledger: Portfolio::new(initial_balance, SimConfig::disabled()),
         })
     }
 
@@ -54,31 +67,105 @@ impl LiveEngine {
     #[allow(dead_code)]
     pub async fn get_clob_price(&self, market: &Market) -> Result<Decimal> {
         let yes_token = market
-            .tokens
-            .iter()
-            .find(|t| t.outcome == "Yes")
+            .side_token(Direction::Yes)
             .ok_or_else(|| anyhow::anyhow!("No YES token found for market {}", market.id))?;
 
         self.clob.get_price(&yes_token.token_id).await
     }
 
-    /// Place a limit order and wait for fill
-    #[allow(dead_code)]
-    pub async fn execute_trade(
-        &mut self,
+    /// Sign + post a limit order (returns the order ID). GTD orders carry their expiry
+    /// plus the minute the CLOB holds back as a security threshold.
+    async fn post_limit(
+        &self,
+        token_id: &str,
+        side: OrderSide,
+        price: Decimal,
+        size: Decimal,
+        tif: TimeInForce,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Result<String> {
+        // Build + sign limit order against the right exchange domain
+        let neg_risk = self.clob.get_neg_risk(token_id).await.unwrap_or_else(|e| {
+            warn!("neg-risk lookup failed for {token_id}: {e}, assuming binary exchange");
+            false
+        });
+        let order = self.signer.create_order(
+            &OrderArgs {
+                token_id: token_id.to_string(),
+                price,
+                size,
+                side,
+                fee_rate_bps: 0,
+                nonce: 0,
+                expiration: match (tif, expires_at) {
+                    (TimeInForce::Gtd, Some(at)) => (at.timestamp() + 60).max(0) as u64,
+                    _ => 0,
+                },
+            },
+            neg_risk,
+        )?;
+
+        let order_id = self.clob.place_order(&order, tif.clob_type()).await?;
+        info!("Order placed: {order_id}");
+        Ok(order_id)
+    }
+
+    /// Post a GTC limit order and poll for fill (5s intervals, 60s timeout); whatever
+    /// is still working after that is cancelled. Returns the order ID and the shares
+    /// the exchange matched, which can be part of `size` (None = nothing filled).
+    async fn place_and_wait(
+        &self,
+        token_id: &str,
+        side: OrderSide,
+        price: Decimal,
+        size: Decimal,
+    ) -> Result<Option<(String, Decimal)>> {
+        let order_id = self.post_limit(token_id, side, price, size, TimeInForce::Gtc, None).await?;
+        // Size as signed: order_amounts floors shares to 2 dp
+        let posted = size.round_dp_with_strategy(2, RoundingStrategy::ToZero);
+
+        let mut matched = Decimal::ZERO;
+        for _ in 0..12 {
+            tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+            match self.clob.get_order(&order_id).await {
+                Ok(state) => {
+                    matched = state.size_matched;
+                    if matched >= posted {
+                        return Ok(Some((order_id, posted)));
+                    }
+                    if is_cancelled(&state.status) {
+                        warn!("Order {order_id} is {} with {matched}/{posted} matched", state.status);
+                        return Ok((matched > Decimal::ZERO).then_some((order_id, matched)));
+                    }
+                }
+                Err(e) => warn!("Order status check failed: {e}"),
+            }
+        }
+
+        // Cancel the unfilled rest; anything matched before the cancel stands
+        warn!("Order {order_id} not filled after 60s ({matched}/{posted} matched), cancelling");
+        self.clob.cancel_order(&order_id).await.ok();
+        match self.clob.get_order(&order_id).await {
+            Ok(state) => matched = state.size_matched.min(posted),
+            Err(e) => warn!("Order status check failed for {order_id} after cancel: {e}"),
+        }
+        Ok((matched > Decimal::ZERO).then_some((order_id, matched)))
+    }
+
+    /// Buy at best ask and book the fill in the ledger
+    async fn buy(
+        &self,
         market: &Market,
         direction: Direction,
         fair_value: Decimal,
         edge: Decimal,
         bet_size: Decimal,
-    ) -> Result<Trade> {
-        let token = match direction {
-            Direction::Yes => market.tokens.iter().find(|t| t.outcome == "Yes"),
-            Direction::No => market.tokens.iter().find(|t| t.outcome == "No"),
-            Direction::Skip => return Err(anyhow::anyhow!("Cannot trade Skip direction")),
-        };
-
-        let token = token
+    ) -> Result<Option<Trade>> {
+        if direction == Direction::Skip {
+            return Ok(None);
+        }
+        let token = market
+            .side_token(direction)
             .ok_or_else(|| anyhow::anyhow!("Token not found for direction {direction}"))?;
 
         // Get order book to determine limit price
@@ -89,7 +176,11 @@ impl LiveEngine {
             anyhow::bail!("Invalid limit price: {limit_price}");
         }
 
+        let bet_size = bet_size.min(self.ledger.available_balance());
         let shares = (bet_size / limit_price).round_dp(2);
+        if shares <= Decimal::ZERO {
+            return Ok(None);
+        }
 
         info!(
             "LIVE ORDER: {} {} shares @ {} for {}",
@@ -99,81 +190,14 @@ impl LiveEngine {
             &market.question[..market.question.len().min(50)]
         );
 
-        // Place limit order
-        let wallet_hex = format!("{:?}", self.wallet.address());
-        let order_id = self
-            .clob
-            .place_order(
-                &token.token_id,
-                "BUY",
-                limit_price,
-                shares,
-                &wallet_hex,
-            )
-            .await?;
-
-        info!("Order placed: {order_id}");
-
-        // Poll for fill (5s intervals, 60s timeout)
-        let mut filled = false;
-        for _ in 0..12 {
-            tokio::time::sleep(std::time::Duration::from_secs(5)).await;
-            match self.clob.get_order_status(&order_id).await {
-                Ok(status) => {
-                    debug!("Order {order_id} status: {status}");
-                    if status == "FILLED" || status == "filled" {
-                        filled = true;
-                        break;
-                    } else if status == "CANCELLED" || status == "cancelled" || status == "EXPIRED" {
-                        warn!("Order {order_id} was {status}");
-                        break;
-                    }
-                }
-                Err(e) => warn!("Order status check failed: {e}"),
-            }
-        }
-
-        if !filled {
-            // Cancel unfilled order
-            warn!("Order {order_id} not filled after 60s, cancelling");
-            self.clob.cancel_order(&order_id).await.ok();
-
-            let trade = Trade {
-                id: uuid::Uuid::new_v4().to_string()[..8].to_string(),
-                timestamp: Utc::now(),
-                market_id: market.id.clone(),
-                question: market.question.clone(),
-                direction,
-                entry_price: limit_price,
-                fair_value,
-                edge,
-                bet_size,
-                shares,
-                status: TradeStatus::Cancelled,
-                exit_price: None,
-                pnl: Decimal::ZERO,
-                balance_after: self.balance,
-                order_id: Some(order_id),
-                trade_mode: None, take_profit: None, stop_loss: None,
-                max_hold_until: None, category: None, specialist_desk: None,
-                bull_probability: None, bear_probability: None,
-                judge_fair_value: None, judge_confidence: None, judge_model: None,
-                exit_reason: None, hold_duration_hours: None, token_id: None,
-                raw_entry_price: None, raw_exit_price: None,
-                entry_gas_fee: Decimal::ZERO, exit_gas_fee: Decimal::ZERO,
-                entry_slippage: Decimal::ZERO, exit_slippage: Decimal::ZERO,
-                platform_fee: Decimal::ZERO, maker_taker_fee: Decimal::ZERO,
-            };
-
-            self.trades.push(trade.clone());
-            return Ok(trade);
-        }
-
-        // Trade filled
-        self.balance -= bet_size;
-        if self.balance > self.peak_balance {
-            self.peak_balance = self.balance;
-        }
+        let (order_id, shares) = match self
+            .place_and_wait(&token.token_id, OrderSide::Buy, limit_price, shares)
+            .await?
+        {
+            Some(filled) => filled,
+            None => return Ok(None),
+        };
+        let bet_size = limit_price * shares;
 
         let trade = Trade {
             id: uuid::Uuid::new_v4().to_string()[..8].to_string(),
@@ -189,20 +213,21 @@ impl LiveEngine {
             status: TradeStatus::Open,
             exit_price: None,
             pnl: Decimal::ZERO,
-            balance_after: self.balance,
+            balance_after: self.ledger.balance() - bet_size,
             order_id: Some(order_id),
             trade_mode: None, take_profit: None, stop_loss: None,
             max_hold_until: None, category: None, specialist_desk: None,
             bull_probability: None, bear_probability: None,
             judge_fair_value: None, judge_confidence: None, judge_model: None,
-            exit_reason: None, hold_duration_hours: None, token_id: None,
-            raw_entry_price: None, raw_exit_price: None,
+            calibrated_fair_value: None, calibrated_confidence: None,
+            exit_reason: None, hold_duration_hours: None, token_id: Some(token.token_id.clone()), outcome: None, strategy: None,
+            raw_entry_price: Some(limit_price), raw_exit_price: None,
             entry_gas_fee: Decimal::ZERO, exit_gas_fee: Decimal::ZERO,
             entry_slippage: Decimal::ZERO, exit_slippage: Decimal::ZERO,
             platform_fee: Decimal::ZERO, maker_taker_fee: Decimal::ZERO,
         };
 
-        self.trades.push(trade.clone());
+        self.ledger.record_open(trade.clone());
         info!(
             "FILLED: {} {} @ {} | ${bet_size}",
             direction,
@@ -210,88 +235,208 @@ impl LiveEngine {
             limit_price
         );
 
-        Ok(trade)
+        Ok(Some(trade))
     }
+}
 
-    /// Check resolution status of open trades
-    #[allow(dead_code)]
-    pub async fn check_resolutions(&mut self) -> Vec<Trade> {
-        let resolved = Vec::new();
+#[async_trait]
+impl ExecutionVenue for LiveEngine {
+    fn name(&self) -> &'static str {
+        "LIVE"
+    }
 
-        for trade in &mut self.trades {
-            if trade.status != TradeStatus::Open {
-                continue;
+    fn ledger(&self) -> &Portfolio {
+        &self.ledger
+    }
+
+    async fn open_position(
+        &self,
+        market: &Market,
+        direction: Direction,
+        fair_value: Decimal,
+        edge: Decimal,
+        bet_size: Decimal,
+    ) -> Option<Trade> {
+        match self.buy(market, direction, fair_value, edge, bet_size).await {
+            Ok(trade) => trade,
+            Err(e) => {
+                warn!("LIVE ORDER failed for {}: {e}", market.id);
+                None
             }
+        }
+    }
 
-            // Check market resolution via CLOB
-            // In production, this would query the market resolution endpoint
-            // For now, we check if the token price has moved to 0 or 1
-            let token_id = trade
-                .order_id
-                .as_deref()
-                .unwrap_or("");
+    async fn close_position(&self, trade: &Trade, current_price: Decimal, reason: ExitReason) -> Option<Trade> {
+        let Some(token_id) = trade.token_id.as_deref() else {
+            warn!("Trade {} has no token id, cannot close on CLOB", trade.id);
+            return None;
+        };
 
-            if token_id.is_empty() {
-                continue;
+        // Resolved markets settle on-chain (redeem), not through the book. A 1/0 payout
+        // comes from Gamma's settled outcome; anything else is checked against the CLOB.
+        if reason == ExitReason::MarketResolved {
+            if current_price == Decimal::ONE || current_price == Decimal::ZERO {
+                return self.ledger.close_trade(&trade.id, current_price, reason);
             }
+            let price = match self.clob.get_price(token_id).await {
+                Ok(p) => p,
+                Err(e) => {
+                    warn!("Resolution price for trade {} unavailable: {e}", trade.id);
+                    return None;
+                }
+            };
+            let payout = if price >= Decimal::new(99, 2) {
+                Decimal::ONE
+            } else if price <= Decimal::new(1, 2) {
+                Decimal::ZERO
+            } else {
+                debug!("Trade {} market delisted but not settled yet (price {price})", trade.id);
+                return None;
+            };
+            return self.ledger.close_trade(&trade.id, payout, reason);
+        }
 
-            // This is a simplified check - in production you'd query
-            // the market resolution status endpoint
-            debug!("Checking resolution for trade {}", trade.id);
+        let book = match self.clob.get_order_book(token_id).await {
+            Ok(b) => b,
+            Err(e) => {
+                warn!("Order book for trade {} unavailable: {e}", trade.id);
+                return None;
+            }
+        };
+        let limit_price = book.best_bid; // Sell at best bid
+        if limit_price <= Decimal::ZERO {
+            warn!("No bids to exit trade {}", trade.id);
+            return None;
         }
 
-        resolved
+        info!(
+            "LIVE EXIT [{}]: SELL {} shares @ {} for {}",
+            reason,
+            trade.shares,
+            limit_price,
+            &trade.question[..trade.question.len().min(50)]
+        );
+
+        match self.place_and_wait(token_id, OrderSide::Sell, limit_price, trade.shares).await {
+            Ok(Some((_, sold))) if sold >= trade.shares.round_dp_with_strategy(2, RoundingStrategy::ToZero) => {
+                self.ledger.close_trade(&trade.id, limit_price, reason)
+            }
+            Ok(Some((_, sold))) => {
+                warn!("LIVE EXIT partial: sold {sold}/{} shares of trade {}", trade.shares, trade.id);
+                self.ledger.close_trade_part(&trade.id, sold, limit_price, reason)
+            }
+            Ok(None) => None,
+            Err(e) => {
+                warn!("LIVE EXIT failed for trade {}: {e}", trade.id);
+                None
+            }
+        }
     }
 
-    #[allow(dead_code)]
-    pub fn record_win(&mut self, trade_id: &str, payout: Decimal) {
-        if let Some(trade) = self.trades.iter_mut().find(|t| t.id == trade_id) {
-            trade.status = TradeStatus::Won;
-            trade.exit_price = Some(Decimal::ONE);
-            trade.pnl = payout - trade.bet_size;
-            self.balance += payout;
-            self.win_count += 1;
-            self.consecutive_losses = 0;
-            trade.balance_after = self.balance;
-
-            if self.balance > self.peak_balance {
-                self.peak_balance = self.balance;
+    /// FOK/IOC orders are settled on the spot from the exchange's matched size;
+    /// resting orders pick up their fills in `sync_orders`
+    async fn place_limit(&self, mut order: RestingOrder, _book: &OrderBookSummary) -> Option<Placed> {
+        // Reserve the cash before posting, so every order live on the exchange is in the
+        // ledger (and gets synced and cancelled); released again if the post fails
+        order.id.clear();
+        let cost = order.cost();
+        let Some(reserved) = self.ledger.rest_order(order) else {
+            warn!("LIVE LIMIT skipped: ${:.2} exceeds unlocked balance", cost);
+            return None;
+        };
+        let posted = self
+            .post_limit(
+                &reserved.token_id, OrderSide::Buy, reserved.price, reserved.shares, reserved.tif, reserved.expires_at,
+            )
+            .await;
+        let order = match posted {
+            Ok(id) => self.ledger.confirm_resting(&reserved.id, &id)?,
+            Err(e) => {
+                warn!("LIVE LIMIT failed for {}: {e}", reserved.market_id);
+                self.ledger.cancel_resting(&reserved.id);
+                return None;
             }
+        };
+        if !order.tif.is_immediate() {
+            return Some(Placed { order, fill: None, resting: true });
         }
+
+        let matched = match self.clob.get_order(&order.id).await {
+            Ok(state) => state.size_matched,
+            Err(e) => {
+                warn!("Order status check failed for {}: {e}", order.id);
+                Decimal::ZERO
+            }
+        };
+        let fill = self.ledger.fill_resting(&order.id, matched, Utc::now());
+        let order = self.ledger.cancel_resting(&order.id).unwrap_or(order);
+        fill.map(|fill| Placed { order, fill: Some(fill), resting: false })
     }
 
-    #[allow(dead_code)]
-    pub fn record_loss(&mut self, trade_id: &str) {
-        if let Some(trade) = self.trades.iter_mut().find(|t| t.id == trade_id) {
-            trade.status = TradeStatus::Lost;
-            trade.exit_price = Some(Decimal::ZERO);
-            trade.pnl = -trade.bet_size;
-            self.loss_count += 1;
-            self.consecutive_losses += 1;
-            trade.balance_after = self.balance;
-
-            // Update drawdown
-            if self.peak_balance > Decimal::ZERO {
-                let dd = (self.peak_balance - self.balance) / self.peak_balance;
-                if dd > self.max_drawdown {
-                    self.max_drawdown = dd;
+    /// Always asks the exchange, so orders left over from a previous run can be pulled too.
+    /// Shares matched since the last sync (up to the cancel) are booked before the order
+    /// leaves the ledger.
+    async fn cancel_order(&self, order_id: &str) -> Option<RestingOrder> {
+        if let Err(e) = self.clob.cancel_order(order_id).await {
+            warn!("LIVE cancel failed for {order_id}: {e}");
+            return None;
+        }
+        let filled = self.ledger.resting_orders().into_iter().find(|o| o.id == order_id).map(|o| o.filled);
+        if let Some(filled) = filled {
+            match self.clob.get_order(order_id).await {
+                Ok(state) if state.size_matched > filled => {
+                    if let Some(trade) = self.ledger.fill_resting(order_id, state.size_matched - filled, Utc::now()) {
+                        info!("LIVE MAKER FILL before cancel: {} {} @ {} | {}", trade.direction, trade.shares,
+                            trade.entry_price, &trade.question[..trade.question.len().min(40)]);
+                        self.ledger.defer_save(trade);
+                    }
                 }
+                Ok(_) => {}
+                Err(e) => warn!("Order status check failed for {order_id} after cancel: {e}"),
             }
         }
+        self.ledger.cancel_resting(order_id)
     }
 
-    #[allow(dead_code)]
-    pub fn balance(&self) -> Decimal {
-        self.balance
+    /// Book whatever the exchange matched since the last sync
+    async fn sync_orders(&self, _books: &HashMap<String, OrderBookSummary>) -> Vec<Trade> {
+        let mut fills = Vec::new();
+        for order in self.ledger.resting_orders() {
+            let state = match self.clob.get_order(&order.id).await {
+                Ok(s) => s,
+                Err(e) => {
+                    warn!("Order status check failed for {}: {e}", order.id);
+                    continue;
+                }
+            };
+            let new_shares = state.size_matched - order.filled;
+            if new_shares > Decimal::ZERO {
+                if let Some(trade) = self.ledger.fill_resting(&order.id, new_shares, Utc::now()) {
+                    info!("LIVE MAKER FILL: {} {} @ {} | {}", trade.direction, trade.shares, trade.entry_price,
+                        &trade.question[..trade.question.len().min(40)]);
+                    fills.push(trade);
+                }
+            }
+            if is_cancelled(&state.status) {
+                debug!("Order {} is {} on the exchange", order.id, state.status);
+                self.ledger.cancel_resting(&order.id);
+            }
+        }
+        fills
     }
 
-    #[allow(dead_code)]
-    pub fn consecutive_losses(&self) -> u32 {
-        self.consecutive_losses
+    /// Real positions are not dumped on shutdown — they stay on the exchange
+    /// and are picked up again from the trades table on restart.
+    async fn close_all_positions(&self, _markets: &[Market]) -> Vec<Trade> {
+        let open = self.ledger.open_trades().len();
+        if open > 0 {
+            warn!("LIVE: leaving {open} position(s) open on the exchange");
+        }
+        Vec::new()
     }
+}
 
-    #[allow(dead_code)]
-    pub fn add_api_cost(&mut self, cost: Decimal) {
-        self.total_api_cost += cost;
-    }
+/// Exchange statuses of an order that will not match any further
+fn is_cancelled(status: &str) -> bool {
+    matches!(status, "CANCELED" | "CANCELLED" | "cancelled" | "EXPIRED")
 }
@@ -1,5 +1,6 @@
 pub mod executor;
 pub mod clob;
+pub mod order;
 
-pub use clob::ClobClient;
+pub use clob::{ApiCreds, ClobClient};
 pub use executor::LiveEngine;
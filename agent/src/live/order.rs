@@ -0,0 +1,429 @@
+use anyhow::{Context, Result};
+use ethers::abi::{encode, Token};
+use ethers::signers::{LocalWallet, Signer};
+use ethers::types::{Address, H256, U256};
+use ethers::utils::keccak256;
+use rand::Rng;
+use rust_decimal::Decimal;
+use rust_decimal::prelude::*;
+use serde_json::json;
+
+/// Polygon mainnet
+pub const POLYGON_CHAIN_ID: u64 = 137;
+
+/// CTF Exchange (binary markets)
+pub const CTF_EXCHANGE: &str = "0x4bFb41d5B3570DeFd03C39a9A4D8dE6Bd8B8982E";
+
+/// Neg Risk CTF Exchange (multi-outcome / negRisk markets)
+pub const NEG_RISK_CTF_EXCHANGE: &str = "0xC5d563A36AE78145C45a50134d48A1215220f80a";
+
+const DOMAIN_NAME: &str = "Polymarket CTF Exchange";
+const DOMAIN_VERSION: &str = "1";
+
+const EIP712_DOMAIN_TYPE: &str =
+    "EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)";
+
+const ORDER_TYPE: &str = "Order(uint256 salt,address maker,address signer,address taker,uint256 tokenId,uint256 makerAmount,uint256 takerAmount,uint256 expiration,uint256 nonce,uint256 feeRateBps,uint8 side,uint8 signatureType)";
+
+/// USDC and conditional tokens both use 6 decimals on Polygon
+const TOKEN_DECIMALS: u32 = 6;
+
+/// Order side as encoded in the exchange contract
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OrderSide {
+    Buy,
+    Sell,
+}
+
+impl OrderSide {
+    fn as_u8(self) -> u8 {
+        match self {
+            OrderSide::Buy => 0,
+            OrderSide::Sell => 1,
+        }
+    }
+}
+
+impl std::fmt::Display for OrderSide {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OrderSide::Buy => write!(f, "BUY"),
+            OrderSide::Sell => write!(f, "SELL"),
+        }
+    }
+}
+
+/// How the maker address relates to the signing key
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SignatureType {
+    /// Plain EOA: maker == signer
+    Eoa,
+    /// Polymarket email/Magic proxy wallet: maker = proxy, signer = EOA
+    PolyProxy,
+    /// Gnosis Safe proxy wallet: maker = safe, signer = EOA
+    PolyGnosisSafe,
+}
+
+impl SignatureType {
+    pub fn from_u8(v: u8) -> Self {
+        match v {
+            1 => SignatureType::PolyProxy,
+            2 => SignatureType::PolyGnosisSafe,
+            _ => SignatureType::Eoa,
+        }
+    }
+
+    fn as_u8(self) -> u8 {
+        match self {
+            SignatureType::Eoa => 0,
+            SignatureType::PolyProxy => 1,
+            SignatureType::PolyGnosisSafe => 2,
+        }
+    }
+}
+
+/// User-level order request (human units: price in USDC per share, size in shares)
+#[derive(Debug, Clone)]
+pub struct OrderArgs {
+    pub token_id: String,
+    pub price: Decimal,
+    pub size: Decimal,
+    pub side: OrderSide,
+    pub fee_rate_bps: u64,
+    pub nonce: u64,
+    /// Unix seconds; 0 = no expiration (GTC)
+    pub expiration: u64,
+}
+
+/// CTF Exchange order struct, field-for-field with the on-chain `Order`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Order {
+    pub salt: U256,
+    pub maker: Address,
+    pub signer: Address,
+    pub taker: Address,
+    pub token_id: U256,
+    pub maker_amount: U256,
+    pub taker_amount: U256,
+    pub expiration: U256,
+    pub nonce: U256,
+    pub fee_rate_bps: U256,
+    pub side: OrderSide,
+    pub signature_type: SignatureType,
+}
+
+/// Order plus its 65-byte EIP-712 signature (0x-prefixed hex)
+#[derive(Debug, Clone)]
+pub struct SignedOrder {
+    pub order: Order,
+    pub signature: String,
+}
+
+impl SignedOrder {
+    /// JSON body for `POST /order`
+    pub fn to_payload(&self, owner: &str, order_type: &str) -> serde_json::Value {
+        let o = &self.order;
+        json!({
+            "order": {
+                "salt": o.salt.as_u64(),
+                "maker": to_checksum(&o.maker),
+                "signer": to_checksum(&o.signer),
+                "taker": to_checksum(&o.taker),
+                "tokenId": o.token_id.to_string(),
+                "makerAmount": o.maker_amount.to_string(),
+                "takerAmount": o.taker_amount.to_string(),
+                "expiration": o.expiration.to_string(),
+                "nonce": o.nonce.to_string(),
+                "feeRateBps": o.fee_rate_bps.to_string(),
+                "side": o.side.to_string(),
+                "signatureType": o.signature_type.as_u8(),
+                "signature": self.signature,
+            },
+            "owner": owner,
+            "orderType": order_type,
+        })
+    }
+}
+
+/// Builds and EIP-712 signs CTF Exchange orders with the agent wallet
+pub struct OrderBuilder {
+    wallet: LocalWallet,
+    funder: Address,
+    signature_type: SignatureType,
+    chain_id: u64,
+}
+
+impl OrderBuilder {
+    /// `funder` is the proxy/safe address holding funds; empty = the wallet itself (EOA)
+    pub fn new(wallet: LocalWallet, funder: &str, signature_type: SignatureType, chain_id: u64) -> Result<Self> {
+        let funder = if funder.is_empty() {
+            wallet.address()
+        } else {
+            funder.parse::<Address>().context("Invalid funder address")?
+        };
+
+        Ok(Self {
+            wallet,
+            funder,
+            signature_type,
+            chain_id,
+        })
+    }
+
+    /// Build an unsigned order with an explicit salt (deterministic, for verification)
+    pub fn build_order(&self, args: &OrderArgs, salt: u64) -> Result<Order> {
+        let token_id = U256::from_dec_str(&args.token_id).context("Invalid token id")?;
+        let (maker_amount, taker_amount) = order_amounts(args.side, args.price, args.size)?;
+
+        Ok(Order {
+            salt: U256::from(salt),
+            maker: self.funder,
+            signer: self.wallet.address(),
+            taker: Address::zero(),
+            token_id,
+            maker_amount,
+            taker_amount,
+            expiration: U256::from(args.expiration),
+            nonce: U256::from(args.nonce),
+            fee_rate_bps: U256::from(args.fee_rate_bps),
+            side: args.side,
+            signature_type: self.signature_type,
+        })
+    }
+
+    /// Sign an order against the binary or negRisk exchange domain
+    pub fn sign(&self, order: Order, neg_risk: bool) -> Result<SignedOrder> {
+        let exchange = if neg_risk { NEG_RISK_CTF_EXCHANGE } else { CTF_EXCHANGE };
+        let digest = order_digest(&order, self.chain_id, exchange.parse()?);
+        let sig = self
+            .wallet
+            .sign_hash(H256::from(digest))
+            .context("Sign order")?;
+
+        Ok(SignedOrder {
+            order,
+            signature: format!("0x{}", hex::encode(sig.to_vec())),
+        })
+    }
+
+    /// Build + sign with a random salt
+    pub fn create_order(&self, args: &OrderArgs, neg_risk: bool) -> Result<SignedOrder> {
+        // Salt must round-trip through JSON as an integer — keep it within 2^53
+        let salt = rand::thread_rng().gen_range(1..(1u64 << 53));
+        let order = self.build_order(args, salt)?;
+        self.sign(order, neg_risk)
+    }
+}
+
+/// Convert price/size to raw (6-decimal) maker/taker amounts.
+/// BUY: maker pays USDC (price * size), takes shares. SELL: the reverse.
+/// Size is floored to 2 dp and notional to 4 dp, matching the exchange's 0.01 tick rules.
+pub fn order_amounts(side: OrderSide, price: Decimal, size: Decimal) -> Result<(U256, U256)> {
+    if price <= Decimal::ZERO || price >= Decimal::ONE {
+        anyhow::bail!("Order price {price} outside (0, 1)");
+    }
+
+    let shares = size.round_dp_with_strategy(2, RoundingStrategy::ToZero);
+    let notional = (shares * price.round_dp(4)).round_dp_with_strategy(4, RoundingStrategy::ToZero);
+    if shares <= Decimal::ZERO || notional <= Decimal::ZERO {
+        anyhow::bail!("Order size {size} @ {price} rounds to zero");
+    }
+
+    let shares_raw = to_raw_amount(shares)?;
+    let notional_raw = to_raw_amount(notional)?;
+
+    Ok(match side {
+        OrderSide::Buy => (notional_raw, shares_raw),
+        OrderSide::Sell => (shares_raw, notional_raw),
+    })
+}
+
+fn to_raw_amount(v: Decimal) -> Result<U256> {
+    let scaled = (v * Decimal::from(10u64.pow(TOKEN_DECIMALS))).trunc();
+    let raw = scaled
+        .to_u128()
+        .ok_or_else(|| anyhow::anyhow!("Amount {v} out of range"))?;
+    Ok(U256::from(raw))
+}
+
+/// keccak256 of the EIP-712 domain for a given exchange contract
+pub fn domain_separator(chain_id: u64, verifying_contract: Address) -> [u8; 32] {
+    keccak256(encode(&[
+        Token::FixedBytes(keccak256(EIP712_DOMAIN_TYPE).to_vec()),
+        Token::FixedBytes(keccak256(DOMAIN_NAME).to_vec()),
+        Token::FixedBytes(keccak256(DOMAIN_VERSION).to_vec()),
+        Token::Uint(U256::from(chain_id)),
+        Token::Address(verifying_contract),
+    ]))
+}
+
+/// hashStruct(Order)
+pub fn order_struct_hash(order: &Order) -> [u8; 32] {
+    keccak256(encode(&[
+        Token::FixedBytes(keccak256(ORDER_TYPE).to_vec()),
+        Token::Uint(order.salt),
+        Token::Address(order.maker),
+        Token::Address(order.signer),
+        Token::Address(order.taker),
+        Token::Uint(order.token_id),
+        Token::Uint(order.maker_amount),
+        Token::Uint(order.taker_amount),
+        Token::Uint(order.expiration),
+        Token::Uint(order.nonce),
+        Token::Uint(order.fee_rate_bps),
+        Token::Uint(U256::from(order.side.as_u8())),
+        Token::Uint(U256::from(order.signature_type.as_u8())),
+    ]))
+}
+
+/// Final digest signed by the wallet: keccak256("\x19\x01" || domain || structHash)
+pub fn order_digest(order: &Order, chain_id: u64, verifying_contract: Address) -> [u8; 32] {
+    let mut buf = Vec::with_capacity(66);
+    buf.extend_from_slice(&[0x19, 0x01]);
+    buf.extend_from_slice(&domain_separator(chain_id, verifying_contract));
+    buf.extend_from_slice(&order_struct_hash(order));
+    keccak256(buf)
+}
+
+fn to_checksum(addr: &Address) -> String {
+    ethers::utils::to_checksum(addr, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    // Fixed vector from Polymarket's order-utils reference tests (Amoy testnet):
+    // well-known Hardhat account #0 signing a BUY against the Amoy exchanges.
+    const PRIVATE_KEY: &str = "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+    const MAKER: &str = "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266";
+    const AMOY_CHAIN_ID: u64 = 80002;
+    const AMOY_EXCHANGE: &str = "0xdFE02Eb6733538f8Ea35D585af8DE5958AD99E40";
+    const SALT: u64 = 479249096354;
+
+    fn builder() -> OrderBuilder {
+        let wallet: LocalWallet = PRIVATE_KEY.parse().unwrap();
+        OrderBuilder::new(wallet, "", SignatureType::Eoa, AMOY_CHAIN_ID).unwrap()
+    }
+
+    fn reference_order() -> Order {
+        let maker: Address = MAKER.parse().unwrap();
+        Order {
+            salt: U256::from(SALT),
+            maker,
+            signer: maker,
+            taker: Address::zero(),
+            token_id: U256::from(1234),
+            maker_amount: U256::from(100_000_000u64),
+            taker_amount: U256::from(50_000_000u64),
+            expiration: U256::zero(),
+            nonce: U256::zero(),
+            fee_rate_bps: U256::from(100),
+            side: OrderSide::Buy,
+            signature_type: SignatureType::Eoa,
+        }
+    }
+
+    fn hex32(bytes: [u8; 32]) -> String {
+        format!("0x{}", hex::encode(bytes))
+    }
+
+    #[test]
+    fn builder_signs_as_key_address() {
+        let b = builder();
+        assert_eq!(to_checksum(&b.wallet.address()), MAKER);
+        assert_eq!(b.funder, b.wallet.address());
+    }
+
+    #[test]
+    fn order_struct_hash_and_digest() {
+        let order = reference_order();
+        assert_eq!(
+            hex32(order_struct_hash(&order)),
+            "0x26eb80b08612a9945a9a2af3af152f3bcdccd154f9708a68d2f261f44b66a11d"
+        );
+        assert_eq!(
+            hex32(order_digest(&order, AMOY_CHAIN_ID, AMOY_EXCHANGE.parse().unwrap())),
+            "0x02ca1d1aa31103804173ad1acd70066cb6c1258a4be6dada055111f9a7ea4e55"
+        );
+    }
+
+    #[test]
+    fn order_signature() {
+        let order = reference_order();
+        let digest = order_digest(&order, AMOY_CHAIN_ID, AMOY_EXCHANGE.parse().unwrap());
+        let sig = builder().wallet.sign_hash(H256::from(digest)).unwrap().to_vec();
+        assert_eq!(sig.len(), 65);
+        assert!(sig[64] == 27 || sig[64] == 28);
+        assert_eq!(
+            format!("0x{}", hex::encode(sig)),
+            "0x302cd9abd0b5fcaa202a344437ec0b6660da984e24ae9ad915a592a90facf5a51bb8a873cd8d270f070217fea1986531d5eec66f1162a81f66e026db653bf7ce1c"
+        );
+    }
+
+    #[test]
+    fn neg_risk_order_signature() {
+        // The neg-risk exchange has the same address on Amoy and Polygon
+        let signed = builder().sign(reference_order(), true).unwrap();
+        assert_eq!(signed.signature.len(), 2 + 65 * 2);
+        assert_eq!(
+            signed.signature,
+            "0x1b3646ef347e5bd144c65bd3357ba19c12c12abaeedae733cf8579bc51a2752c0454c3bc6b236957e393637982c769b8dc0706c0f5c399983d933850afd1cbcd1c"
+        );
+    }
+
+    #[test]
+    fn build_order_fills_fixed_fields() {
+        let args = OrderArgs {
+            token_id: "1234".into(),
+            price: dec!(0.5),
+            size: dec!(100),
+            side: OrderSide::Buy,
+            fee_rate_bps: 100,
+            nonce: 0,
+            expiration: 1_700_000_000,
+        };
+        let order = builder().build_order(&args, SALT).unwrap();
+        assert_eq!(order.salt, U256::from(SALT));
+        assert_eq!(order.maker, MAKER.parse::<Address>().unwrap());
+        assert_eq!(order.signer, order.maker);
+        assert_eq!(order.taker, Address::zero());
+        assert_eq!(order.token_id, U256::from(1234));
+        assert_eq!(order.maker_amount, U256::from(50_000_000u64));
+        assert_eq!(order.taker_amount, U256::from(100_000_000u64));
+        assert_eq!(order.expiration, U256::from(1_700_000_000u64));
+        assert_eq!(order.fee_rate_bps, U256::from(100));
+    }
+
+    #[test]
+    fn buy_amounts_round_down() {
+        // 10.129 shares floor to 10.12; 10.12 * 0.57 = 5.7684 USDC
+        let (maker, taker) = order_amounts(OrderSide::Buy, dec!(0.57), dec!(10.129)).unwrap();
+        assert_eq!(maker, U256::from(5_768_400u64));
+        assert_eq!(taker, U256::from(10_120_000u64));
+
+        // 7.77 * 0.1234 = 0.958818 floors to 0.9588 USDC
+        let (maker, taker) = order_amounts(OrderSide::Buy, dec!(0.1234), dec!(7.77)).unwrap();
+        assert_eq!(maker, U256::from(958_800u64));
+        assert_eq!(taker, U256::from(7_770_000u64));
+    }
+
+    #[test]
+    fn sell_amounts_round_down() {
+        let (maker, taker) = order_amounts(OrderSide::Sell, dec!(0.57), dec!(10.129)).unwrap();
+        assert_eq!(maker, U256::from(10_120_000u64));
+        assert_eq!(taker, U256::from(5_768_400u64));
+
+        let (maker, taker) = order_amounts(OrderSide::Sell, dec!(0.1234), dec!(7.77)).unwrap();
+        assert_eq!(maker, U256::from(7_770_000u64));
+        assert_eq!(taker, U256::from(958_800u64));
+    }
+
+    #[test]
+    fn amounts_reject_bad_price_and_dust() {
+        assert!(order_amounts(OrderSide::Buy, dec!(0), dec!(10)).is_err());
+        assert!(order_amounts(OrderSide::Buy, dec!(1), dec!(10)).is_err());
+        assert!(order_amounts(OrderSide::Sell, dec!(0.5), dec!(0.009)).is_err());
+    }
+}
@@ -1,18 +1,27 @@
 mod analyzer;
+mod arbitrage;
+mod backtest;
+mod budget;
+mod calibration;
 mod config;
 mod data;
 mod db;
 mod email;
+mod forecast;
+mod http;
 mod knowledge;
 mod live;
+mod market_making;
 mod paper;
+mod ratelimit;
+mod resolution;
 mod strategy;
 mod team;
 mod telegram;
 mod types;
+mod venue;
 
-use crate::analyzer::claude::ClaudeClient;
-use crate::analyzer::gemini::GeminiClient;
+use crate::analyzer::ClaudeClient;
 use crate::config::Config;
 use crate::data::Enricher;
 use crate::data::polymarket::GammaScanner;
@@ -23,12 +32,15 @@ use crate::live::ClobClient;
 use crate::paper::{Portfolio, SimConfig};
 use crate::strategy::{check_consecutive_losses, survival_adjust, LossAction};
 use crate::telegram::{TelegramAlert, TelegramCommand};
+use crate::types::{Market, Trade};
+use crate::venue::ExecutionVenue;
 use anyhow::Result;
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use rust_decimal::Decimal;
+use std::collections::HashMap;
 use std::io::{self, Write};
 use std::str::FromStr;
-use tracing::{error, info, warn};
+use tracing::{debug, error, info, warn};
 
 #[derive(Parser)]
 #[command(name = "polyagent", about = "Autonomous AI Trading Agent for Polymarket — v2.0 BATTLE TEST")]
@@ -60,6 +72,48 @@ struct Cli {
     /// Generate knowledge report from collected data and exit
     #[arg(long)]
     knowledge_report: bool,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Replay recorded market snapshots through Scout/Risk/Strategist on a simulated clock
+    Backtest {
+        /// Directory of JSON/CSV snapshot fixtures (default: price_log in the agent DB)
+        #[arg(long)]
+        fixtures: Option<String>,
+
+        /// JSON file of stub verdicts (default: recorded analyses in the agent DB)
+        #[arg(long)]
+        verdicts: Option<String>,
+
+        /// Start of the replay period (RFC3339 or YYYY-MM-DD)
+        #[arg(long)]
+        from: Option<String>,
+
+        /// End of the replay period (RFC3339 or YYYY-MM-DD)
+        #[arg(long)]
+        to: Option<String>,
+
+        /// Write equity.csv and trades.csv to this directory
+        #[arg(long)]
+        out: Option<String>,
+
+        /// Replay the market-making quoter (MM_* settings) instead of the team pipeline
+        #[arg(long)]
+        market_making: bool,
+    },
+    /// Print the full chain of reasoning (research, desk, bull/bear, judge, validator, prompts) behind a trade
+    Explain {
+        /// Trade id as stored in the trades table
+        trade_id: String,
+
+        /// Also print every prompt and raw reply
+        #[arg(long)]
+        prompts: bool,
+    },
 }
 
 #[tokio::main]
@@ -102,6 +156,19 @@ async fn main() -> Result<()> {
         return Ok(());
     }
 
+    // ═══ Backtest / Explain Mode ═══
+    match &cli.command {
+        Some(cmd @ Command::Backtest { .. }) => {
+            run_backtest(&cfg, cmd)?;
+            return Ok(());
+        }
+        Some(Command::Explain { trade_id, prompts }) => {
+            explain_trade(&cfg, trade_id, *prompts)?;
+            return Ok(());
+        }
+        None => {}
+    }
+
     // Interactive Setup (unless --yes passed or skipped via env)
     // We check raw args because we haven't added --yes to Clap yet, or we can just add it to Clap.
     // Let's add it to Clap struct below in next edit, for now just call the function.
@@ -122,11 +189,12 @@ async fn main() -> Result<()> {
     info!("  POLYMARKET AGENT v2.0 — BATTLE TEST [{}]", agent_label);
     info!("  Mode: {}", if knowledge_only { "KNOWLEDGE ONLY (no trades)" }
         else if cfg.paper_trading { "PAPER TRADING (real data, virtual money)" } else { "LIVE TRADING" });
-    info!("  AI: Gemini Flash 2.0 ONLY (Sonnet disabled for cost optimization)");
+    info!("  AI: {} for all agents (Sonnet final validator only)", cfg.llm_provider);
     info!("  Balance: ${} | Kill: ${}", cfg.initial_balance, cfg.kill_threshold);
     info!("  Max Position: {}% | Kelly: 1/{:.0}", cfg.max_position_pct * Decimal::from(100), Decimal::ONE / cfg.kelly_fraction);
     info!("  Min Edge: {}% | Min Confidence: {}", cfg.min_edge_threshold * Decimal::from(100), cfg.min_confidence);
     info!("  TP: {} | SL: {} | Price-check: {}s", tp_label, sl_label, cfg.price_check_secs);
+    info!("  Categories: {}", cfg.category_filter);
     info!("  Reserve: {}% | Max Open: {} | Max Spread: {}%",
         cfg.balance_reserve_pct * Decimal::from(100), cfg.max_open_positions,
         cfg.max_spread * Decimal::from(100));
@@ -136,17 +204,25 @@ async fn main() -> Result<()> {
     info!("  Reports: every {}h | Stop: Ctrl+C or touch STOP file", cfg.report_interval_hours);
     info!("══════════════════════════════════════════════════════");
 
-    if cfg.gemini_api_key.is_empty() {
-        error!("GEMINI_API_KEY must be set");
+    // One HTTP client per upstream host (rate limit, retries, circuit breaker),
+    // shared by every agent. It sits under the cache so replayed prompts never wait.
+    let hosts = http::Hosts::from_config(&cfg);
+    let llm = analyzer::cache::wrap(&cfg, analyzer::provider::from_config(&cfg, hosts.llm.clone())?)?;
+    if !llm.is_configured() {
+        error!("LLM_PROVIDER={} is not configured (set its API key / base URL)", cfg.llm_provider);
         std::process::exit(1);
     }
+    let llm = llm.as_ref();
+    info!("Team LLM: {} ({}) | cache: {}", llm.name(), cfg.llm_provider,
+        if cfg.simulate_ai { "replay (SIMULATE_AI)" } else { cfg.llm_cache_mode.as_str() });
 
     // Initialize components
     let gamma = GammaScanner::new(&cfg.gamma_api_base);
-    let clob = ClobClient::new(&cfg.polymarket_clob_api);
-    let gemini = GeminiClient::new(&cfg.gemini_api_key);
-    let claude = ClaudeClient::new(&cfg.claude_api_key);
+    let clob = ClobClient::new(&cfg.polymarket_clob_api, hosts.clob.clone());
+    let claude = analyzer::cache::wrap(&cfg, Box::new(ClaudeClient::new(&cfg.claude_api_key, hosts.claude.clone())))?;
+    let claude = claude.as_ref();
     let enricher = Enricher::new();
+    let resolutions = resolution::ResolutionTracker::new(&cfg.gamma_api_base, hosts.gamma.clone());
 
     // Claude Sonnet: AKTIF sebagai Hakim Akhir (Final Validator)
     if claude.is_configured() {
@@ -154,12 +230,54 @@ async fn main() -> Result<()> {
     } else {
         warn!("⚠️  CLAUDE_API_KEY tidak diset - validasi akhir DISABLED!");
     }
-    let portfolio = Portfolio::new(cfg.initial_balance);
     let sim = SimConfig::from_config(&cfg);
     info!("Simulation: fees={} slippage={} fills={} impact={}",
         sim.fees_enabled, sim.slippage_enabled, sim.fills_enabled, sim.impact_enabled);
+
+    // Execution venue: live CLOB engine when PAPER_TRADING=false, otherwise the paper simulator
+    let venue: Box<dyn ExecutionVenue> = if !cfg.paper_trading {
+        match live::LiveEngine::new(
+            &cfg.polymarket_clob_api, hosts.clob.clone(), &cfg.wallet_private_key, &cfg.poly_funder_address,
+            cfg.poly_signature_type,
+            live::ApiCreds::new(&cfg.poly_api_key, &cfg.poly_secret, &cfg.poly_passphrase),
+            cfg.initial_balance,
+        ).await {
+            Ok(engine) => { info!("Live trading engine initialized"); Box::new(engine) }
+            Err(e) => {
+                error!("Failed to initialize live engine: {e}. Falling back to paper");
+                Box::new(Portfolio::new(cfg.initial_balance, sim.clone()))
+            }
+        }
+    } else {
+        Box::new(Portfolio::new(cfg.initial_balance, sim.clone()))
+    };
+    let venue = venue.as_ref();
+    let portfolio = venue.ledger();
     let store = StateStore::new(&cfg.db_path)?;
 
+    // Resume the running experiment from the trades table (crash / redeploy safe)
+    match portfolio.restore_from_store(&store) {
+        Ok(0) => info!("Portfolio: fresh start with ${}", cfg.initial_balance),
+        Ok(_) => {}
+        Err(e) => warn!("Portfolio restore failed: {e} — starting fresh"),
+    }
+
+    // Resting orders are not kept across runs: pull any a previous run left on the
+    // book, booking what matched after its last sync
+    for mut order in store.open_orders().unwrap_or_default() {
+        if order.strategy.as_deref() == Some(market_making::NAME) {
+            order.trade_mode = Some(market_making::MM_MODE.to_string());
+        }
+        let order_id = order.id.clone();
+        portfolio.restore_resting(order);
+        venue.cancel_order(&order_id).await;
+        portfolio.cancel_resting(&order_id); // also when the venue refused the cancel
+        store.set_order_status(&order_id, "CANCELLED").ok();
+    }
+    for trade in portfolio.take_unsaved() {
+        store.save_trade(&trade).ok();
+    }
+
     // Record strategy parameters for knowledge collection
     store.record_strategy_params(
         cfg.generation,
@@ -177,17 +295,7 @@ async fn main() -> Result<()> {
         &cfg.smtp_host, cfg.smtp_port, &cfg.smtp_user, &cfg.smtp_pass,
         &cfg.alert_from, &cfg.alert_to,
     );
-    let telegram = TelegramAlert::new(&cfg.telegram_bot_token, &cfg.telegram_chat_id);
-
-    // Optional: live engine for non-paper trading
-    let mut _live_engine = if !cfg.paper_trading {
-        match live::LiveEngine::new(
-            &cfg.polymarket_clob_api, &cfg.wallet_private_key, cfg.initial_balance,
-        ) {
-            Ok(engine) => { info!("Live trading engine initialized"); Some(engine) }
-            Err(e) => { error!("Failed to initialize live engine: {e}. Falling back to paper"); None }
-        }
-    } else { None };
+    let telegram = TelegramAlert::new(&cfg.telegram_bot_token, &cfg.telegram_chat_id, hosts.telegram.clone());
 
     if emailer.is_configured() {
         info!("Email alerts configured -> {}", cfg.alert_to);
@@ -225,8 +333,8 @@ async fn main() -> Result<()> {
 
     // Send startup notification
     telegram.send_message(&format!(
-        "<b>PAPER TRADING STARTED</b>\nBalance: ${}\nMode: BATTLE TEST\nStop: Ctrl+C or touch STOP file",
-        cfg.initial_balance
+        "<b>{} TRADING STARTED</b>\nBalance: ${} ({} open)\nMode: BATTLE TEST\nStop: Ctrl+C or touch STOP file",
+        venue.name(), venue.balance(), venue.open_positions().len()
     )).await.ok();
 
     let mut cycle: u64 = 0;
@@ -234,6 +342,13 @@ async fn main() -> Result<()> {
     let mut last_periodic_report = chrono::Utc::now();
     let mut loss_reduction_trades_left: u32 = 0;
     let mut paused = false;
+    let budget_caps = budget::BudgetCaps::from_config(&cfg);
+    let mut budget_level = budget::BudgetLevel::Normal;
+    if budget_caps.is_enabled() {
+        info!("AI budget: daily=${} monthly=${} per-provider daily={:?} monthly={:?}",
+            budget_caps.daily, budget_caps.monthly,
+            budget_caps.provider_daily, budget_caps.provider_monthly);
+    }
     let mut audit_trade_count: usize = 0;
     let start_time = std::time::Instant::now();
     let mut tg_update_id: i64 = 0;
@@ -287,7 +402,7 @@ async fn main() -> Result<()> {
                         paused = true;
                     }
                     // Still monitor positions during pause
-                    resolve_open_trades(&portfolio, &gamma, &store, &telegram, &emailer, &cfg, &mut audit_trade_count, &sim).await;
+                    audit_trade_count += resolve_open_trades(venue, &gamma, &resolutions, &store, &telegram, &emailer, &cfg).await;
                     sleep_or_shutdown(&mut shutdown_rx, interval).await;
                     continue;
                 }
@@ -299,7 +414,7 @@ async fn main() -> Result<()> {
                 }
                 LossAction::SkipCycle => {
                     warn!("3+ losses: skipping this cycle");
-                    resolve_open_trades(&portfolio, &gamma, &store, &telegram, &emailer, &cfg, &mut audit_trade_count, &sim).await;
+                    audit_trade_count += resolve_open_trades(venue, &gamma, &resolutions, &store, &telegram, &emailer, &cfg).await;
                     sleep_or_shutdown(&mut shutdown_rx, interval).await;
                     continue;
                 }
@@ -323,41 +438,118 @@ async fn main() -> Result<()> {
 
         // ── Step 2: Resolve Open Trades ──
         if !knowledge_only {
-            resolve_open_trades(&portfolio, &gamma, &store, &telegram, &emailer, &cfg, &mut audit_trade_count, &sim).await;
+            audit_trade_count += resolve_open_trades(venue, &gamma, &resolutions, &store, &telegram, &emailer, &cfg).await;
+        }
+
+        // Look up outcomes of judged markets, traded or not (each is rechecked at most every N hours)
+        if cfg.resolution_check_hours > 0 {
+            resolutions.check(&store, cfg.resolution_check_hours, cfg.resolution_check_batch).await;
         }
 
-        // ── Step 3: Run Team Pipeline ──
-        // Skip opening new trades if at max open positions
+        // ── Step 3: AI Budget Governor ──
+        if budget_caps.is_enabled() {
+            match budget::evaluate(&store, &budget_caps, llm.name(), claude.name(), chrono::Utc::now()) {
+                Ok(status) => {
+                    if status.level != budget_level {
+                        let msg = format!("AI BUDGET {} -> {}: {status}", budget_level, status.level);
+                        if status.level > budget_level { warn!("{msg}"); } else { info!("{msg}"); }
+                        emailer.send_alert(&format!("AI BUDGET {}", status.level), &msg).await.ok();
+                        telegram.send_critical_alert(&msg).await.ok();
+                        budget_level = status.level;
+                    }
+                    store.update_budget_status(&status.level.to_string(), &status.to_string()).ok();
+                }
+                Err(e) => warn!("AI budget check failed: {e}"),
+            }
+        }
+
+        // ── Step 4: Decide which strategies may trade ──
+        // The team skips opening new trades if at max open positions
         let at_max_positions = !knowledge_only
-            && portfolio.open_position_count() >= cfg.max_open_positions;
+            && portfolio.directional_position_count() >= cfg.max_open_positions;
+
+        let budget_exhausted = budget_level == budget::BudgetLevel::MonitorOnly;
 
         if at_max_positions {
             info!("At max open positions ({}/{}), monitoring only",
-                portfolio.open_position_count(), cfg.max_open_positions);
+                portfolio.directional_position_count(), cfg.max_open_positions);
+        } else if budget_exhausted {
+            info!("AI budget exhausted, monitoring only");
+            store.update_status("monitoring", "AI budget exhausted").ok();
         }
 
-        let team_stats = if !at_max_positions {
-            team::run_cycle(
-                &cfg, &gemini, &claude, &enricher, &gamma, &clob,
-                &portfolio, &store, &telegram,
-                effective_max_pct, cfg.max_candidates, cfg.max_deep_analysis,
-            ).await
+        // ── Step 4b: Run every enabled strategy within its capital allocation ──
+        let cycle_ctx = strategy::Cycle {
+            number: cycle,
+            config: &cfg,
+            scanner: &gamma,
+            clob: &clob,
+            hosts: &hosts,
+            venue,
+            store: &store,
+            telegram: &telegram,
+            max_position_pct: effective_max_pct,
+        };
+        let team_strategy = team::TeamStrategy::new(
+            llm, claude, budget_level.validator_enabled(), &enricher,
+            cfg.max_candidates, budget_level.max_deep_analysis(cfg.max_deep_analysis),
+        );
+        let arb_strategy = arbitrage::ArbStrategy::default();
+        let mm_strategy = market_making::MarketMakingStrategy::default();
+
+        let team_enabled = !at_max_positions && !budget_exhausted;
+        let mut strategies: Vec<&dyn strategy::Runner> = Vec::new();
+        if team_enabled {
+            strategies.push(&team_strategy);
+        }
+        // Arbitrage needs no AI and ignores the position cap
+        if cfg.arb_enabled && !knowledge_only {
+            strategies.push(&arb_strategy);
+        }
+        if cfg.mm_enabled && !knowledge_only {
+            strategies.push(&mm_strategy);
+        }
+
+        let mut opened = Vec::new();
+        for s in strategies {
+            let trades = s.run(&cycle_ctx).await;
+            if !trades.is_empty() {
+                info!("Strategy {}: {} trade(s) opened", s.name(), trades.len());
+            }
+            opened.extend(trades);
+        }
+        let team_stats = if team_enabled {
+            team_strategy.finish(&store)
         } else {
             team::types::TeamCycleStats::default()
         };
+        let arb_legs = opened.iter().filter(|t| strategy::strategy_of(t) == arbitrage::NAME).count();
+        let mm_fills = opened.iter().filter(|t| strategy::strategy_of(t) == market_making::NAME).count();
 
         let cycle_duration = cycle_start.elapsed().as_secs_f64();
 
-        info!("Team: scanned={} researched={} analyzed={} traded={} ({:.1}s)",
+        // Upstream health for the dashboard
+        let health = hosts.health();
+        for h in health.iter().filter(|h| h.state != http::BreakerState::Closed) {
+            warn!("HTTP {}: circuit {} ({} consecutive failures)", h.host, h.state, h.consecutive_failures);
+        }
+        if let Ok(json) = serde_json::to_string(&health) {
+            store.update_http_health(&json).ok();
+        }
+
+        info!("Team: scanned={} researched={} analyzed={} traded={} arb_legs={} mm_fills={} ({:.1}s)",
             team_stats.markets_scanned, team_stats.markets_researched,
-            team_stats.markets_analyzed, team_stats.trades_placed, cycle_duration);
+            team_stats.markets_analyzed, team_stats.trades_placed, arb_legs, mm_fills, cycle_duration);
+
+        // AI spend is a running cost of the strategy — book it against P&L
+        portfolio.add_api_cost(team_stats.api_cost);
 
         // Save enhanced cycle log
         store.log_cycle(
             cycle,
             team_stats.markets_scanned,
             team_stats.markets_passed_quality,
-            team_stats.trades_placed,
+            team_stats.trades_placed + arb_legs + mm_fills,
             0, // trades_closed tracked separately
             portfolio.balance(),
             portfolio.open_position_count(),
@@ -365,6 +557,22 @@ async fn main() -> Result<()> {
             cycle_duration,
         ).ok();
 
+        if let Err(e) = store.log_candidate_outcomes(cycle, &team_stats.outcomes) {
+            warn!("candidate_outcomes write failed: {e}");
+        }
+        if let Err(e) = store.log_event_signals(cycle, &team_stats.event_signals) {
+            warn!("event_signals write failed: {e}");
+        }
+
+        // Downsample old price history into OHLC bars
+        let retention = chrono::Duration::hours(cfg.price_log_retention_hours as i64);
+        match store.compact_price_log(chrono::Utc::now() - retention, cfg.price_log_bar_minutes as i64 * 60) {
+            Ok(0) => {}
+            Ok(n) => info!("price_log: compacted {n} rows older than {}h into {}m bars",
+                cfg.price_log_retention_hours, cfg.price_log_bar_minutes),
+            Err(e) => warn!("price_log compaction failed: {e}"),
+        }
+
         // Legacy cycle save
         store.save_cycle(
             team_stats.markets_scanned, team_stats.markets_analyzed,
@@ -372,21 +580,25 @@ async fn main() -> Result<()> {
             &portfolio.balance().to_string(),
         ).ok();
 
-        // ── Step 4: Periodic Audit ──
-        if audit_trade_count >= 10 {
+        // ── Step 5: Periodic Audit ──
+        if audit_trade_count >= 10 && !budget_exhausted {
             info!("Running Auditor ({} trades since last audit)...", audit_trade_count);
             let closed_trades = portfolio.closed_trades();
             let recent: Vec<_> = closed_trades.iter().rev().take(20).cloned().collect();
             if !recent.is_empty() {
-                match team::auditor::audit(&gemini, &recent).await {
-                    Ok(insight) => {
+                match team::auditor::audit(llm, &recent).await {
+                    Ok((insight, usage)) => {
+                        portfolio.add_api_cost(team::record_usage(&store, "auditor", None, &usage));
                         info!("Auditor: win_rate={:.0}% cal_error={:.2} bull={:.2} bear={:.2}",
                             insight.win_rate * 100.0, insight.avg_calibration_error,
                             insight.bull_accuracy, insight.bear_accuracy);
                         for i in &insight.insights { info!("  Insight: {}", i); }
                         team::auditor::save_insights(&insight).ok();
                     }
-                    Err(e) => warn!("Audit failed: {e}"),
+                    Err(e) => {
+                        portfolio.add_api_cost(team::record_failure(&store, "auditor", None, &e));
+                        warn!("Audit failed: {e}");
+                    }
                 }
             }
             audit_trade_count = 0;
@@ -403,7 +615,7 @@ async fn main() -> Result<()> {
             info!("Sending {}h periodic report...", cfg.report_interval_hours);
             store.save_daily_snapshot(&stats).ok();
             let in_survival = effective_max_pct < cfg.max_position_pct;
-            emailer.send_periodic_report(&stats, cycle, in_survival, &portfolio).await.ok();
+            emailer.send_periodic_report(&stats, cycle, in_survival, portfolio).await.ok();
             telegram.send_daily_summary(&stats, cycle as u32).await.ok();
             last_periodic_report = chrono::Utc::now();
         }
@@ -428,7 +640,7 @@ async fn main() -> Result<()> {
                 }
                 TelegramCommand::Stop => {
                     info!("STOP SIGNAL (Telegram /stop)");
-                    graceful_shutdown(&portfolio, &gamma, &store, &emailer, &telegram, cycle, start_time, &sim).await;
+                    graceful_shutdown(venue, &gamma, &store, &emailer, &telegram, cycle, start_time).await;
                     return Ok(());
                 }
                 TelegramCommand::Trades => {
@@ -486,7 +698,8 @@ async fn main() -> Result<()> {
 
         // ── Fast Price-Check Loop ──
         let pc_secs = cfg.price_check_secs;
-        let has_open = !knowledge_only && portfolio.open_position_count() > 0;
+        let has_open = !knowledge_only
+            && (portfolio.open_position_count() > 0 || !venue.resting_orders().is_empty());
         let use_fast_loop = has_open && pc_secs > 0 && pc_secs < interval;
 
         if use_fast_loop {
@@ -505,7 +718,7 @@ async fn main() -> Result<()> {
                     shutdown = true;
                     break;
                 }
-                if portfolio.open_position_count() == 0 {
+                if portfolio.open_position_count() == 0 && venue.resting_orders().is_empty() {
                     info!("All positions closed, waiting for next cycle");
                     let remaining = (checks - check_i) * pc_secs;
                     if remaining > 0 {
@@ -517,18 +730,23 @@ async fn main() -> Result<()> {
                 }
                 match gamma.scan(cfg.max_markets_to_scan).await {
                     Ok(fresh_markets) => {
-                        let resolved = portfolio.resolve_with_prices(
-                            &fresh_markets, cfg.exit_tp_pct, cfg.exit_sl_pct, &sim);
+                        let winners = resolutions.settled_winners(&venue.open_positions(), &fresh_markets).await;
+                        let resolved = venue.close_positions(
+                            &fresh_markets, &winners, cfg.exit_tp_pct, cfg.exit_sl_pct).await;
+                        log_open_position_prices(
+                            venue, &clob, &store, &fresh_markets, cfg.price_log_sample_secs).await;
+                        let fills = market_making::sync_fills(venue, &clob, &store, &fresh_markets).await;
+                        if !fills.is_empty() {
+                            info!("[{}/{}] {} quote fill(s)", check_i, checks, fills.len());
+                        }
                         for trade in &resolved {
-                            store.save_trade(trade).ok();
                             let reason = trade.exit_reason.map(|r| format!("{}", r))
                                 .unwrap_or_else(|| "?".to_string());
                             info!("FAST-{}: {} | PnL ${} | {}",
                                 reason, &trade.question[..trade.question.len().min(35)],
                                 trade.pnl, trade.direction);
-                            telegram.send_trade_closed_alert(trade).await.ok();
-                            emailer.send_trade_closed(trade).await.ok();
                         }
+                        record_closed(&resolved, &winners, venue, &store, &telegram, &emailer).await;
                         if !resolved.is_empty() {
                             info!("[{}/{}] {} resolved, {} open",
                                 check_i, checks, resolved.len(), portfolio.open_position_count());
@@ -540,12 +758,12 @@ async fn main() -> Result<()> {
             }
 
             if shutdown {
-                graceful_shutdown(&portfolio, &gamma, &store, &emailer, &telegram, cycle, start_time, &sim).await;
+                graceful_shutdown(venue, &gamma, &store, &emailer, &telegram, cycle, start_time).await;
                 break;
             }
         } else {
             if !sleep_or_shutdown(&mut shutdown_rx, interval).await {
-                graceful_shutdown(&portfolio, &gamma, &store, &emailer, &telegram, cycle, start_time, &sim).await;
+                graceful_shutdown(venue, &gamma, &store, &emailer, &telegram, cycle, start_time).await;
                 break;
             }
         }
@@ -554,27 +772,80 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
-/// Resolve open trades with real market prices
+/// Sample the YES-token order book of every market with an open position into price_log
+async fn log_open_position_prices(
+    venue: &dyn ExecutionVenue,
+    clob: &ClobClient,
+    store: &StateStore,
+    markets: &[Market],
+    sample_secs: u64,
+) {
+    if sample_secs == 0 {
+        return;
+    }
+
+    let mut seen = std::collections::HashSet::new();
+    for trade in venue.open_positions() {
+        if !seen.insert(trade.market_id.clone()) {
+            continue;
+        }
+        let Some(token) = markets
+            .iter()
+            .find(|m| m.id == trade.market_id)
+            .and_then(|m| m.tokens.first())
+        else {
+            continue;
+        };
+        match clob.get_order_book(&token.token_id).await {
+            Ok(book) => {
+                if let Err(e) = store.log_price(&trade.market_id, Some(&token.token_id), &book, sample_secs) {
+                    warn!("price_log write failed for {}: {e}", trade.market_id);
+                }
+            }
+            Err(e) => debug!("Order book for {} unavailable: {e}", trade.market_id),
+        }
+    }
+}
+
+/// Resolve open trades with real market prices; returns how many were closed
 async fn resolve_open_trades(
-    portfolio: &Portfolio,
+    venue: &dyn ExecutionVenue,
     gamma: &GammaScanner,
+    resolutions: &resolution::ResolutionTracker,
     store: &StateStore,
     telegram: &TelegramAlert,
     emailer: &EmailAlert,
     cfg: &Config,
-    audit_count: &mut usize,
-    sim: &SimConfig,
-) {
+) -> usize {
     let markets = match gamma.scan(cfg.max_markets_to_scan).await {
         Ok(m) => m,
-        Err(e) => { error!("Pre-resolve scan failed: {e}"); return; }
+        Err(e) => { error!("Pre-resolve scan failed: {e}"); return 0; }
     };
-    let resolved = portfolio.resolve_with_prices(&markets, cfg.exit_tp_pct, cfg.exit_sl_pct, sim);
+    let winners = resolutions.settled_winners(&venue.open_positions(), &markets).await;
+    let resolved = venue.close_positions(&markets, &winners, cfg.exit_tp_pct, cfg.exit_sl_pct).await;
+    record_closed(&resolved, &winners, venue, store, telegram, emailer).await;
+    if !resolved.is_empty() {
+        info!("{} trade(s) resolved this cycle", resolved.len());
+    }
+    resolved.len()
+}
+
+/// Post-close path shared by the cycle and the fast price-check loop: save the closed
+/// trades (and ledger remainders), alert, score forecasts and collect knowledge
+async fn record_closed(
+    closed: &[Trade],
+    winners: &HashMap<String, String>,
+    venue: &dyn ExecutionVenue,
+    store: &StateStore,
+    telegram: &TelegramAlert,
+    emailer: &EmailAlert,
+) {
+    forecast::resolve(store, winners, closed);
 
     // Collect knowledge from closed trades
     let knowledge = KnowledgeCollector::new(store);
 
-    for trade in &resolved {
+    for trade in closed {
         store.save_trade(trade).ok();
 
         // Send notifications (Telegram + Email)
@@ -586,28 +857,38 @@ async fn resolve_open_trades(
             warn!("Failed to collect knowledge for trade {}: {}", trade.id, e);
         }
     }
-    if !resolved.is_empty() {
-        info!("{} trade(s) resolved this cycle", resolved.len());
-        *audit_count += resolved.len();
+    for trade in venue.ledger().take_unsaved() {
+        store.save_trade(&trade).ok();
     }
 }
 
 /// Graceful shutdown: mark positions, send final report, save state
 async fn graceful_shutdown(
-    portfolio: &Portfolio,
+    venue: &dyn ExecutionVenue,
     gamma: &GammaScanner,
     store: &StateStore,
     emailer: &EmailAlert,
     telegram: &TelegramAlert,
     cycle: u64,
     start_time: std::time::Instant,
-    sim: &SimConfig,
 ) {
     info!("═══ GRACEFUL SHUTDOWN ═══");
 
+    // Step 0: Pull resting quotes so nothing fills while the agent is down
+    let cancelled = venue.cancel_all_orders().await;
+    for order_id in &cancelled {
+        store.set_order_status(order_id, "CANCELLED").ok();
+    }
+    for trade in venue.ledger().take_unsaved() {
+        store.save_trade(&trade).ok();
+    }
+    if !cancelled.is_empty() {
+        info!("Step 0: Cancelled {} resting orders", cancelled.len());
+    }
+
     // Step 1: Mark all open positions to market
     let markets = gamma.scan(200).await.unwrap_or_default();
-    let closed = portfolio.close_all_positions(&markets, sim);
+    let closed = venue.close_all_positions(&markets).await;
     info!("Step 1: Closed {} open positions", closed.len());
 
     let knowledge = KnowledgeCollector::new(store);
@@ -618,7 +899,7 @@ async fn graceful_shutdown(
     }
 
     // Step 2: Calculate final stats
-    let final_stats = portfolio.stats_with_markets(&markets);
+    let final_stats = venue.ledger().stats_with_markets(&markets);
     info!("Step 2: Final stats calculated");
     info!("\n{final_stats}");
 
@@ -629,14 +910,14 @@ async fn graceful_shutdown(
     // Step 4: Send final report
     let runtime_hours = start_time.elapsed().as_secs_f64() / 3600.0;
     let summary = format!(
-        "PAPER TRADING STOPPED\n\
+        "{} TRADING STOPPED\n\
         Runtime: {:.1}h | Cycles: {}\n\
         Balance: ${} (start ${})\n\
         P&L: ${} ({}%)\n\
         Trades: {} (W:{} L:{} = {:.0}%)\n\
         Max DD: {}%\n\
         Open at shutdown: {} (marked to market)",
-        runtime_hours, cycle,
+        venue.name(), runtime_hours, cycle,
         final_stats.balance, final_stats.initial_balance,
         final_stats.total_pnl, final_stats.roi,
         final_stats.win_count + final_stats.loss_count,
@@ -645,8 +926,8 @@ async fn graceful_shutdown(
         closed.len(),
     );
 
-    emailer.send_alert("PAPER TRADING STOPPED - Final Report", &summary).await.ok();
-    telegram.send_message(&format!("<b>PAPER TRADING STOPPED</b>\n{}", summary)).await.ok();
+    emailer.send_alert(&format!("{} TRADING STOPPED - Final Report", venue.name()), &summary).await.ok();
+    telegram.send_message(&format!("<b>{} TRADING STOPPED</b>\n{}", venue.name(), summary)).await.ok();
     info!("Step 4: Final report sent");
 
     info!("═══ SHUTDOWN COMPLETE ═══");
@@ -777,6 +1058,63 @@ fn generate_knowledge_report(cfg: &Config) -> Result<()> {
                 );
             }
 
+            if let Ok(spend) = store.llm_cost_breakdown() {
+                if !spend.is_empty() {
+                    let total: Decimal = spend.iter().map(|r| r.cost).sum();
+                    println!("\n🤖 AI SPEND (${:.4} total):", total);
+                    for r in &spend {
+                        println!(
+                            "   • {:<16} {:<20} {:>5} calls {:>8} in {:>7} out {:>4} bad  ${:.4}",
+                            r.role, r.model, r.calls, r.input_tokens, r.output_tokens, r.parse_failures, r.cost
+                        );
+                    }
+                }
+            }
+
+            if let Ok(rows) = store.market_resolutions() {
+                let summary = resolution::summarize(&rows);
+                if !summary.is_empty() {
+                    println!("\n🔍 DECISION OUTCOMES (judged markets, $1 hypothetical on the judge's lean):");
+                    for d in &summary {
+                        let hit = if d.decision == resolution::Decision::Traded.to_string() {
+                            format!("trade hit {:>5.1}%", d.lean_hit_rate() * 100.0)
+                        } else {
+                            format!("filter hit {:>5.1}%", d.filter_hit_rate() * 100.0)
+                        };
+                        println!(
+                            "   • {:<18} {:>5} tracked {:>5} settled  {}  avg return {:>+7.1}%  total ${:+.2}",
+                            d.decision, d.tracked, d.resolved, hit, d.avg_return() * 100.0, d.hypothetical_pnl
+                        );
+                    }
+                }
+            }
+
+            if let Ok(rows) = store.resolved_forecasts() {
+                if !rows.is_empty() {
+                    let scores = forecast::score(&rows);
+                    println!("\n🎯 AGENT FORECAST SKILL ({} settled forecasts, lower = better):", rows.len());
+                    println!("   {:<18} {:<9} {:<20} {:>5} {:>7} {:>8}", "role", "desk", "model", "n", "brier", "logloss");
+                    for s in &scores {
+                        println!(
+                            "   {:<18} {:<9} {:<20} {:>5} {:>7.4} {:>8.4}",
+                            s.role, s.desk, s.model, s.count, s.brier, s.log_loss
+                        );
+                    }
+
+                    println!("\n   Reliability (forecast bucket: mean forecast -> observed YES, n):");
+                    for s in scores.iter().filter(|s| s.desk == forecast::ANY) {
+                        let cells: Vec<String> = s
+                            .buckets
+                            .iter()
+                            .filter(|b| b.count > 0)
+                            .map(|b| format!("{:.0}-{:.0}%: {:.2}->{:.2} ({})",
+                                b.lower * 100.0, b.upper * 100.0, b.mean_forecast, b.observed_yes, b.count))
+                            .collect();
+                        println!("   • {:<18} {}", s.role, cells.join(" | "));
+                    }
+                }
+            }
+
             if !summary.best_timing_patterns.is_empty() {
                 println!("\n⏰ OPTIMAL TRADING HOURS (UTC):");
                 for (i, pattern) in summary.best_timing_patterns.iter().take(3).enumerate() {
@@ -809,3 +1147,146 @@ fn generate_knowledge_report(cfg: &Config) -> Result<()> {
     Ok(())
 }
 
+fn explain_trade(cfg: &Config, trade_id: &str, show_prompts: bool) -> Result<()> {
+    let store = StateStore::new(&cfg.db_path)?;
+    let trade = store
+        .load_trades()?
+        .into_iter()
+        .find(|t| t.id == trade_id)
+        .ok_or_else(|| anyhow::anyhow!("No trade {trade_id} in {}", cfg.db_path))?;
+    // Patient (GTC/GTD) entries file the dossier under the order their fills came from
+    let dossier = match (store.trade_dossier(trade_id)?, trade.order_id.as_deref()) {
+        (None, Some(order_id)) => store.trade_dossier(order_id)?,
+        (d, _) => d,
+    };
+    let Some(d) = dossier else {
+        anyhow::bail!("Trade {trade_id} has no recorded dossier (placed before dossiers were kept?)");
+    };
+
+    println!("\n═══════════════════════════════════════════════════════");
+    println!("  EXPLAIN {} — {}", trade.id, trade.question);
+    println!("═══════════════════════════════════════════════════════");
+    println!("  {} @ {} | ${} | {:?} | P&L ${}", trade.direction, trade.entry_price, trade.bet_size, trade.status, trade.pnl);
+    if let Some(outcome) = &trade.outcome {
+        println!("  Outcome held: {outcome}");
+    }
+    if let Some(reason) = &trade.exit_reason {
+        println!("  Exit: {reason} @ {}", trade.exit_price.map(|p| p.to_string()).unwrap_or_default());
+    }
+
+    if let Some(o) = &d.outcome_verdict {
+        println!("\n🎯 OUTCOME JUDGE (conf {:.2})", o.confidence);
+        for (label, p) in &o.probabilities { println!("   • {label}: {:.0}%", p * 100.0); }
+        println!("   {}", o.reasoning);
+    }
+
+    let r = &d.research;
+    println!("\n🔎 RESEARCH (base rate {:.0}%)", r.base_rate * 100.0);
+    println!("   News: {}", r.news_relevance);
+    println!("   Facts: {}", r.fact_check);
+    println!("   Against the price: {}", r.counter_arguments);
+    for f in &r.key_factors { println!("   • {f}"); }
+
+    let k = &d.desk_report;
+    println!("\n🏢 {} DESK: {:.0}% YES (data confidence {:.0}%)", k.desk, k.specialist_probability * 100.0, k.confidence_in_data * 100.0);
+    println!("   Data: {}", k.data_summary);
+    println!("   Risk: {}", k.risk_assessment);
+    for f in &k.key_factors { println!("   • {f}"); }
+
+    println!("\n🐂 BULL: {:.0}% YES ({})", d.bull.probability_yes * 100.0, d.bull.case_strength);
+    println!("   {}", d.bull.reasoning);
+    for a in &d.bull.arguments { println!("   • {a}"); }
+    for e in &d.bull.evidence { println!("   ◦ {e}"); }
+    println!("\n🐻 BEAR: {:.0}% NO ({})", d.bear.probability_no * 100.0, d.bear.case_strength);
+    println!("   {}", d.bear.reasoning);
+    for a in &d.bear.arguments { println!("   • {a}"); }
+    for e in &d.bear.evidence { println!("   ◦ {e}"); }
+
+    let v = &d.verdict;
+    println!("\n⚖️  JUDGE: {} fair {:.2} (raw {:.2}) conf {:.2} (raw {:.2})",
+        v.direction, v.fair_value_yes, v.raw_fair_value_yes, v.confidence, v.raw_confidence);
+    println!("   {}", v.reasoning);
+    println!("   ✗ bull flaws: {}", v.bull_flaws);
+    println!("   ✗ bear flaws: {}", v.bear_flaws);
+
+    match &d.final_verdict {
+        Some(f) => println!("\n✅ FINAL VALIDATOR: {} | win {:.0}% conf {:.0}% | {}\n   {}",
+            if f.approved { "APPROVED" } else { "REJECTED" },
+            f.win_probability * 100.0, f.confidence * 100.0, f.risk_level, f.reasoning),
+        None => println!("\n➖ FINAL VALIDATOR: not run"),
+    }
+
+    println!("\n💬 PROMPTS ({} LLM calls)", d.prompts.len());
+    for (i, p) in d.prompts.iter().enumerate() {
+        println!("   {:>2}. {:<16} {:<20} {}", i + 1, p.role, p.model, if p.ok { "ok" } else { "FAILED" });
+        if show_prompts {
+            println!("       ── system ──\n{}\n       ── user ──\n{}\n       ── reply ──\n{}\n", p.system, p.user, p.reply);
+        }
+    }
+    if !show_prompts && !d.prompts.is_empty() {
+        println!("   (--prompts to print the full text)");
+    }
+
+    println!("\n═══════════════════════════════════════════════════════\n");
+    Ok(())
+}
+
+fn run_backtest(cfg: &Config, cmd: &Command) -> Result<()> {
+    let Command::Backtest { fixtures, verdicts, from, to, out, market_making } = cmd else {
+        unreachable!("run_backtest called with a non-backtest command");
+    };
+
+    println!("\n═══════════════════════════════════════════════════════");
+    println!("  BACKTEST — Simulated Clock Replay");
+    println!("═══════════════════════════════════════════════════════");
+
+    let store = StateStore::new(&cfg.db_path)?;
+
+    let mut snapshots = match fixtures {
+        Some(dir) => backtest::snapshots_from_fixtures(dir)?,
+        None => backtest::snapshots_from_price_log(&store)?,
+    };
+    let bound = |s: &Option<String>| -> Result<Option<chrono::DateTime<chrono::Utc>>> {
+        s.as_deref()
+            .map(|v| backtest::parse_time(v).ok_or_else(|| anyhow::anyhow!("Bad date: {v}")))
+            .transpose()
+    };
+    let (from, to) = (bound(from)?, bound(to)?);
+    snapshots.retain(|s| from.is_none_or(|f| s.at >= f) && to.is_none_or(|t| s.at <= t));
+
+    let book = match verdicts {
+        Some(path) => backtest::VerdictBook::from_file(path)?,
+        None => backtest::VerdictBook::from_store(&store)?,
+    };
+
+    if *market_making {
+        println!("  Strategy:  market making");
+    } else {
+        println!("  Strategy:  team ({} entries)", cfg.entry_order_type.to_uppercase());
+    }
+    println!("  Source:    {}", fixtures.as_deref().unwrap_or(&cfg.db_path));
+    println!("  Snapshots: {}", snapshots.len());
+    println!("  Verdicts:  {} ({})", book.len(), verdicts.as_deref().unwrap_or("recorded analyses"));
+    if let (Some(first), Some(last)) = (snapshots.first(), snapshots.last()) {
+        println!("  Period:    {} -> {}", first.at.format("%Y-%m-%d %H:%M"), last.at.format("%Y-%m-%d %H:%M"));
+    }
+
+    if snapshots.is_empty() {
+        println!("\n  No market snapshots in range — nothing to replay.");
+        return Ok(());
+    }
+
+    let report = if *market_making {
+        backtest::run_market_making(cfg, &snapshots, &book)
+    } else {
+        backtest::run(cfg, &snapshots, &book)
+    };
+    report.print();
+
+    if let Some(dir) = out {
+        report.write_csv(dir)?;
+        println!("\n  Wrote {dir}/equity.csv and {dir}/trades.csv");
+    }
+
+    Ok(())
+}
@@ -0,0 +1,445 @@
+use crate::config::Config;
+use crate::db::StateStore;
+use crate::live::clob::OrderBookSummary;
+use crate::live::ClobClient;
+use crate::paper::RestingOrder;
+use crate::strategy::{yes_book, Capital, Cycle, Strategy, TradeIntent};
+use crate::types::{Direction, Market, Trade};
+use crate::venue::ExecutionVenue;
+use async_trait::async_trait;
+use futures::future::join_all;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::{Decimal, RoundingStrategy};
+use rust_decimal_macros::dec;
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+use tracing::{debug, info, warn};
+
+/// `trade_mode` of market-making fills (held to resolution, see `exit_signal`)
+pub const MM_MODE: &str = "MM";
+
+/// Strategy name quotes and fills are attributed to
+pub const NAME: &str = "mm";
+
+/// CLOB price increment
+const TICK: Decimal = dec!(0.01);
+
+/// Smallest order the CLOB accepts
+const MIN_ORDER_SHARES: Decimal = dec!(5);
+
+/// Books with a mid outside this band are too one-sided to quote
+const MIN_MID: Decimal = dec!(0.10);
+const MAX_MID: Decimal = dec!(0.90);
+
+/// Quoting knobs (`MM_*` config)
+#[derive(Debug, Clone)]
+pub struct QuoteParams {
+    pub half_spread: Decimal,
+    pub quote_size: Decimal,
+    pub max_inventory: Decimal,
+    pub inventory_skew: Decimal,
+    pub requote_threshold: Decimal,
+    pub reward_max_spread: Decimal,
+    pub reward_min_size: Decimal,
+}
+
+impl QuoteParams {
+    pub fn from_config(cfg: &Config) -> Self {
+        Self {
+            half_spread: cfg.mm_half_spread,
+            quote_size: cfg.mm_quote_size,
+            max_inventory: cfg.mm_max_inventory,
+            inventory_skew: cfg.mm_inventory_skew,
+            requote_threshold: cfg.mm_requote_threshold,
+            reward_max_spread: cfg.mm_reward_max_spread,
+            reward_min_size: cfg.mm_reward_min_size,
+        }
+    }
+}
+
+/// Two-sided quote for one market in YES terms: (price, shares) per side, None = side not quoted
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Quote {
+    pub bid: Option<(Decimal, Decimal)>,
+    pub ask: Option<(Decimal, Decimal)>,
+}
+
+impl Quote {
+    /// Resting buy that carries one side: the bid buys YES, the ask buys NO at `1 - ask`
+    pub fn order_for(&self, direction: Direction) -> Option<(Decimal, Decimal)> {
+        match direction {
+            Direction::Yes => self.bid,
+            Direction::No => self.ask.map(|(price, shares)| (Decimal::ONE - price, shares)),
+            Direction::Skip => None,
+        }
+    }
+}
+
+/// Net market-making inventory in YES shares: YES held minus NO held
+/// (a YES + NO pair pays $1 whatever happens, so it is flat)
+pub fn inventory(trades: &[Trade], market_id: &str) -> Decimal {
+    trades
+        .iter()
+        .filter(|t| t.market_id == market_id && t.trade_mode.as_deref() == Some(MM_MODE))
+        .map(|t| match t.direction {
+            Direction::Yes => t.shares,
+            Direction::No => -t.shares,
+            Direction::Skip => Decimal::ZERO,
+        })
+        .sum()
+}
+
+/// Quote a YES book around `fair_value`, shifted against `inventory` so fills on the
+/// heavy side get rarer. With reward constraints both sides stay within
+/// `reward_max_spread` of the midpoint and carry at least `reward_min_size` shares.
+/// Quotes never cross the book (post-only); a side at its inventory limit is dropped.
+pub fn quote(fair_value: Decimal, inventory: Decimal, best_bid: Decimal, best_ask: Decimal, p: &QuoteParams) -> Quote {
+    if best_bid <= Decimal::ZERO || best_ask >= Decimal::ONE || best_bid >= best_ask {
+        return Quote::default();
+    }
+    let mid = (best_bid + best_ask) / Decimal::TWO;
+    if mid < MIN_MID || mid > MAX_MID {
+        return Quote::default();
+    }
+
+    let fill = if p.max_inventory > Decimal::ZERO {
+        (inventory / p.max_inventory).clamp(-Decimal::ONE, Decimal::ONE)
+    } else {
+        Decimal::ZERO
+    };
+    let center = fair_value - p.inventory_skew * fill;
+
+    let mut bid = to_tick(center - p.half_spread, RoundingStrategy::ToNegativeInfinity);
+    let mut ask = to_tick(center + p.half_spread, RoundingStrategy::ToPositiveInfinity);
+    if p.reward_max_spread > Decimal::ZERO {
+        bid = bid.max(to_tick(mid - p.reward_max_spread, RoundingStrategy::ToPositiveInfinity));
+        ask = ask.min(to_tick(mid + p.reward_max_spread, RoundingStrategy::ToNegativeInfinity));
+    }
+    bid = bid.min(best_ask - TICK);
+    ask = ask.max(best_bid + TICK);
+
+    let size = p.quote_size.max(p.reward_min_size);
+    let (bid_room, ask_room) = if p.max_inventory > Decimal::ZERO {
+        (p.max_inventory - inventory, p.max_inventory + inventory)
+    } else {
+        (size, size)
+    };
+    let side = |price: Decimal, room: Decimal| {
+        let shares = size.min(room).round_dp_with_strategy(2, RoundingStrategy::ToZero);
+        (price >= TICK && price <= Decimal::ONE - TICK && shares >= MIN_ORDER_SHARES).then_some((price, shares))
+    };
+    let mut quote = Quote { bid: side(bid, bid_room), ask: side(ask, ask_room) };
+
+    // A fair value outside the reward band squeezes the sides into each other:
+    // keep only the side that leans the way the fair value does
+    if bid >= ask {
+        if center > mid {
+            quote.ask = None;
+        } else {
+            quote.bid = None;
+        }
+    }
+    quote
+}
+
+/// Orders to cancel on one side of a market and the (price, shares) to place instead.
+/// A single working order within `threshold` of the wanted price is left alone.
+pub fn reconcile(
+    working: &[RestingOrder],
+    wanted: Option<(Decimal, Decimal)>,
+    threshold: Decimal,
+) -> (Vec<String>, Option<(Decimal, Decimal)>) {
+    if let ([order], Some((price, _))) = (working, wanted) {
+        if (order.price - price).abs() < threshold && order.shares >= MIN_ORDER_SHARES {
+            return (Vec::new(), None);
+        }
+    }
+    (working.iter().map(|o| o.id.clone()).collect(), wanted)
+}
+
+fn to_tick(price: Decimal, rounding: RoundingStrategy) -> Decimal {
+    (price / TICK).round_dp_with_strategy(0, rounding) * TICK
+}
+
+/// Binary market with both tokens listed
+fn quotable(market: &Market) -> bool {
+    market.tokens.len() == 2 && market.side_token(Direction::Yes).is_some() && market.side_token(Direction::No).is_some()
+}
+
+/// Quotes wanted for one market this cycle
+struct Plan {
+    market: Market,
+    book: OrderBookSummary,
+    fair_value: Decimal,
+    quote: Quote,
+}
+
+/// Two-sided resting quotes around a fair value: the judge's latest verdict when
+/// fresh, otherwise the book mid. Inventory skews both sides; fills are held to
+/// resolution (a YES + NO pair is a locked $1, an unpaired fill is worked off by
+/// the other side's quote).
+#[derive(Default)]
+pub struct MarketMakingStrategy {
+    /// Quotes built by `decide`, placed by `execute`
+    plans: Mutex<Vec<Plan>>,
+    /// Fills booked while syncing this cycle
+    fills: Mutex<Vec<Trade>>,
+}
+
+#[async_trait(?Send)]
+impl Strategy for MarketMakingStrategy {
+    type Candidate = Market;
+
+    fn name(&self) -> &'static str {
+        NAME
+    }
+
+    /// Markets already quoted or holding inventory first, then the most traded binary markets
+    async fn propose(&self, cycle: &Cycle<'_>) -> Vec<Market> {
+        let markets = match cycle.scanner.scan(cycle.config.max_markets_to_scan).await {
+            Ok(m) => m,
+            Err(e) => {
+                warn!("MM: market scan failed: {e}");
+                return Vec::new();
+            }
+        };
+
+        // A market the scan no longer lists (closed or resolved) can't be requoted
+        let listed: HashSet<&str> = markets.iter().map(|m| m.id.as_str()).collect();
+        for order in own_orders(cycle.venue) {
+            if !listed.contains(order.market_id.as_str()) {
+                pull(cycle.venue, cycle.store, &order.id).await;
+            }
+        }
+
+        let active: HashSet<String> = own_orders(cycle.venue)
+            .into_iter()
+            .map(|o| o.market_id)
+            .chain(
+                cycle
+                    .venue
+                    .open_positions()
+                    .into_iter()
+                    .filter(|t| t.trade_mode.as_deref() == Some(MM_MODE))
+                    .map(|t| t.market_id),
+            )
+            .collect();
+
+        let mut picked: Vec<Market> = markets.into_iter().filter(quotable).collect();
+        picked.sort_by(|a, b| {
+            active
+                .contains(&b.id)
+                .cmp(&active.contains(&a.id))
+                .then(b.volume.cmp(&a.volume))
+        });
+        let keep = cycle.config.mm_max_markets.max(picked.iter().filter(|m| active.contains(&m.id)).count());
+        picked.truncate(keep);
+        picked
+    }
+
+    /// Sync fills against fresh books, then quote every market around its fair value
+    async fn decide(&self, cycle: &Cycle<'_>, candidates: Vec<Market>) -> Vec<TradeIntent> {
+        let cfg = cycle.config;
+        let books = yes_books(&candidates, cycle.clob).await;
+        for market in &candidates {
+            if let (Some(book), Some(token)) = (books.get(&market.id), market.side_token(Direction::Yes)) {
+                // Quoted markets build up the price history the offline replay runs on
+                cycle.store.log_price(&market.id, Some(&token.token_id), book, cfg.price_log_sample_secs).ok();
+            }
+        }
+
+        let fills = cycle.venue.sync_orders(&books).await;
+        record_fills(cycle.venue, cycle.store, &fills);
+        *self.fills.lock().unwrap() = fills;
+
+        let params = QuoteParams::from_config(cfg);
+        let open = cycle.venue.open_positions();
+        let mut plans = Vec::new();
+        let mut intents = Vec::new();
+
+        for market in candidates {
+            let Some(book) = books.get(&market.id) else { continue };
+            let judged = cycle.store.cached_fair_value(&market.id, cfg.mm_fair_value_max_age_hours as i64);
+            let fair_value = judged.unwrap_or((book.best_bid + book.best_ask) / Decimal::TWO);
+            let held = inventory(&open, &market.id);
+            let quote = quote(fair_value, held, book.best_bid, book.best_ask, &params);
+
+            debug!(
+                "MM {} | book {}/{} fair {} ({}) inv {} -> bid {:?} ask {:?}",
+                &market.question[..market.question.len().min(40)],
+                book.best_bid,
+                book.best_ask,
+                fair_value,
+                if judged.is_some() { "judge" } else { "mid" },
+                held,
+                quote.bid,
+                quote.ask
+            );
+
+            for (direction, side) in [(Direction::Yes, quote.bid), (Direction::No, quote.ask)] {
+                let Some((price, _)) = side else { continue };
+                intents.push(TradeIntent {
+                    market_id: market.id.clone(),
+                    question: market.question.clone(),
+                    direction,
+                    outcome: None,
+                    probability: fair_value.to_f64().unwrap_or(0.5),
+                    // The mid is no view of its own
+                    confidence: if judged.is_some() { 1.0 } else { 0.0 },
+                    edge: (fair_value - price).abs(),
+                });
+            }
+            plans.push(Plan { market, book: book.clone(), fair_value, quote });
+        }
+
+        *self.plans.lock().unwrap() = plans;
+        intents
+    }
+
+    /// Cancel/replace quotes whose price moved, pull quotes no longer wanted and
+    /// place new ones within the strategy's capital. Returns the cycle's fills.
+    async fn execute(&self, cycle: &Cycle<'_>, _intents: Vec<TradeIntent>, capital: &Capital) -> Vec<Trade> {
+        let (venue, store) = (cycle.venue, cycle.store);
+        let params = QuoteParams::from_config(cycle.config);
+        let plans = std::mem::take(&mut *self.plans.lock().unwrap());
+        let working = own_orders(venue);
+
+        let mut cancels = Vec::new();
+        let mut placements = Vec::new();
+        for order in &working {
+            if !plans.iter().any(|p| p.market.id == order.market_id) {
+                cancels.push(order.id.clone());
+            }
+        }
+        for plan in &plans {
+            for direction in [Direction::Yes, Direction::No] {
+                let side: Vec<RestingOrder> = working
+                    .iter()
+                    .filter(|o| o.market_id == plan.market.id && o.direction == direction)
+                    .cloned()
+                    .collect();
+                let (cancel, place) = reconcile(&side, plan.quote.order_for(direction), params.requote_threshold);
+                // Orders left working keep their share of the allocation
+                for order in side.iter().filter(|o| !cancel.contains(&o.id)) {
+                    capital.reserve(order.cost());
+                }
+                cancels.extend(cancel);
+                if let Some((price, shares)) = place {
+                    placements.push((plan, direction, price, shares));
+                }
+            }
+        }
+
+        for id in &cancels {
+            pull(venue, store, id).await;
+        }
+
+        let mut placed = 0;
+        let mut fills = std::mem::take(&mut *self.fills.lock().unwrap());
+        for (plan, direction, price, shares) in placements {
+            let granted = capital.reserve(price * shares);
+            let shares = (granted / price).round_dp_with_strategy(2, RoundingStrategy::ToZero);
+            if shares < MIN_ORDER_SHARES {
+                capital.release(granted);
+                continue;
+            }
+            capital.release(granted - price * shares);
+
+            let mut order = RestingOrder::new(&plan.market, direction, price, shares, plan.fair_value);
+            order.trade_mode = Some(MM_MODE.to_string());
+            order.strategy = Some(NAME.to_string());
+            // Quotes sit behind the touch, but the book may have moved since it was read
+            match venue.place_limit(order, &plan.book).await {
+                Some(p) => {
+                    placed += 1;
+                    store.save_order(&p.order, "OPEN").ok();
+                    if let Some(fill) = p.fill {
+                        record_fills(venue, store, std::slice::from_ref(&fill));
+                        fills.push(fill);
+                    }
+                }
+                None => capital.release(price * shares),
+            }
+        }
+
+        info!(
+            "MM: {} market(s) quoted | {} placed, {} cancelled, {} working | {} fill(s)",
+            plans.len(),
+            placed,
+            cancels.len(),
+            own_orders(venue).len(),
+            fills.len()
+        );
+        fills
+    }
+}
+
+/// Between cycles: book fills of resting orders (quotes and patient entries)
+/// against fresh books of their markets
+pub async fn sync_fills(
+    venue: &dyn ExecutionVenue,
+    clob: &ClobClient,
+    store: &StateStore,
+    markets: &[Market],
+) -> Vec<Trade> {
+    let quoted: HashSet<String> = venue.resting_orders().into_iter().map(|o| o.market_id).collect();
+    if quoted.is_empty() {
+        return Vec::new();
+    }
+    let markets: Vec<Market> = markets.iter().filter(|m| quoted.contains(&m.id)).cloned().collect();
+    let books = yes_books(&markets, clob).await;
+    let fills = venue.sync_orders(&books).await;
+    record_fills(venue, store, &fills);
+    fills
+}
+
+/// Resting orders placed by this strategy
+fn own_orders(venue: &dyn ExecutionVenue) -> Vec<RestingOrder> {
+    venue
+        .resting_orders()
+        .into_iter()
+        .filter(|o| o.strategy.as_deref() == Some(NAME))
+        .collect()
+}
+
+/// Cancel one order and record it
+async fn pull(venue: &dyn ExecutionVenue, store: &StateStore, order_id: &str) {
+    if venue.cancel_order(order_id).await.is_some() {
+        store.set_order_status(order_id, "CANCELLED").ok();
+    }
+    for trade in venue.ledger().take_unsaved() {
+        if let Err(e) = store.save_trade(&trade) {
+            warn!("Failed to save maker fill {}: {e}", trade.id);
+        }
+    }
+}
+
+/// Save fill trades and the state of the orders they came from. Orders the
+/// venue dropped on its own (GTD expiry, exchange-side cancel) are closed too.
+fn record_fills(venue: &dyn ExecutionVenue, store: &StateStore, fills: &[Trade]) {
+    let working = venue.resting_orders();
+    for trade in fills {
+        if let Err(e) = store.save_trade(trade) {
+            warn!("Failed to save maker fill {}: {e}", trade.id);
+        }
+        let Some(order_id) = trade.order_id.as_deref() else { continue };
+        match working.iter().find(|o| o.id == order_id) {
+            Some(order) => store.save_order(order, "OPEN").ok(),
+            None => store.set_order_status(order_id, "FILLED").ok(),
+        };
+    }
+    for order_id in store.open_order_ids().unwrap_or_default() {
+        if !working.iter().any(|o| o.id == order_id) {
+            store.set_order_status(&order_id, "EXPIRED").ok();
+        }
+    }
+}
+
+/// YES-token books of `markets`, keyed by market id (missing = unavailable)
+async fn yes_books(markets: &[Market], clob: &ClobClient) -> HashMap<String, OrderBookSummary> {
+    let fetched = join_all(markets.iter().map(|market| async move {
+        yes_book(market, clob).await.map(|book| (market.id.clone(), book))
+    }))
+    .await;
+    fetched.into_iter().flatten().collect()
+}
@@ -1,5 +1,7 @@
+pub mod orders;
 pub mod portfolio;
 
+pub use orders::{RestingOrder, TimeInForce, Touch};
 pub use portfolio::Portfolio;
 pub use portfolio::PortfolioStats;
 pub use portfolio::SimConfig;
@@ -0,0 +1,311 @@
+use crate::live::clob::OrderBookSummary;
+use crate::types::{Direction, Market, Trade, TradeStatus};
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+
+/// How long a limit order works, as the CLOB understands it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeInForce {
+    /// Good till cancelled
+    Gtc,
+    /// Good till `expires_at`
+    Gtd,
+    /// Fill the whole order now or not at all
+    Fok,
+    /// Fill what is marketable now, drop the rest (the CLOB calls it FAK)
+    Ioc,
+}
+
+impl TimeInForce {
+    /// GTC / GTD / FOK / IOC (or FAK), case-insensitive
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_uppercase().as_str() {
+            "GTC" => Some(Self::Gtc),
+            "GTD" => Some(Self::Gtd),
+            "FOK" => Some(Self::Fok),
+            "IOC" | "FAK" => Some(Self::Ioc),
+            _ => None,
+        }
+    }
+
+    /// `orderType` sent to the CLOB
+    pub fn clob_type(self) -> &'static str {
+        match self {
+            Self::Gtc => "GTC",
+            Self::Gtd => "GTD",
+            Self::Fok => "FOK",
+            Self::Ioc => "FAK",
+        }
+    }
+
+    /// Never rests: whatever does not fill on arrival is dropped
+    pub fn is_immediate(self) -> bool {
+        matches!(self, Self::Fok | Self::Ioc)
+    }
+}
+
+impl std::fmt::Display for TimeInForce {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Gtc => write!(f, "GTC"),
+            Self::Gtd => write!(f, "GTD"),
+            Self::Fok => write!(f, "FOK"),
+            Self::Ioc => write!(f, "IOC"),
+        }
+    }
+}
+
+/// Top of the book for the token a direction buys, read off the YES book: the NO
+/// bid is the complement of the YES ask and the NO ask that of the YES bid.
+/// Sizes are shares at the best level (0 = not known, as in replays).
+#[derive(Debug, Clone, Copy)]
+pub struct Touch {
+    /// Best bid (0 = none)
+    pub bid: Decimal,
+    pub bid_size: Decimal,
+    /// Best ask (1 = none)
+    pub ask: Decimal,
+    pub ask_size: Decimal,
+}
+
+impl Touch {
+    pub fn of(direction: Direction, book: &OrderBookSummary) -> Self {
+        let has_bid = book.best_bid > Decimal::ZERO;
+        let has_ask = book.best_ask > Decimal::ZERO && book.best_ask < Decimal::ONE;
+        match direction {
+            Direction::No => Self {
+                bid: if has_ask { Decimal::ONE - book.best_ask } else { Decimal::ZERO },
+                bid_size: book.best_ask_size,
+                ask: if has_bid { Decimal::ONE - book.best_bid } else { Decimal::ONE },
+                ask_size: book.best_bid_size,
+            },
+            _ => Self {
+                bid: if has_bid { book.best_bid } else { Decimal::ZERO },
+                bid_size: book.best_bid_size,
+                ask: if has_ask { book.best_ask } else { Decimal::ONE },
+                ask_size: book.best_ask_size,
+            },
+        }
+    }
+}
+
+/// A limit BUY working on the book. Quotes are always buys: a YES bid, or a
+/// NO bid at `1 - ask` standing in for a YES ask (the ledger only holds longs).
+#[derive(Debug, Clone)]
+pub struct RestingOrder {
+    /// Venue order id (empty until the venue accepts it)
+    pub id: String,
+    pub market_id: String,
+    pub question: String,
+    /// Token bought (empty when the market lists none for the side, as in replays)
+    pub token_id: String,
+    pub direction: Direction,
+    /// Limit price of the token bought
+    pub price: Decimal,
+    /// Shares still working
+    pub shares: Decimal,
+    /// Shares filled so far
+    pub filled: Decimal,
+    pub placed_at: DateTime<Utc>,
+    pub tif: TimeInForce,
+    /// GTD only: the order is withdrawn from this time on
+    pub expires_at: Option<DateTime<Utc>>,
+    /// Paper queue position: shares resting at our price ahead of us
+    /// (None = our level has not been seen at the top of the book yet)
+    pub queue_ahead: Option<Decimal>,
+    /// Paper queue position: size of our level when last seen at the top
+    pub level_size: Decimal,
+    /// P(YES) the quote was built around; becomes the fills' `fair_value`
+    pub fair_value: Decimal,
+    /// Copied onto the trades this order's fills open
+    pub trade_mode: Option<String>,
+    pub strategy: Option<String>,
+    pub category: Option<String>,
+    /// Trade the fills open, already carrying the strategy's trail (exit levels,
+    /// agent verdicts); price, size and fees are replaced with the fill's
+    pub template: Option<Box<Trade>>,
+}
+
+impl RestingOrder {
+    /// Unplaced GTC buy of `shares` of the `direction` token of a binary market
+    pub fn new(market: &Market, direction: Direction, price: Decimal, shares: Decimal, fair_value: Decimal) -> Self {
+        Self {
+            id: String::new(),
+            market_id: market.id.clone(),
+            question: market.question.clone(),
+            token_id: market.side_token(direction).map(|t| t.token_id.clone()).unwrap_or_default(),
+            direction,
+            price,
+            shares,
+            filled: Decimal::ZERO,
+            placed_at: Utc::now(),
+            tif: TimeInForce::Gtc,
+            expires_at: None,
+            queue_ahead: None,
+            level_size: Decimal::ZERO,
+            fair_value,
+            trade_mode: None,
+            strategy: None,
+            category: Some(market.category.clone()),
+            template: None,
+        }
+    }
+
+    /// Cash the order locks while it works
+    pub fn cost(&self) -> Decimal {
+        self.price * self.shares
+    }
+
+    pub fn is_expired(&self, at: DateTime<Utc>) -> bool {
+        self.tif == TimeInForce::Gtd && self.expires_at.is_some_and(|t| at >= t)
+    }
+
+    /// Price and size this order takes on arrival: everything offered at or below
+    /// its limit, approximated by the best ask level (unknown size = all of it)
+    pub fn marketable(&self, book: &OrderBookSummary) -> Option<(Decimal, Decimal)> {
+        let touch = Touch::of(self.direction, book);
+        if touch.ask >= Decimal::ONE || touch.ask > self.price {
+            return None;
+        }
+        let size = if touch.ask_size > Decimal::ZERO { touch.ask_size.min(self.shares) } else { self.shares };
+        Some((touch.ask, size))
+    }
+
+    /// Where a freshly rested order joins the queue: behind everything already
+    /// bid at its price when that is the best bid, first in line when it improves
+    /// the bid, unknown when it sits below the touch
+    pub fn join_queue(&mut self, book: &OrderBookSummary) {
+        let touch = Touch::of(self.direction, book);
+        if touch.bid_size <= Decimal::ZERO && touch.bid > Decimal::ZERO {
+            return; // no size data: fills come from prices alone
+        }
+        if self.price > touch.bid {
+            self.queue_ahead = Some(Decimal::ZERO);
+            self.level_size = Decimal::ZERO;
+        } else if self.price == touch.bid {
+            self.queue_ahead = Some(touch.bid_size);
+            self.level_size = touch.bid_size;
+        }
+    }
+
+    /// Shares this order fills at its limit price given a new look at the book.
+    /// An ask at or through the limit fills it (up to the ask size at the touch,
+    /// in full when the book trades through). Otherwise size leaving our level is
+    /// taken as volume traded there: it works off the queue ahead of us first and
+    /// only the excess reaches us. Cancellations look like trades here, so queue
+    /// fills are an optimistic estimate; without size data only prices fill.
+    pub fn observe(&mut self, book: &OrderBookSummary) -> Decimal {
+        let touch = Touch::of(self.direction, book);
+
+        if touch.ask < Decimal::ONE && touch.ask <= self.price {
+            self.queue_ahead = Some(Decimal::ZERO);
+            self.level_size = Decimal::ZERO;
+            if touch.ask < self.price || touch.ask_size <= Decimal::ZERO {
+                return self.shares;
+            }
+            return touch.ask_size.min(self.shares);
+        }
+
+        if touch.bid_size <= Decimal::ZERO && touch.bid > Decimal::ZERO {
+            return Decimal::ZERO;
+        }
+        let level_now = if touch.bid == self.price {
+            touch.bid_size
+        } else if touch.bid < self.price {
+            Decimal::ZERO // our level emptied out (we would be the best bid)
+        } else {
+            return Decimal::ZERO; // behind the touch: our level is not visible
+        };
+
+        let Some(ahead) = self.queue_ahead else {
+            // First sighting of our level: everyone there is assumed ahead of us
+            self.queue_ahead = Some(level_now);
+            self.level_size = level_now;
+            return Decimal::ZERO;
+        };
+        let traded = (self.level_size - level_now).max(Decimal::ZERO);
+        self.queue_ahead = Some((ahead - traded).max(Decimal::ZERO));
+        self.level_size = level_now;
+        (traded - ahead).max(Decimal::ZERO).min(self.shares)
+    }
+
+    /// Trade opened by filling `shares` at `price` at time `at`: the template with
+    /// the fill's numbers, exit levels moved with the entry price and max-hold with
+    /// the fill time, or a bare trade carrying this order's attribution
+    pub fn fill_trade(&self, shares: Decimal, price: Decimal, fee: Decimal, at: DateTime<Utc>) -> Trade {
+        let bet_size = price * shares;
+        let mut trade = match &self.template {
+            Some(template) => {
+                let mut t = (**template).clone();
+                if template.entry_price > Decimal::ZERO {
+                    let scale = price / template.entry_price;
+                    t.take_profit = template.take_profit.map(|p| p * scale);
+                    t.stop_loss = template.stop_loss.map(|p| p * scale);
+                }
+                t.max_hold_until = template.max_hold_until.map(|m| m + (at - template.timestamp));
+                t
+            }
+            None => Trade {
+                id: String::new(),
+                timestamp: at,
+                market_id: self.market_id.clone(),
+                question: self.question.clone(),
+                direction: self.direction,
+                entry_price: price,
+                fair_value: self.fair_value,
+                edge: Decimal::ZERO,
+                bet_size,
+                shares,
+                status: TradeStatus::Open,
+                exit_price: None,
+                pnl: Decimal::ZERO,
+                balance_after: Decimal::ZERO,
+                order_id: None,
+                trade_mode: self.trade_mode.clone(),
+                take_profit: None,
+                stop_loss: None,
+                max_hold_until: None,
+                category: self.category.clone(),
+                specialist_desk: None,
+                bull_probability: None,
+                bear_probability: None,
+                judge_fair_value: None,
+                judge_confidence: None,
+                judge_model: None,
+                calibrated_fair_value: None,
+                calibrated_confidence: None,
+                exit_reason: None,
+                hold_duration_hours: None,
+                token_id: None,
+                outcome: None,
+                strategy: self.strategy.clone(),
+                raw_entry_price: None,
+                raw_exit_price: None,
+                entry_gas_fee: Decimal::ZERO,
+                exit_gas_fee: Decimal::ZERO,
+                entry_slippage: Decimal::ZERO,
+                exit_slippage: Decimal::ZERO,
+                platform_fee: Decimal::ZERO,
+                maker_taker_fee: Decimal::ZERO,
+            },
+        };
+
+        let fair_token = match self.direction {
+            Direction::No => Decimal::ONE - self.fair_value,
+            _ => self.fair_value,
+        };
+        trade.id = uuid::Uuid::new_v4().to_string()[..8].to_string();
+        trade.timestamp = at;
+        trade.entry_price = price;
+        trade.raw_entry_price = Some(price);
+        trade.edge = fair_token - price;
+        trade.bet_size = bet_size;
+        trade.shares = shares;
+        trade.order_id = Some(self.id.clone());
+        trade.maker_taker_fee = fee;
+        if !self.token_id.is_empty() {
+            trade.token_id = Some(self.token_id.clone());
+        }
+        trade
+    }
+}
@@ -1,9 +1,16 @@
 use crate::config::Config;
+use crate::db::StateStore;
+use crate::paper::{RestingOrder, TimeInForce};
 use crate::types::{Direction, ExitReason, Market, Trade, TradeStatus};
-use chrono::Utc;
+use crate::live::clob::OrderBookSummary;
+use crate::venue::{ExecutionVenue, Placed};
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use rand::Rng;
 use rust_decimal::Decimal;
 use rust_decimal::prelude::*;
+use std::collections::HashMap;
 use std::sync::Mutex;
 use tracing::{info, warn};
 
@@ -16,7 +23,6 @@ pub struct SimConfig {
     pub gas_fee_min: Decimal,
     pub gas_fee_max: Decimal,
     pub platform_fee_pct: Decimal,
-    #[allow(dead_code)]
     pub maker_fee_pct: Decimal,
     pub taker_fee_pct: Decimal,
     pub base_slippage_pct: Decimal,
@@ -52,7 +58,6 @@ impl SimConfig {
         }
     }
 
-    #[allow(dead_code)]
     pub fn disabled() -> Self {
         Self {
             fees_enabled: false,
@@ -142,6 +147,7 @@ fn simulate_fill(sim: &SimConfig, bet_size: Decimal, volume: Decimal) -> Option<
 
 pub struct Portfolio {
     inner: Mutex<PortfolioInner>,
+    sim: SimConfig,
 }
 
 struct PortfolioInner {
@@ -156,10 +162,16 @@ struct PortfolioInner {
     max_drawdown: Decimal,
     start_time: chrono::DateTime<Utc>,
     consecutive_losses: u32,
+    /// Limit orders working on the book. Saved to `resting_orders`; on restart they are
+    /// put back with `restore_resting` to book late fills, then cancelled.
+    resting: Vec<RestingOrder>,
+    /// Trades changed where the caller can't see it (remainders of partial exits,
+    /// fills booked while cancelling), not yet handed back for saving
+    unsaved: Vec<Trade>,
 }
 
 impl Portfolio {
-    pub fn new(initial_balance: Decimal) -> Self {
+    pub fn new(initial_balance: Decimal, sim: SimConfig) -> Self {
         Self {
             inner: Mutex::new(PortfolioInner {
                 balance: initial_balance,
@@ -173,10 +185,91 @@ impl Portfolio {
                 max_drawdown: Decimal::ZERO,
                 start_time: Utc::now(),
                 consecutive_losses: 0,
+                resting: Vec::new(),
+                unsaved: Vec::new(),
             }),
+            sim,
         }
     }
 
+    /// Rebuild balance, open positions, win/loss counters, drawdown and loss streak
+    /// from the `trades` table (and AI spend from `llm_usage`) so a restart continues
+    /// the running experiment. Returns the number of trades restored (0 = fresh start).
+    pub fn restore_from_store(&self, store: &StateStore) -> Result<usize> {
+        self.inner.lock().unwrap().total_api_cost = store.total_llm_cost()?;
+
+        let trades = store.load_trades()?;
+        if trades.is_empty() {
+            return Ok(0);
+        }
+
+        let mut inner = self.inner.lock().unwrap();
+
+        // Last saved row holds the most recent balance (open deducts, close returns)
+        if let Some(last) = trades.last() {
+            inner.balance = last.balance_after;
+        }
+
+        inner.open_trades = trades
+            .iter()
+            .filter(|t| t.status == TradeStatus::Open)
+            .cloned()
+            .collect();
+
+        // Replay closed trades in close order for streak + drawdown
+        let mut closed: Vec<&Trade> = trades
+            .iter()
+            .filter(|t| t.status == TradeStatus::Won || t.status == TradeStatus::Lost)
+            .filter(|t| !is_hedged_leg(t))
+            .collect();
+        closed.sort_by_key(|t| {
+            let held_mins = (t.hold_duration_hours.unwrap_or(0.0) * 60.0) as i64;
+            t.timestamp + chrono::Duration::minutes(held_mins)
+        });
+
+        inner.win_count = closed.iter().filter(|t| t.status == TradeStatus::Won).count() as u32;
+        inner.loss_count = closed.iter().filter(|t| t.status == TradeStatus::Lost).count() as u32;
+        inner.consecutive_losses = closed
+            .iter()
+            .rev()
+            .take_while(|t| t.status == TradeStatus::Lost)
+            .count() as u32;
+
+        let mut peak = inner.initial_balance;
+        let mut max_dd = Decimal::ZERO;
+        for bal in closed.iter().map(|t| t.balance_after).chain(std::iter::once(inner.balance)) {
+            if bal > peak {
+                peak = bal;
+            }
+            if peak > Decimal::ZERO {
+                let dd = (peak - bal) / peak;
+                if dd > max_dd {
+                    max_dd = dd;
+                }
+            }
+        }
+        inner.peak_balance = peak;
+        inner.max_drawdown = max_dd;
+
+        if let Some(first) = trades.iter().map(|t| t.timestamp).min() {
+            inner.start_time = first;
+        }
+
+        info!(
+            "Portfolio restored: {} trades ({} open, W:{} L:{}) | balance ${} | peak ${} | loss streak {}",
+            trades.len(),
+            inner.open_trades.len(),
+            inner.win_count,
+            inner.loss_count,
+            inner.balance,
+            inner.peak_balance,
+            inner.consecutive_losses,
+        );
+
+        inner.trades = trades;
+        Ok(inner.trades.len())
+    }
+
     pub fn balance(&self) -> Decimal {
         self.inner.lock().unwrap().balance
     }
@@ -193,6 +286,171 @@ impl Portfolio {
         self.inner.lock().unwrap().open_trades.len()
     }
 
+    /// Open positions that express a view (arbitrage legs and market-making fills are hedged books)
+    pub fn directional_position_count(&self) -> usize {
+        self.inner.lock().unwrap().open_trades.iter().filter(|t| !is_hedged_leg(t)).count()
+    }
+
+    /// Cash not locked by resting orders
+    pub fn available_balance(&self) -> Decimal {
+        let inner = self.inner.lock().unwrap();
+        inner.balance - inner.resting.iter().map(|o| o.cost()).sum::<Decimal>()
+    }
+
+    /// Limit orders still working
+    pub fn resting_orders(&self) -> Vec<RestingOrder> {
+        self.inner.lock().unwrap().resting.clone()
+    }
+
+    /// Book a limit order the exchange accepted as working (paper orders go through
+    /// `submit_limit`). None = not enough unlocked cash.
+    pub fn rest_order(&self, mut order: RestingOrder) -> Option<RestingOrder> {
+        let mut inner = self.inner.lock().unwrap();
+        let locked: Decimal = inner.resting.iter().map(|o| o.cost()).sum();
+        if order.shares <= Decimal::ZERO || order.cost() > inner.balance - locked {
+            return None;
+        }
+        if order.id.is_empty() {
+            order.id = uuid::Uuid::new_v4().to_string()[..8].to_string();
+        }
+        inner.resting.push(order.clone());
+        Some(order)
+    }
+
+    /// Give a booked order the id the venue assigned it once posted
+    pub fn confirm_resting(&self, order_id: &str, venue_id: &str) -> Option<RestingOrder> {
+        let mut inner = self.inner.lock().unwrap();
+        let order = inner.resting.iter_mut().find(|o| o.id == order_id)?;
+        order.id = venue_id.to_string();
+        Some(order.clone())
+    }
+
+    /// Put back an order a previous run left working. No cash check: the venue
+    /// already holds it, and the capital spent on its earlier fills is in the trades.
+    pub fn restore_resting(&self, order: RestingOrder) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.resting.retain(|o| o.id != order.id);
+        inner.resting.push(order);
+    }
+
+    /// Drop a working order; returns what was left of it
+    pub fn cancel_resting(&self, order_id: &str) -> Option<RestingOrder> {
+        let mut inner = self.inner.lock().unwrap();
+        let pos = inner.resting.iter().position(|o| o.id == order_id)?;
+        Some(inner.resting.remove(pos))
+    }
+
+    /// Book `shares` of a resting order as filled at its limit price (maker fill:
+    /// no slippage, maker fee only). The order stays working until fully filled.
+    pub fn fill_resting(&self, order_id: &str, shares: Decimal, at: DateTime<Utc>) -> Option<Trade> {
+        let mut inner = self.inner.lock().unwrap();
+        let pos = inner.resting.iter().position(|o| o.id == order_id)?;
+        let order = &mut inner.resting[pos];
+        let shares = shares.min(order.shares);
+        if shares <= Decimal::ZERO {
+            return None;
+        }
+        order.shares -= shares;
+        order.filled += shares;
+        let order = if order.shares <= Decimal::ZERO {
+            inner.resting.remove(pos)
+        } else {
+            order.clone()
+        };
+
+        let maker_fee = if self.sim.fees_enabled { order.price * shares * self.sim.maker_fee_pct } else { Decimal::ZERO };
+        let trade = order.fill_trade(shares, order.price, maker_fee, at);
+        Some(book_fill(&mut inner, trade))
+    }
+
+    /// Paper order entry, mirroring the CLOB: the part of the order the book offers
+    /// at or below its limit fills at once as a taker (at the ask, taker fee and gas);
+    /// a FOK that cannot fill in full is killed, an IOC drops what is left, and a
+    /// GTC/GTD remainder rests in the queue until the book reaches it.
+    /// None = not enough unlocked cash, killed, or nothing filled for an IOC.
+    pub fn submit_limit(&self, mut order: RestingOrder, book: &OrderBookSummary, at: DateTime<Utc>) -> Option<Placed> {
+        if order.shares <= Decimal::ZERO || order.cost() > self.available_balance() {
+            return None;
+        }
+        if order.id.is_empty() {
+            order.id = uuid::Uuid::new_v4().to_string()[..8].to_string();
+        }
+        order.placed_at = at;
+
+        let mut fill = None;
+        if let Some((price, shares)) = order.marketable(book) {
+            if order.tif == TimeInForce::Fok && shares < order.shares {
+                info!("SIM FOK KILLED: {} of {} shares @ {} | {}", shares, order.shares, order.price,
+                    &order.question[..order.question.len().min(40)]);
+                return None;
+            }
+            let (gas, taker_fee) = if self.sim.fees_enabled {
+                (random_gas_fee(self.sim.gas_fee_min, self.sim.gas_fee_max), price * shares * self.sim.taker_fee_pct)
+            } else {
+                (Decimal::ZERO, Decimal::ZERO)
+            };
+            let mut trade = order.fill_trade(shares, price, taker_fee, at);
+            trade.entry_gas_fee = gas;
+            order.shares -= shares;
+            order.filled += shares;
+            info!("SIM TAKER FILL: {} {} @ {} ({}) | {}", trade.direction, shares, price, order.tif,
+                &trade.question[..trade.question.len().min(40)]);
+            fill = Some(book_fill(&mut self.inner.lock().unwrap(), trade));
+        }
+
+        let resting = !order.tif.is_immediate() && order.shares > Decimal::ZERO;
+        if resting {
+            order.join_queue(book);
+            self.inner.lock().unwrap().resting.push(order.clone());
+        } else if fill.is_none() {
+            return None;
+        }
+        Some(Placed { order, fill, resting })
+    }
+
+    /// Paper fill simulation for one market: expire lapsed GTD orders, then fill
+    /// what a fresh look at its YES book reaches (see `RestingOrder::observe`)
+    pub fn match_resting(&self, market_id: &str, book: &OrderBookSummary, at: DateTime<Utc>) -> Vec<Trade> {
+        self.expire_resting(at);
+        let due: Vec<(String, Decimal)> = {
+            let mut inner = self.inner.lock().unwrap();
+            inner
+                .resting
+                .iter_mut()
+                .filter(|o| o.market_id == market_id)
+                .map(|o| (o.id.clone(), o.observe(book)))
+                .filter(|(_, shares)| *shares > Decimal::ZERO)
+                .collect()
+        };
+
+        let mut fills = Vec::new();
+        for (order_id, shares) in due {
+            if let Some(trade) = self.fill_resting(&order_id, shares, at) {
+                info!(
+                    "SIM MAKER FILL: {} {} @ {} | {}",
+                    trade.direction,
+                    trade.shares,
+                    trade.entry_price,
+                    &trade.question[..trade.question.len().min(40)]
+                );
+                fills.push(trade);
+            }
+        }
+        fills
+    }
+
+    /// Withdraw GTD orders whose expiry has passed; returns them
+    pub fn expire_resting(&self, at: DateTime<Utc>) -> Vec<RestingOrder> {
+        let mut inner = self.inner.lock().unwrap();
+        let (expired, working): (Vec<_>, Vec<_>) = std::mem::take(&mut inner.resting).into_iter().partition(|o| o.is_expired(at));
+        inner.resting = working;
+        for order in &expired {
+            info!("SIM ORDER EXPIRED: {} {} @ {} | {}", order.direction, order.shares, order.price,
+                &order.question[..order.question.len().min(40)]);
+        }
+        expired
+    }
+
     pub fn execute_trade(
         &self,
         market_id: &str,
@@ -202,13 +460,15 @@ impl Portfolio {
         fair_value: Decimal,
         edge: Decimal,
         mut bet_size: Decimal,
-        sim: &SimConfig,
         market_volume: Decimal,
     ) -> Option<Trade> {
+        let sim = &self.sim;
         let mut inner = self.inner.lock().unwrap();
 
-        if bet_size > inner.balance {
-            bet_size = inner.balance;
+        // Cash locked by resting orders is spoken for
+        let available = inner.balance - inner.resting.iter().map(|o| o.cost()).sum::<Decimal>();
+        if bet_size > available {
+            bet_size = available;
         }
         if bet_size <= Decimal::ZERO {
             return None;
@@ -281,8 +541,8 @@ impl Portfolio {
 
         // Total deduction = bet_size + gas + maker/taker
         let total_deduction = bet_size + gas_fee + maker_taker;
-        if total_deduction > inner.balance {
-            bet_size = (inner.balance - gas_fee - maker_taker).max(Decimal::ZERO);
+        if total_deduction > available {
+            bet_size = (available - gas_fee - maker_taker).max(Decimal::ZERO);
             if bet_size <= Decimal::ZERO {
                 return None;
             }
@@ -317,9 +577,13 @@ impl Portfolio {
             judge_fair_value: None,
             judge_confidence: None,
             judge_model: None,
+            calibrated_fair_value: None,
+            calibrated_confidence: None,
             exit_reason: None,
             hold_duration_hours: None,
             token_id: None,
+            outcome: None,
+            strategy: None,
             raw_entry_price: Some(raw_price),
             raw_exit_price: None,
             entry_gas_fee: gas_fee,
@@ -342,316 +606,105 @@ impl Portfolio {
         Some(trade)
     }
 
-    /// Resolve open trades using real market prices with mode-based exit logic.
-    /// Supports Scalp (TP/SL price levels), Swing (50% edge captured), Conviction (hold to resolution + safety valve).
-    /// Returns a vec of trades that were closed this cycle (for DB persistence).
-    pub fn resolve_with_prices(
-        &self,
-        markets: &[Market],
-        exit_tp_pct: Decimal,
-        exit_sl_pct: Decimal,
-        sim: &SimConfig,
-    ) -> Vec<Trade> {
-        let mut inner = self.inner.lock().unwrap();
-        let mut resolved = Vec::new();
-
-        let pending: Vec<Trade> = inner.open_trades.drain(..).collect();
-        let mut still_open = Vec::new();
-
-        for mut trade in pending {
-            if trade.direction == Direction::Skip {
-                continue;
-            }
-
-            let market_opt = markets.iter().find(|m| m.id == trade.market_id);
-
-            let mut exit_reason: Option<ExitReason> = None;
-            let current_price;
-
-            if let Some(market) = market_opt {
-                let yes_price = market.yes_price;
-                current_price = match trade.direction {
-                    Direction::Yes => yes_price,
-                    Direction::No => Decimal::ONE - yes_price,
-                    Direction::Skip => unreachable!(),
-                };
-
-                // Mode-based exit logic (uses RAW market price for trigger decisions)
-                let mode = trade.trade_mode.as_deref().unwrap_or("SWING");
-
-                match mode {
-                    "SCALP" => {
-                        if let Some(tp) = trade.take_profit {
-                            if current_price >= tp {
-                                exit_reason = Some(ExitReason::TakeProfit);
-                            }
-                        }
-                        if exit_reason.is_none() {
-                            if let Some(sl) = trade.stop_loss {
-                                if current_price <= sl {
-                                    exit_reason = Some(ExitReason::StopLoss);
-                                }
-                            }
-                        }
-                        if exit_reason.is_none() {
-                            if let Some(max_hold) = trade.max_hold_until {
-                                if Utc::now() > max_hold {
-                                    exit_reason = Some(ExitReason::TimeExpiry);
-                                }
-                            }
-                        }
-                    }
-                    "SWING" => {
-                        if let Some(tp) = trade.take_profit {
-                            if current_price >= tp {
-                                exit_reason = Some(ExitReason::TakeProfit);
-                            }
-                        }
-                        if exit_reason.is_none() {
-                            if let Some(jfv) = trade.judge_fair_value {
-                                let fair_dec = Decimal::from_f64(jfv).unwrap_or(trade.fair_value);
-                                let total_edge = fair_dec - trade.entry_price;
-                                if total_edge.abs() > Decimal::ZERO {
-                                    let captured = (current_price - trade.entry_price) / total_edge;
-                                    if captured >= Decimal::new(60, 2) {
-                                        exit_reason = Some(ExitReason::EdgeCaptured);
-                                    }
-                                }
-                            }
-                        }
-                        if exit_reason.is_none() {
-                            if let Some(sl) = trade.stop_loss {
-                                if current_price <= sl {
-                                    exit_reason = Some(ExitReason::StopLoss);
-                                }
-                            }
-                        }
-                        if exit_reason.is_none() {
-                            if let Some(max_hold) = trade.max_hold_until {
-                                if Utc::now() > max_hold {
-                                    exit_reason = Some(ExitReason::TimeExpiry);
-                                }
-                            }
-                        }
-                    }
-                    "CONVICTION" => {
-                        let unrealized_pnl = (current_price - trade.entry_price) * trade.shares;
-                        let pnl_pct = if trade.bet_size > Decimal::ZERO {
-                            (unrealized_pnl / trade.bet_size * Decimal::from(100)).to_f64().unwrap_or(0.0)
-                        } else { 0.0 };
-                        let conf = trade.judge_confidence.unwrap_or(0.5);
-                        if pnl_pct < -30.0 && conf < 0.70 {
-                            exit_reason = Some(ExitReason::SafetyValve);
-                        }
-                    }
-                    _ => {
-                        let unrealized_pnl = (current_price - trade.entry_price) * trade.shares;
-                        let change_pct = if trade.bet_size > Decimal::ZERO {
-                            unrealized_pnl / trade.bet_size
-                        } else { Decimal::ZERO };
-
-                        if exit_tp_pct > Decimal::ZERO && change_pct >= exit_tp_pct {
-                            exit_reason = Some(ExitReason::TakeProfit);
-                        } else if exit_sl_pct > Decimal::ZERO && change_pct <= -exit_sl_pct {
-                            exit_reason = Some(ExitReason::StopLoss);
-                        }
-                    }
-                }
-            } else {
-                current_price = trade.entry_price;
-                exit_reason = Some(ExitReason::MarketResolved);
-            }
-
-            if let Some(reason) = exit_reason {
-                // Store raw exit price
-                trade.raw_exit_price = Some(current_price);
-
-                // Sim: exit slippage (selling = worse fill)
-                let exit_slippage_pct = if sim.slippage_enabled {
-                    let spread = Decimal::new(3, 2); // estimate spread at exit
-                    calculate_slippage_pct(sim, trade.bet_size, spread)
-                } else {
-                    Decimal::ZERO
-                };
-
-                // Actual exit price with slippage (selling = lower price)
-                let actual_exit_price = current_price * (Decimal::ONE - exit_slippage_pct);
-                let exit_slippage_cost = (current_price - actual_exit_price).abs() * trade.shares;
-
-                // Gross PnL (using adjusted entry from execute_trade and slippage-adjusted exit)
-                let gross_pnl = (actual_exit_price - trade.entry_price) * trade.shares;
-
-                // Sim: exit gas fee
-                let exit_gas = if sim.fees_enabled {
-                    random_gas_fee(sim.gas_fee_min, sim.gas_fee_max)
-                } else {
-                    Decimal::ZERO
-                };
-
-                // Sim: exit maker/taker fee
-                let exit_value = (actual_exit_price * trade.shares).abs();
-                let exit_maker_taker = if sim.fees_enabled {
-                    exit_value * sim.taker_fee_pct
-                } else {
-                    Decimal::ZERO
-                };
-
-                // Sim: platform fee (only on profitable trades)
-                let plat_fee = if sim.fees_enabled && gross_pnl > Decimal::ZERO {
-                    gross_pnl * sim.platform_fee_pct
-                } else {
-                    Decimal::ZERO
-                };
-
-                // Store simulation tracking
-                trade.exit_slippage = exit_slippage_cost;
-                trade.exit_gas_fee = exit_gas;
-                trade.platform_fee = plat_fee;
-                // Accumulate exit maker/taker into total
-                trade.maker_taker_fee = trade.maker_taker_fee + exit_maker_taker;
-
-                trade.exit_price = Some(actual_exit_price);
-                trade.pnl = gross_pnl;
-                trade.exit_reason = Some(reason);
-                let hold_hours = (Utc::now() - trade.timestamp).num_minutes() as f64 / 60.0;
-                trade.hold_duration_hours = Some(hold_hours);
-
-                // Win/loss based on gross PnL (trade quality)
-                if gross_pnl > Decimal::ZERO {
-                    trade.status = TradeStatus::Won;
-                    inner.win_count += 1;
-                    inner.consecutive_losses = 0;
-                } else {
-                    trade.status = TradeStatus::Lost;
-                    inner.loss_count += 1;
-                    inner.consecutive_losses += 1;
-                }
-
-                // Return capital: bet_size + gross_pnl - exit_fees
-                let return_amount = trade.bet_size + gross_pnl - exit_gas - exit_maker_taker - plat_fee;
-                inner.balance += if return_amount > Decimal::ZERO { return_amount } else { Decimal::ZERO };
-                trade.balance_after = inner.balance;
-
-                if let Some(t) = inner.trades.iter_mut().find(|t| t.id == trade.id) {
-                    *t = trade.clone();
-                }
-
-                let total_fees = trade.entry_gas_fee + exit_gas + trade.entry_slippage + exit_slippage_cost + plat_fee + trade.maker_taker_fee;
-                let net_pnl = gross_pnl - total_fees + trade.entry_gas_fee; // entry gas already deducted from balance
-                info!("CLOSED [{}]: {} {} | PnL ${} (net ${}) | Fees ${} | Reason: {} | Held {:.1}h",
-                    trade.trade_mode.as_deref().unwrap_or("?"),
-                    trade.direction,
-                    &trade.question[..trade.question.len().min(35)],
-                    gross_pnl.round_dp(4), net_pnl.round_dp(4), total_fees.round_dp(4),
-                    reason, hold_hours);
-
-                resolved.push(trade);
-            } else {
-                still_open.push(trade);
-            }
-        }
-
-        inner.open_trades = still_open;
+    /// Record a position filled outside the simulator (live fills at a known price and size)
+    pub fn record_open(&self, trade: Trade) {
+        book_fill(&mut self.inner.lock().unwrap(), trade);
+    }
 
-        if inner.balance > inner.peak_balance {
-            inner.peak_balance = inner.balance;
+    /// Replace the stored copy of a trade (executor adds mode/TP/SL and the agent trail after the fill)
+    pub fn update_trade(&self, trade: &Trade) {
+        let mut inner = self.inner.lock().unwrap();
+        if let Some(t) = inner.open_trades.iter_mut().find(|t| t.id == trade.id) {
+            *t = trade.clone();
         }
-        if inner.peak_balance > Decimal::ZERO {
-            let dd = (inner.peak_balance - inner.balance) / inner.peak_balance;
-            if dd > inner.max_drawdown {
-                inner.max_drawdown = dd;
-            }
+        if let Some(t) = inner.trades.iter_mut().find(|t| t.id == trade.id) {
+            *t = trade.clone();
         }
+    }
 
-        resolved
+    /// Close one open trade at `current_price` (raw price of the held side).
+    /// Exit slippage and fees come from this portfolio's SimConfig.
+    pub fn close_trade(&self, trade_id: &str, current_price: Decimal, reason: ExitReason) -> Option<Trade> {
+        self.close_trade_at(trade_id, current_price, reason, Utc::now())
     }
 
-    /// Close all open positions at current prices (for graceful shutdown)
-    pub fn close_all_positions(&self, markets: &[Market], sim: &SimConfig) -> Vec<Trade> {
+    /// `close_trade` with hold duration measured to `at` (backtest clock)
+    pub fn close_trade_at(
+        &self,
+        trade_id: &str,
+        current_price: Decimal,
+        reason: ExitReason,
+        at: DateTime<Utc>,
+    ) -> Option<Trade> {
         let mut inner = self.inner.lock().unwrap();
-        let mut closed = Vec::new();
-
-        let pending: Vec<Trade> = inner.open_trades.drain(..).collect();
-
-        for mut trade in pending {
-            let market_opt = markets.iter().find(|m| m.id == trade.market_id);
-            let current_price = if let Some(market) = market_opt {
-                match trade.direction {
-                    Direction::Yes => market.yes_price,
-                    Direction::No => Decimal::ONE - market.yes_price,
-                    Direction::Skip => trade.entry_price,
-                }
-            } else {
-                trade.entry_price
-            };
-
-            trade.raw_exit_price = Some(current_price);
-
-            // Sim: exit slippage
-            let exit_slippage_pct = if sim.slippage_enabled {
-                let spread = Decimal::new(3, 2);
-                calculate_slippage_pct(sim, trade.bet_size, spread)
-            } else {
-                Decimal::ZERO
-            };
-            let actual_exit_price = current_price * (Decimal::ONE - exit_slippage_pct);
-            let exit_slippage_cost = (current_price - actual_exit_price).abs() * trade.shares;
-
-            let gross_pnl = (actual_exit_price - trade.entry_price) * trade.shares;
-            let hold_hours = (Utc::now() - trade.timestamp).num_minutes() as f64 / 60.0;
-
-            // Sim: exit fees
-            let exit_gas = if sim.fees_enabled {
-                random_gas_fee(sim.gas_fee_min, sim.gas_fee_max)
-            } else {
-                Decimal::ZERO
-            };
-            let exit_value = (actual_exit_price * trade.shares).abs();
-            let exit_maker_taker = if sim.fees_enabled {
-                exit_value * sim.taker_fee_pct
-            } else {
-                Decimal::ZERO
-            };
-            let plat_fee = if sim.fees_enabled && gross_pnl > Decimal::ZERO {
-                gross_pnl * sim.platform_fee_pct
-            } else {
-                Decimal::ZERO
-            };
-
-            trade.exit_slippage = exit_slippage_cost;
-            trade.exit_gas_fee = exit_gas;
-            trade.platform_fee = plat_fee;
-            trade.maker_taker_fee = trade.maker_taker_fee + exit_maker_taker;
-
-            trade.exit_price = Some(actual_exit_price);
-            trade.pnl = gross_pnl;
-            trade.exit_reason = Some(ExitReason::ManualStop);
-            trade.hold_duration_hours = Some(hold_hours);
-            trade.status = if gross_pnl > Decimal::ZERO { TradeStatus::Won } else { TradeStatus::Lost };
-
-            let return_amount = trade.bet_size + gross_pnl - exit_gas - exit_maker_taker - plat_fee;
-            inner.balance += if return_amount > Decimal::ZERO { return_amount } else { Decimal::ZERO };
-            trade.balance_after = inner.balance;
-
-            if gross_pnl > Decimal::ZERO {
-                inner.win_count += 1;
-                inner.consecutive_losses = 0;
-            } else {
-                inner.loss_count += 1;
-                inner.consecutive_losses += 1;
-            }
+        let pos = inner.open_trades.iter().position(|t| t.id == trade_id)?;
+        let trade = inner.open_trades.remove(pos);
+        Some(settle(&mut inner, trade, current_price, reason, at, &self.sim))
+    }
 
-            if let Some(t) = inner.trades.iter_mut().find(|t| t.id == trade.id) {
-                *t = trade.clone();
-            }
+    /// Close only `shares` of an open trade (a live exit the book matched in part).
+    /// The sold shares are booked as a closed trade of their own; the rest stays open
+    /// under the original id with its cost basis and entry fees scaled down, and is
+    /// queued for `take_unsaved`. Selling the whole position is a plain `close_trade`.
+    pub fn close_trade_part(
+        &self,
+        trade_id: &str,
+        shares: Decimal,
+        current_price: Decimal,
+        reason: ExitReason,
+    ) -> Option<Trade> {
+        let mut inner = self.inner.lock().unwrap();
+        let pos = inner.open_trades.iter().position(|t| t.id == trade_id)?;
+        if shares <= Decimal::ZERO {
+            return None;
+        }
+        if shares >= inner.open_trades[pos].shares {
+            let trade = inner.open_trades.remove(pos);
+            return Some(settle(&mut inner, trade, current_price, reason, Utc::now(), &self.sim));
+        }
 
-            closed.push(trade);
+        let rest = &mut inner.open_trades[pos];
+        let part = shares / rest.shares;
+        let mut sold = rest.clone();
+        sold.id = uuid::Uuid::new_v4().to_string()[..8].to_string();
+        sold.shares = shares;
+        sold.bet_size = rest.bet_size * part;
+        sold.entry_gas_fee = rest.entry_gas_fee * part;
+        sold.entry_slippage = rest.entry_slippage * part;
+        sold.maker_taker_fee = rest.maker_taker_fee * part;
+        rest.shares -= sold.shares;
+        rest.bet_size -= sold.bet_size;
+        rest.entry_gas_fee -= sold.entry_gas_fee;
+        rest.entry_slippage -= sold.entry_slippage;
+        rest.maker_taker_fee -= sold.maker_taker_fee;
+
+        inner.trades.push(sold.clone());
+        let closed = settle(&mut inner, sold, current_price, reason, Utc::now(), &self.sim);
+
+        // Saved after the closed part, so the latest row still carries the latest balance
+        let balance = inner.balance;
+        let mut rest = inner.open_trades[pos].clone();
+        rest.balance_after = balance;
+        inner.open_trades[pos] = rest.clone();
+        if let Some(t) = inner.trades.iter_mut().find(|t| t.id == trade_id) {
+            *t = rest.clone();
         }
+        inner.unsaved.retain(|t| t.id != trade_id);
+        inner.unsaved.push(rest);
+        Some(closed)
+    }
 
-        closed
+    /// Queue a trade booked outside the caller's view for the next `take_unsaved`
+    pub fn defer_save(&self, trade: Trade) {
+        self.inner.lock().unwrap().unsaved.push(trade);
+    }
+
+    /// Trades queued since the last call, to be saved after any closed trades the
+    /// same step returned (partial-exit remainders carry the latest balance)
+    pub fn take_unsaved(&self) -> Vec<Trade> {
+        std::mem::take(&mut self.inner.lock().unwrap().unsaved)
     }
 
-    #[allow(dead_code)]
     pub fn add_api_cost(&self, cost: Decimal) {
         self.inner.lock().unwrap().total_api_cost += cost;
     }
@@ -671,17 +724,17 @@ impl Portfolio {
         let locked_balance: Decimal = inner.open_trades.iter().map(|t| t.bet_size).sum();
         let mut unrealized_pnl = Decimal::ZERO;
         for trade in &inner.open_trades {
+            if trade.direction == Direction::Skip {
+                continue;
+            }
             if let Some(market) = markets.iter().find(|m| m.id == trade.market_id) {
-                let current_price = match trade.direction {
-                    Direction::Yes => market.yes_price,
-                    Direction::No => Decimal::ONE - market.yes_price,
-                    Direction::Skip => continue,
-                };
-                unrealized_pnl += (current_price - trade.entry_price) * trade.shares;
+                unrealized_pnl += (position_price(trade, market) - trade.entry_price) * trade.shares;
             }
         }
 
-        let total_pnl = realized_pnl + unrealized_pnl;
+        // Net of AI spend: the LLM bill is part of what the strategy costs to run
+        let trading_pnl = realized_pnl + unrealized_pnl;
+        let total_pnl = trading_pnl - inner.total_api_cost;
         let roi = if inner.initial_balance > Decimal::ZERO {
             (total_pnl / inner.initial_balance * Decimal::from(100)).round_dp(1)
         } else {
@@ -706,6 +759,7 @@ impl Portfolio {
             initial_balance: inner.initial_balance,
             realized_pnl,
             unrealized_pnl,
+            trading_pnl,
             total_pnl,
             roi,
             peak_balance: inner.peak_balance,
@@ -753,12 +807,317 @@ impl Portfolio {
     }
 }
 
+/// Paper venue: simulated fills against scanner prices
+#[async_trait]
+impl ExecutionVenue for Portfolio {
+    fn name(&self) -> &'static str {
+        "PAPER"
+    }
+
+    fn ledger(&self) -> &Portfolio {
+        self
+    }
+
+    async fn open_position(
+        &self,
+        market: &Market,
+        direction: Direction,
+        fair_value: Decimal,
+        edge: Decimal,
+        bet_size: Decimal,
+    ) -> Option<Trade> {
+        self.execute_trade(
+            &market.id,
+            &market.question,
+            direction,
+            market.yes_price,
+            fair_value,
+            edge,
+            bet_size,
+            market.volume,
+        )
+    }
+
+    async fn close_position(&self, trade: &Trade, current_price: Decimal, reason: ExitReason) -> Option<Trade> {
+        self.close_trade(&trade.id, current_price, reason)
+    }
+
+    async fn place_limit(&self, order: RestingOrder, book: &OrderBookSummary) -> Option<Placed> {
+        self.submit_limit(order, book, Utc::now())
+    }
+
+    async fn cancel_order(&self, order_id: &str) -> Option<RestingOrder> {
+        self.cancel_resting(order_id)
+    }
+
+    async fn sync_orders(&self, books: &HashMap<String, OrderBookSummary>) -> Vec<Trade> {
+        let now = Utc::now();
+        self.expire_resting(now);
+        books
+            .iter()
+            .flat_map(|(market_id, book)| self.match_resting(market_id, book, now))
+            .collect()
+    }
+}
+
+/// Current price of the side a trade holds (YES price, or 1 - YES for NO positions;
+/// the held outcome's token price in a categorical market)
+pub fn position_price(trade: &Trade, market: &Market) -> Decimal {
+    if let Some(outcome) = &trade.outcome {
+        return market.token_for(outcome).map_or(trade.entry_price, |t| t.price);
+    }
+    match trade.direction {
+        Direction::Yes => market.yes_price,
+        Direction::No => Decimal::ONE - market.yes_price,
+        Direction::Skip => trade.entry_price,
+    }
+}
+
+/// Arbitrage legs and market-making fills only pay off as a book: one leg's win
+/// or loss says nothing about the strategy, so they stay out of the win rate and
+/// the loss streak
+pub fn is_hedged_leg(trade: &Trade) -> bool {
+    matches!(
+        trade.trade_mode.as_deref(),
+        Some(crate::arbitrage::ARB_MODE) | Some(crate::market_making::MM_MODE)
+    )
+}
+
+/// Settlement price of a position in a closed market given the outcome that won:
+/// 1 for the winning outcome's holders, 0 for every other outcome (binary
+/// positions hold the "Yes" or "No" outcome of their direction)
+pub fn settlement_price(trade: &Trade, winner: &str) -> Decimal {
+    let held = trade.outcome.clone().unwrap_or_else(|| trade.direction.to_string());
+    if winner.eq_ignore_ascii_case(&held) { Decimal::ONE } else { Decimal::ZERO }
+}
+
+/// Mode-based exit decision (uses RAW market price for trigger decisions).
+/// Supports Scalp (TP/SL price levels), Swing (60% edge captured), Conviction (hold to resolution + safety valve),
+/// Arb and MM (hold to resolution).
+pub fn exit_signal(
+    trade: &Trade,
+    current_price: Decimal,
+    exit_tp_pct: Decimal,
+    exit_sl_pct: Decimal,
+    now: DateTime<Utc>,
+) -> Option<ExitReason> {
+    let mut exit_reason: Option<ExitReason> = None;
+    let mode = trade.trade_mode.as_deref().unwrap_or("SWING");
+
+    match mode {
+        "SCALP" => {
+            if let Some(tp) = trade.take_profit {
+                if current_price >= tp {
+                    exit_reason = Some(ExitReason::TakeProfit);
+                }
+            }
+            if exit_reason.is_none() {
+                if let Some(sl) = trade.stop_loss {
+                    if current_price <= sl {
+                        exit_reason = Some(ExitReason::StopLoss);
+                    }
+                }
+            }
+            if exit_reason.is_none() {
+                if let Some(max_hold) = trade.max_hold_until {
+                    if now > max_hold {
+                        exit_reason = Some(ExitReason::TimeExpiry);
+                    }
+                }
+            }
+        }
+        "SWING" => {
+            if let Some(tp) = trade.take_profit {
+                if current_price >= tp {
+                    exit_reason = Some(ExitReason::TakeProfit);
+                }
+            }
+            if exit_reason.is_none() {
+                // Measured against the calibrated fair value the position was sized on
+                if let Some(jfv) = trade.calibrated_fair_value.or(trade.judge_fair_value) {
+                    let fair_dec = Decimal::from_f64(jfv).unwrap_or(trade.fair_value);
+                    let total_edge = fair_dec - trade.entry_price;
+                    if total_edge.abs() > Decimal::ZERO {
+                        let captured = (current_price - trade.entry_price) / total_edge;
+                        if captured >= Decimal::new(60, 2) {
+                            exit_reason = Some(ExitReason::EdgeCaptured);
+                        }
+                    }
+                }
+            }
+            if exit_reason.is_none() {
+                if let Some(sl) = trade.stop_loss {
+                    if current_price <= sl {
+                        exit_reason = Some(ExitReason::StopLoss);
+                    }
+                }
+            }
+            if exit_reason.is_none() {
+                if let Some(max_hold) = trade.max_hold_until {
+                    if now > max_hold {
+                        exit_reason = Some(ExitReason::TimeExpiry);
+                    }
+                }
+            }
+        }
+        // Arbitrage legs only pay off as a set, and market-making inventory is
+        // worked off by quoting the other side: hold both to resolution
+        "ARB" | "MM" => {}
+        "CONVICTION" => {
+            let unrealized_pnl = (current_price - trade.entry_price) * trade.shares;
+            let pnl_pct = if trade.bet_size > Decimal::ZERO {
+                (unrealized_pnl / trade.bet_size * Decimal::from(100)).to_f64().unwrap_or(0.0)
+            } else { 0.0 };
+            let conf = trade.calibrated_confidence.or(trade.judge_confidence).unwrap_or(0.5);
+            if pnl_pct < -30.0 && conf < 0.70 {
+                exit_reason = Some(ExitReason::SafetyValve);
+            }
+        }
+        _ => {
+            let unrealized_pnl = (current_price - trade.entry_price) * trade.shares;
+            let change_pct = if trade.bet_size > Decimal::ZERO {
+                unrealized_pnl / trade.bet_size
+            } else { Decimal::ZERO };
+
+            if exit_tp_pct > Decimal::ZERO && change_pct >= exit_tp_pct {
+                exit_reason = Some(ExitReason::TakeProfit);
+            } else if exit_sl_pct > Decimal::ZERO && change_pct <= -exit_sl_pct {
+                exit_reason = Some(ExitReason::StopLoss);
+            }
+        }
+    }
+
+    exit_reason
+}
+
+/// Pay for a fill and open its position
+fn book_fill(inner: &mut PortfolioInner, mut trade: Trade) -> Trade {
+    inner.balance -= trade.bet_size + trade.entry_gas_fee + trade.maker_taker_fee;
+    trade.balance_after = inner.balance;
+    inner.trades.push(trade.clone());
+    inner.open_trades.push(trade.clone());
+    trade
+}
+
+/// Book the exit of a trade already removed from `open_trades`: exit slippage, fees,
+/// PnL, win/loss counters, returned capital and drawdown.
+fn settle(
+    inner: &mut PortfolioInner,
+    mut trade: Trade,
+    current_price: Decimal,
+    reason: ExitReason,
+    at: DateTime<Utc>,
+    sim: &SimConfig,
+) -> Trade {
+    // Store raw exit price
+    trade.raw_exit_price = Some(current_price);
+
+    // Redeeming a settled position at 1/0 doesn't cross a book: no slippage, taker fee or gas
+    let redeemed = reason == ExitReason::MarketResolved
+        && (current_price == Decimal::ONE || current_price.is_zero());
+
+    // Sim: exit slippage (selling = worse fill)
+    let exit_slippage_pct = if sim.slippage_enabled && !redeemed {
+        let spread = Decimal::new(3, 2); // estimate spread at exit
+        calculate_slippage_pct(sim, trade.bet_size, spread)
+    } else {
+        Decimal::ZERO
+    };
+
+    // Actual exit price with slippage (selling = lower price)
+    let actual_exit_price = current_price * (Decimal::ONE - exit_slippage_pct);
+    let exit_slippage_cost = (current_price - actual_exit_price).abs() * trade.shares;
+
+    // Gross PnL (using adjusted entry from execute_trade and slippage-adjusted exit)
+    let gross_pnl = (actual_exit_price - trade.entry_price) * trade.shares;
+
+    // Sim: exit gas fee
+    let exit_gas = if sim.fees_enabled && !redeemed {
+        random_gas_fee(sim.gas_fee_min, sim.gas_fee_max)
+    } else {
+        Decimal::ZERO
+    };
+
+    // Sim: exit maker/taker fee
+    let exit_value = (actual_exit_price * trade.shares).abs();
+    let exit_maker_taker = if sim.fees_enabled && !redeemed {
+        exit_value * sim.taker_fee_pct
+    } else {
+        Decimal::ZERO
+    };
+
+    // Sim: platform fee (only on profitable trades)
+    let plat_fee = if sim.fees_enabled && gross_pnl > Decimal::ZERO {
+        gross_pnl * sim.platform_fee_pct
+    } else {
+        Decimal::ZERO
+    };
+
+    // Store simulation tracking
+    trade.exit_slippage = exit_slippage_cost;
+    trade.exit_gas_fee = exit_gas;
+    trade.platform_fee = plat_fee;
+    // Accumulate exit maker/taker into total
+    trade.maker_taker_fee += exit_maker_taker;
+
+    trade.exit_price = Some(actual_exit_price);
+    trade.pnl = gross_pnl;
+    trade.exit_reason = Some(reason);
+    let hold_hours = (at - trade.timestamp).num_minutes() as f64 / 60.0;
+    trade.hold_duration_hours = Some(hold_hours);
+
+    // Win/loss based on gross PnL (trade quality)
+    trade.status = if gross_pnl > Decimal::ZERO { TradeStatus::Won } else { TradeStatus::Lost };
+    if !is_hedged_leg(&trade) {
+        if trade.status == TradeStatus::Won {
+            inner.win_count += 1;
+            inner.consecutive_losses = 0;
+        } else {
+            inner.loss_count += 1;
+            inner.consecutive_losses += 1;
+        }
+    }
+
+    // Return capital: bet_size + gross_pnl - exit_fees
+    let return_amount = trade.bet_size + gross_pnl - exit_gas - exit_maker_taker - plat_fee;
+    inner.balance += if return_amount > Decimal::ZERO { return_amount } else { Decimal::ZERO };
+    trade.balance_after = inner.balance;
+
+    if let Some(t) = inner.trades.iter_mut().find(|t| t.id == trade.id) {
+        *t = trade.clone();
+    }
+
+    if inner.balance > inner.peak_balance {
+        inner.peak_balance = inner.balance;
+    }
+    if inner.peak_balance > Decimal::ZERO {
+        let dd = (inner.peak_balance - inner.balance) / inner.peak_balance;
+        if dd > inner.max_drawdown {
+            inner.max_drawdown = dd;
+        }
+    }
+
+    let total_fees = trade.entry_gas_fee + exit_gas + trade.entry_slippage + exit_slippage_cost + plat_fee + trade.maker_taker_fee;
+    let net_pnl = gross_pnl - total_fees + trade.entry_gas_fee; // entry gas already deducted from balance
+    info!("CLOSED [{}]: {} {} | PnL ${} (net ${}) | Fees ${} | Reason: {} | Held {:.1}h",
+        trade.trade_mode.as_deref().unwrap_or("?"),
+        trade.direction,
+        &trade.question[..trade.question.len().min(35)],
+        gross_pnl.round_dp(4), net_pnl.round_dp(4), total_fees.round_dp(4),
+        reason, hold_hours);
+
+    trade
+}
+
 #[derive(Debug, Clone)]
 pub struct PortfolioStats {
     pub balance: Decimal,
     pub initial_balance: Decimal,
     pub realized_pnl: Decimal,
     pub unrealized_pnl: Decimal,
+    /// Realized + unrealized, before AI spend
+    pub trading_pnl: Decimal,
+    /// Trading P&L net of `total_api_cost`
     pub total_pnl: Decimal,
     pub roi: Decimal,
     pub peak_balance: Decimal,
@@ -801,7 +1160,8 @@ impl std::fmt::Display for PortfolioStats {
 ║  Available:   ${}
 ║  Realized:    {}${}
 ║  Unrealized:  {}${} ({} open)
-║  Total P&L:   ${} ({})
+║  Trading P&L: ${}
+║  Total P&L:   ${} ({}) net of API
 ║  Peak:        ${}
 ║  Max DD:      {}%
 ║  Trades:      {} closed (W:{} L:{} = {:.0}%)
@@ -820,6 +1180,7 @@ impl std::fmt::Display for PortfolioStats {
             upnl_sign,
             self.unrealized_pnl,
             self.open_positions,
+            self.trading_pnl,
             self.total_pnl,
             roi_str,
             self.peak_balance,
@@ -832,8 +1193,53 @@ impl std::fmt::Display for PortfolioStats {
             self.total_gas_fees.round_dp(4),
             self.total_slippage_cost.round_dp(4),
             self.total_platform_fees.round_dp(4),
-            self.total_api_cost,
+            self.total_api_cost.round_dp(4),
             self.consecutive_losses,
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn restore_round_trips_balance_positions_streak_and_drawdown() {
+        let store = StateStore::new(":memory:").unwrap();
+        let portfolio = Portfolio::new(dec!(1000), SimConfig::disabled());
+        let open = |id: &str, bet: Decimal| {
+            let trade = portfolio
+                .execute_trade(id, "Will it?", Direction::Yes, dec!(0.5), dec!(0.6), dec!(0.1), bet, dec!(10000))
+                .unwrap();
+            store.save_trade(&trade).unwrap();
+            trade.id
+        };
+        let close = |id: &str, price: Decimal| {
+            let trade = portfolio.close_trade(id, price, ExitReason::TakeProfit).unwrap();
+            store.save_trade(&trade).unwrap();
+        };
+
+        let won = open("m1", dec!(50));
+        close(&won, dec!(0.6));
+        let lost = open("m2", dec!(100));
+        close(&lost, dec!(0.25));
+        let lost_again = open("m3", dec!(40));
+        let held = open("m4", dec!(50));
+        // Closed after m4 opened: its row is the last one and carries the latest balance
+        close(&lost_again, dec!(0.2));
+
+        let restored = Portfolio::new(dec!(1000), SimConfig::disabled());
+        assert_eq!(restored.restore_from_store(&store).unwrap(), 4);
+
+        let (before, after) = (portfolio.stats(), restored.stats());
+        assert_eq!(after.balance, before.balance);
+        assert_eq!(after.balance, dec!(1000) + dec!(10) - dec!(50) - dec!(24) - dec!(50));
+        assert_eq!(restored.open_trades().iter().map(|t| t.id.clone()).collect::<Vec<_>>(), vec![held]);
+        assert_eq!((after.win_count, after.loss_count), (1, 2));
+        assert_eq!(after.consecutive_losses, 2);
+        assert_eq!(after.peak_balance, dec!(1010));
+        assert_eq!(after.max_drawdown_pct, before.max_drawdown_pct);
+        assert!(after.max_drawdown_pct > Decimal::ZERO);
+    }
+}
@@ -0,0 +1,54 @@
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Async token bucket shared by every caller of one upstream. Clones share the bucket.
+/// Bursts up to ~10 seconds' worth of requests, then paces at `per_minute`.
+#[derive(Clone, Default)]
+pub struct RateLimiter {
+    /// None = unlimited
+    bucket: Option<Arc<Mutex<Bucket>>>,
+}
+
+struct Bucket {
+    tokens: f64,
+    capacity: f64,
+    per_sec: f64,
+    last: Instant,
+}
+
+impl RateLimiter {
+    /// `per_minute` = 0 disables the limit
+    pub fn new(per_minute: u32) -> Self {
+        if per_minute == 0 {
+            return Self::default();
+        }
+        let capacity = (per_minute as f64 / 6.0).max(1.0);
+        Self {
+            bucket: Some(Arc::new(Mutex::new(Bucket {
+                tokens: capacity,
+                capacity,
+                per_sec: per_minute as f64 / 60.0,
+                last: Instant::now(),
+            }))),
+        }
+    }
+
+    /// Wait until a request may go out
+    pub async fn acquire(&self) {
+        let Some(bucket) = &self.bucket else { return };
+        loop {
+            let wait = {
+                let mut b = bucket.lock().unwrap();
+                let now = Instant::now();
+                b.tokens = (b.tokens + now.duration_since(b.last).as_secs_f64() * b.per_sec).min(b.capacity);
+                b.last = now;
+                if b.tokens >= 1.0 {
+                    b.tokens -= 1.0;
+                    return;
+                }
+                Duration::from_secs_f64((1.0 - b.tokens) / b.per_sec)
+            };
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
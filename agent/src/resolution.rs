@@ -0,0 +1,267 @@
+use crate::db::{MarketResolutionRow, StateStore};
+use crate::forecast;
+use crate::http::HttpClient;
+use crate::types::{Direction, Market, Trade};
+use anyhow::{Context, Result};
+use rust_decimal::Decimal;
+use rust_decimal::prelude::ToPrimitive;
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+use tracing::{debug, info, warn};
+
+/// Where a judged market left the pipeline
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Decision {
+    /// Judge (or calibration) said SKIP
+    Skip,
+    RiskRejected,
+    /// Edge smaller than the stop-loss distance
+    EdgeBelowStop,
+    ValidatorRejected,
+    /// Approved but the order didn't fill
+    NotFilled,
+    /// Approved and sent as a limit order still working on the book
+    Resting,
+    Traded,
+}
+
+impl std::fmt::Display for Decision {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Decision::Skip => write!(f, "SKIP"),
+            Decision::RiskRejected => write!(f, "RISK_REJECTED"),
+            Decision::EdgeBelowStop => write!(f, "EDGE_BELOW_SL"),
+            Decision::ValidatorRejected => write!(f, "VALIDATOR_REJECTED"),
+            Decision::NotFilled => write!(f, "NOT_FILLED"),
+            Decision::Resting => write!(f, "RESTING"),
+            Decision::Traded => write!(f, "TRADED"),
+        }
+    }
+}
+
+/// Remember a decision on a judged market. For a SKIP the lean is the side
+/// the judge's fair value favoured, so the skipped bet can still be scored.
+pub fn track(store: &StateStore, market: &Market, decision: Decision, direction: Direction, fair_value_yes: f64) {
+    let lean = match direction {
+        Direction::Skip => {
+            let yes_price = market.yes_price.to_f64().unwrap_or(0.5);
+            if fair_value_yes >= yes_price { Direction::Yes } else { Direction::No }
+        }
+        d => d,
+    };
+    if let Err(e) = store.track_decision(market, &decision.to_string(), lean, fair_value_yes) {
+        warn!("market_resolutions write failed for {}: {e}", market.id);
+    }
+}
+
+/// Looks up final outcomes of tracked markets on the Gamma API
+pub struct ResolutionTracker {
+    base_url: String,
+    http: HttpClient,
+}
+
+#[derive(Deserialize)]
+struct GammaMarket {
+    #[serde(default)]
+    closed: bool,
+    /// JSON-encoded outcome labels, `["Yes", "No"]` or one per categorical outcome
+    #[serde(default)]
+    outcomes: Option<serde_json::Value>,
+    /// JSON-encoded `["<yes price>", "<no price>"]`; settles to 1/0 on resolution
+    #[serde(default, rename = "outcomePrices")]
+    outcome_prices: Option<serde_json::Value>,
+}
+
+/// Gamma list fields arrive as a string holding a JSON array (or, rarely, the array itself)
+fn json_list(value: Option<serde_json::Value>) -> Vec<serde_json::Value> {
+    match value {
+        Some(serde_json::Value::String(s)) => serde_json::from_str(&s).unwrap_or_default(),
+        Some(serde_json::Value::Array(a)) => a,
+        _ => Vec::new(),
+    }
+}
+
+fn json_decimal(value: &serde_json::Value) -> Option<Decimal> {
+    match value {
+        serde_json::Value::String(s) => Decimal::from_str(s).ok(),
+        serde_json::Value::Number(n) => Decimal::from_str(&n.to_string()).ok(),
+        _ => None,
+    }
+}
+
+impl ResolutionTracker {
+    pub fn new(gamma_api_base: &str, http: HttpClient) -> Self {
+        Self {
+            base_url: gamma_api_base.trim_end_matches('/').to_string(),
+            http,
+        }
+    }
+
+    /// Gamma's record of a market once it has closed (None while still open)
+    async fn fetch_closed(&self, market_id: &str) -> Result<Option<GammaMarket>> {
+        let resp = self
+            .http
+            .send(self.http.get(format!("{}/markets/{market_id}", self.base_url)))
+            .await
+            .context("Gamma market request")?;
+
+        if !resp.status().is_success() {
+            anyhow::bail!("Gamma API {} for market {market_id}", resp.status());
+        }
+
+        let market: GammaMarket = resp.json().await.context("Parse Gamma market")?;
+        Ok(market.closed.then_some(market))
+    }
+
+    /// `Some(true)` if the market settled YES, `Some(false)` if NO, `None` while still open
+    pub async fn fetch_outcome(&self, market_id: &str) -> Result<Option<bool>> {
+        let Some(market) = self.fetch_closed(market_id).await? else {
+            return Ok(None);
+        };
+        let yes_price = json_list(market.outcome_prices).first().and_then(json_decimal);
+
+        // Closed but not yet settled at 0/1 (e.g. disputed) — check again later
+        Ok(yes_price.and_then(forecast::settled_yes))
+    }
+
+    /// Label of the outcome that settled at 1 ("Yes"/"No" for binary markets).
+    /// None while the market is open, or closed but not settled at 0/1 yet.
+    pub async fn fetch_winner(&self, market_id: &str) -> Result<Option<String>> {
+        let Some(market) = self.fetch_closed(market_id).await? else {
+            return Ok(None);
+        };
+        let labels = json_list(market.outcomes);
+        let settled: Option<Vec<bool>> = json_list(market.outcome_prices)
+            .iter()
+            .map(|p| json_decimal(p).and_then(forecast::settled_yes))
+            .collect();
+        let Some(settled) = settled.filter(|s| s.len() == labels.len()) else {
+            return Ok(None);
+        };
+        let mut winners = labels.iter().zip(settled).filter(|(_, won)| *won);
+        Ok(match (winners.next(), winners.next()) {
+            (Some((label, _)), None) => label.as_str().map(str::to_string),
+            _ => None,
+        })
+    }
+
+    /// Winners of markets holding open positions that the scan no longer lists and
+    /// Gamma reports closed and settled, keyed by market id. Markets still listed
+    /// are open, whatever their prices, and are not looked up.
+    pub async fn settled_winners(&self, open: &[Trade], listed: &[Market]) -> HashMap<String, String> {
+        let mut winners = HashMap::new();
+        let mut checked = HashSet::new();
+        for trade in open {
+            if listed.iter().any(|m| m.id == trade.market_id) || !checked.insert(trade.market_id.as_str()) {
+                continue;
+            }
+            match self.fetch_winner(&trade.market_id).await {
+                Ok(Some(winner)) => {
+                    winners.insert(trade.market_id.clone(), winner);
+                }
+                Ok(None) => debug!("Delisted market {} not settled yet", trade.market_id),
+                Err(e) => debug!("Settlement check for {} failed: {e}", trade.market_id),
+            }
+        }
+        winners
+    }
+
+    /// Check up to `batch` unsettled markets not looked at in the last `recheck_hours`.
+    /// Settled outcomes also score the forecasts made on those markets.
+    pub async fn check(&self, store: &StateStore, recheck_hours: u64, batch: usize) -> usize {
+        let cutoff = chrono::Utc::now() - chrono::Duration::hours(recheck_hours as i64);
+        let pending = match store.pending_resolutions(cutoff, batch) {
+            Ok(p) => p,
+            Err(e) => {
+                warn!("Resolution tracker: lookup failed: {e}");
+                return 0;
+            }
+        };
+
+        let mut settled = 0;
+        for market_id in &pending {
+            match self.fetch_outcome(market_id).await {
+                Ok(outcome) => {
+                    store.record_resolution(market_id, outcome).ok();
+                    if let Some(yes) = outcome {
+                        store.resolve_forecasts(market_id, yes).ok();
+                        settled += 1;
+                    }
+                }
+                Err(e) => debug!("Resolution check for {market_id} failed: {e}"),
+            }
+        }
+
+        if settled > 0 {
+            info!("Resolution tracker: {settled}/{} checked markets settled", pending.len());
+        }
+        settled
+    }
+}
+
+/// How the markets behind one decision actually turned out
+#[derive(Debug, Clone, Default)]
+pub struct DecisionSummary {
+    pub decision: String,
+    pub tracked: usize,
+    pub resolved: usize,
+    /// Resolved markets that settled on the side the judge leaned toward
+    pub lean_correct: usize,
+    /// Sum of returns of a hypothetical $1 on the lean side at the decision price
+    pub hypothetical_pnl: f64,
+}
+
+impl DecisionSummary {
+    /// How often the judge's lean was right
+    pub fn lean_hit_rate(&self) -> f64 {
+        if self.resolved == 0 { 0.0 } else { self.lean_correct as f64 / self.resolved as f64 }
+    }
+
+    /// For a filter: how often dropping the market was the right call (the lean side lost)
+    pub fn filter_hit_rate(&self) -> f64 {
+        if self.resolved == 0 { 0.0 } else { 1.0 - self.lean_hit_rate() }
+    }
+
+    /// Average return per $1 the decision left on (or put on) the table
+    pub fn avg_return(&self) -> f64 {
+        if self.resolved == 0 { 0.0 } else { self.hypothetical_pnl / self.resolved as f64 }
+    }
+}
+
+/// Group tracked decisions by outcome, in pipeline order
+pub fn summarize(rows: &[MarketResolutionRow]) -> Vec<DecisionSummary> {
+    let order = [
+        Decision::Skip,
+        Decision::RiskRejected,
+        Decision::EdgeBelowStop,
+        Decision::ValidatorRejected,
+        Decision::NotFilled,
+        Decision::Resting,
+        Decision::Traded,
+    ];
+
+    order
+        .iter()
+        .map(|d| d.to_string())
+        .filter_map(|decision| {
+            let mut s = DecisionSummary { decision, ..Default::default() };
+            for r in rows.iter().filter(|r| r.decision == s.decision) {
+                s.tracked += 1;
+                let Some(yes) = r.outcome_yes else { continue };
+                s.resolved += 1;
+
+                let (won, price) = match r.lean {
+                    Direction::No => (!yes, 1.0 - r.decision_price),
+                    _ => (yes, r.decision_price),
+                };
+                if won {
+                    s.lean_correct += 1;
+                }
+                let price = price.clamp(0.01, 0.99);
+                s.hypothetical_pnl += if won { 1.0 / price - 1.0 } else { -1.0 };
+            }
+            (s.tracked > 0).then_some(s)
+        })
+        .collect()
+}
@@ -1,5 +1,18 @@
-use crate::types::Direction;
+use crate::config::Config;
+use crate::data::polymarket::GammaScanner;
+use crate::db::StateStore;
+use crate::http::Hosts;
+use crate::live::clob::OrderBookSummary;
+use crate::live::ClobClient;
+use crate::telegram::TelegramAlert;
+use crate::types::{Direction, Market, Trade};
+use crate::venue::ExecutionVenue;
+use async_trait::async_trait;
 use rust_decimal::Decimal;
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+use std::str::FromStr;
+use std::sync::Mutex;
+use tracing::{debug, info};
 
 #[derive(Debug, Clone)]
 pub struct KellyResult {
@@ -145,6 +158,40 @@ pub fn kelly_bet(
     result
 }
 
+/// Choose which outcome of a categorical market to buy, and size it.
+///
+/// One outcome token is held per market. Bought alone, outcome k at price c_k
+/// with probability p_k grows the bankroll at full Kelly by
+///   g_k = p_k ln(p_k / c_k) + (1 - p_k) ln((1 - p_k) / (1 - c_k))
+/// so the outcome with the largest g_k among those priced below p_k is taken,
+/// then sized by `kelly_bet` as a YES bet on that outcome.
+/// Returns the outcome index, or None when no outcome has an edge worth a bet.
+pub fn kelly_outcome(
+    bankroll: Decimal,
+    probabilities: &[f64],
+    prices: &[Decimal],
+    max_pct: Decimal,
+    kelly_fraction: Decimal,
+) -> Option<(usize, KellyResult)> {
+    let (best, _) = probabilities
+        .iter()
+        .zip(prices)
+        .enumerate()
+        .filter_map(|(i, (&p, price))| {
+            let c = price.to_f64()?;
+            if !(0.0..1.0).contains(&c) || c <= 0.0 || p <= c || p >= 1.0 {
+                return None;
+            }
+            let growth = p * (p / c).ln() + (1.0 - p) * ((1.0 - p) / (1.0 - c)).ln();
+            Some((i, growth))
+        })
+        .max_by(|a, b| a.1.total_cmp(&b.1))?;
+
+    let fair = Decimal::from_f64(probabilities[best])?;
+    let kelly = kelly_bet(bankroll, fair, prices[best], Direction::Yes, max_pct, kelly_fraction);
+    (kelly.bet_size > Decimal::ZERO).then_some((best, kelly))
+}
+
 /// Survival mode adjustments when balance is low
 pub fn survival_adjust(
     bankroll: Decimal,
@@ -167,3 +214,163 @@ pub fn survival_adjust(
 
     (normal_max_pct, false)
 }
+
+/// Everything a strategy can use during one cycle of the main loop
+pub struct Cycle<'a> {
+    pub number: u64,
+    pub config: &'a Config,
+    pub scanner: &'a GammaScanner,
+    pub clob: &'a ClobClient,
+    pub hosts: &'a Hosts,
+    pub venue: &'a dyn ExecutionVenue,
+    pub store: &'a StateStore,
+    pub telegram: &'a TelegramAlert,
+    /// Per-position cap after survival mode and loss-streak reductions
+    pub max_position_pct: Decimal,
+}
+
+/// YES-token book of a binary market (None = no YES token, or the CLOB request failed)
+pub async fn yes_book(market: &Market, clob: &ClobClient) -> Option<OrderBookSummary> {
+    let token = market.side_token(Direction::Yes)?;
+    match clob.get_order_book(&token.token_id).await {
+        Ok(book) => Some(book),
+        Err(e) => {
+            debug!("Book for {} unavailable: {e}", market.id);
+            None
+        }
+    }
+}
+
+/// One order a strategy wants placed, with the view behind it
+#[derive(Debug, Clone)]
+pub struct TradeIntent {
+    pub market_id: String,
+    pub question: String,
+    pub direction: Direction,
+    /// Outcome bought in a categorical market
+    pub outcome: Option<String>,
+    /// Strategy's P(YES), or P(outcome) for categorical intents
+    pub probability: f64,
+    pub confidence: f64,
+    pub edge: Decimal,
+}
+
+/// A source of trades. Each cycle the main loop asks every enabled strategy to
+/// propose candidates, turn them into trade intents, then place the intents its
+/// capital allocation allows. Strategies tag what they open with `name()`.
+#[async_trait(?Send)]
+pub trait Strategy {
+    /// What `propose` hands to `decide` (a market, a set of markets, ...)
+    type Candidate;
+
+    /// Attribution label stored in `trades.strategy` and keyed in `STRATEGY_ALLOCATIONS`
+    fn name(&self) -> &'static str;
+
+    async fn propose(&self, cycle: &Cycle<'_>) -> Vec<Self::Candidate>;
+
+    async fn decide(&self, cycle: &Cycle<'_>, candidates: Vec<Self::Candidate>) -> Vec<TradeIntent>;
+
+    /// Place the intents, never committing more than `capital` grants. Returns the trades opened.
+    async fn execute(&self, cycle: &Cycle<'_>, intents: Vec<TradeIntent>, capital: &Capital) -> Vec<Trade>;
+}
+
+/// Object-safe face of `Strategy`, so the main loop can iterate over a mixed list
+#[async_trait(?Send)]
+pub trait Runner {
+    fn name(&self) -> &'static str;
+
+    /// propose -> decide -> execute within this strategy's allocation
+    async fn run(&self, cycle: &Cycle<'_>) -> Vec<Trade>;
+}
+
+#[async_trait(?Send)]
+impl<S: Strategy> Runner for S {
+    fn name(&self) -> &'static str {
+        Strategy::name(self)
+    }
+
+    async fn run(&self, cycle: &Cycle<'_>) -> Vec<Trade> {
+        let name = Strategy::name(self);
+        let allocation = allocation(&cycle.config.strategy_allocations, name);
+        let capital = Capital::new(name, allocation, &cycle.venue.open_positions(), cycle.venue.balance());
+
+        // Execute runs even without candidates or intents: it also withdraws standing
+        // orders nobody wants any more (scan failed, nothing quotable)
+        let candidates = self.propose(cycle).await;
+        let intents = if candidates.is_empty() {
+            Vec::new()
+        } else {
+            self.decide(cycle, candidates).await
+        };
+        if !intents.is_empty() {
+            info!(
+                "[{name}] {} intent(s), capital ${:.2} ({}% allocation)",
+                intents.len(),
+                capital.remaining(),
+                allocation * Decimal::from(100)
+            );
+        }
+        for intent in &intents {
+            debug!(
+                "[{name}] {} {} p={:.2} conf={:.2} edge={:.3} | {}",
+                intent.direction,
+                intent.outcome.as_deref().unwrap_or(""),
+                intent.probability,
+                intent.confidence,
+                intent.edge,
+                &intent.question[..intent.question.len().min(50)]
+            );
+        }
+
+        self.execute(cycle, intents, &capital).await
+    }
+}
+
+/// Strategy a trade is attributed to (trades from before attribution are the team's)
+pub fn strategy_of(trade: &Trade) -> &str {
+    trade.strategy.as_deref().unwrap_or(crate::team::NAME)
+}
+
+/// Share of capital for `name` from a `name=share,name=share` spec.
+/// Unlisted strategies (or an empty spec) may use all of it.
+pub fn allocation(spec: &str, name: &str) -> Decimal {
+    spec.split(',')
+        .filter_map(|entry| entry.split_once('='))
+        .find(|(n, _)| n.trim().eq_ignore_ascii_case(name))
+        .and_then(|(_, share)| Decimal::from_str(share.trim()).ok())
+        .map(|share| share.clamp(Decimal::ZERO, Decimal::ONE))
+        .unwrap_or(Decimal::ONE)
+}
+
+/// Capital one strategy may still commit this cycle: its allocation of equity
+/// (cash plus stakes in open positions) minus what its own open trades hold.
+/// Concurrent orders reserve from it and release what they don't use.
+pub struct Capital {
+    remaining: Mutex<Decimal>,
+}
+
+impl Capital {
+    pub fn new(name: &str, allocation: Decimal, open_trades: &[Trade], balance: Decimal) -> Self {
+        let staked: Decimal = open_trades.iter().map(|t| t.bet_size).sum();
+        let used: Decimal = open_trades.iter().filter(|t| strategy_of(t) == name).map(|t| t.bet_size).sum();
+        let limit = (balance + staked) * allocation;
+        Self { remaining: Mutex::new((limit - used).max(Decimal::ZERO)) }
+    }
+
+    pub fn remaining(&self) -> Decimal {
+        *self.remaining.lock().unwrap()
+    }
+
+    /// Take up to `amount`; returns what was granted
+    pub fn reserve(&self, amount: Decimal) -> Decimal {
+        let mut remaining = self.remaining.lock().unwrap();
+        let granted = amount.min(*remaining).max(Decimal::ZERO);
+        *remaining -= granted;
+        granted
+    }
+
+    /// Give back a reservation that was not spent
+    pub fn release(&self, amount: Decimal) {
+        *self.remaining.lock().unwrap() += amount;
+    }
+}
@@ -1,4 +1,5 @@
-use crate::analyzer::gemini::GeminiClient;
+use crate::analyzer::structured::check_probability;
+use crate::analyzer::{call_structured, LlmProvider, LlmUsage, StructuredOutput};
 use crate::team::types::AuditInsight;
 use crate::types::{Trade, TradeStatus};
 use anyhow::Result;
@@ -23,11 +24,11 @@ RULES:
 /// Agent 10: Auditor — Post-trade learning (Gemini AI, periodic)
 /// Analyzes closed trades to find patterns and generate insights.
 pub async fn audit(
-    gemini: &GeminiClient,
+    llm: &dyn LlmProvider,
     closed_trades: &[Trade],
-) -> Result<AuditInsight> {
+) -> Result<(AuditInsight, LlmUsage)> {
     if closed_trades.is_empty() {
-        return Ok(AuditInsight {
+        let insight = AuditInsight {
             timestamp: chrono::Utc::now().to_rfc3339(),
             trade_count: 0,
             win_rate: 0.0,
@@ -36,7 +37,8 @@ pub async fn audit(
             bull_accuracy: 0.0,
             bear_accuracy: 0.0,
             desk_accuracy: HashMap::new(),
-        });
+        };
+        return Ok((insight, LlmUsage::default()));
     }
 
     let win_count = closed_trades.iter().filter(|t| t.status == TradeStatus::Won).count();
@@ -72,11 +74,10 @@ pub async fn audit(
         trade_summaries.join("\n"),
     );
 
-    let (text, cost) = gemini.call(AUDIT_SYSTEM, &user_msg, 500).await?;
-    info!("Auditor: analyzed {} trades (${:.4})", total, cost);
+    let (parsed, usage) = call_structured::<AuditResponse>(llm, AUDIT_SYSTEM, &user_msg, 500).await?;
+    info!("Auditor: analyzed {} trades (${:.4})", total, usage.cost);
 
-    let insight = parse_audit(&text, total, win_rate)?;
-    Ok(insight)
+    Ok((parsed.into_insight(total, win_rate), usage))
 }
 
 /// Save audit insights to knowledge.json for injection into future prompts
@@ -117,58 +118,60 @@ pub fn save_insights(insights: &AuditInsight) -> Result<()> {
     Ok(())
 }
 
-fn parse_audit(text: &str, trade_count: usize, win_rate: f64) -> Result<AuditInsight> {
-    let json_str = extract_json(text);
+#[derive(Deserialize)]
+struct AuditResponse {
+    avg_calibration_error: f64,
+    #[serde(default)]
+    insights: Vec<String>,
+    #[serde(default)]
+    bull_accuracy: f64,
+    #[serde(default)]
+    bear_accuracy: f64,
+    #[serde(default)]
+    desk_accuracy: HashMap<String, f64>,
+}
 
-    #[derive(Deserialize)]
-    struct Resp {
-        avg_calibration_error: f64,
-        #[serde(default)]
-        insights: Vec<String>,
-        #[serde(default)]
-        bull_accuracy: f64,
-        #[serde(default)]
-        bear_accuracy: f64,
-        #[serde(default)]
-        desk_accuracy: HashMap<String, f64>,
+impl StructuredOutput for AuditResponse {
+    fn schema() -> serde_json::Value {
+        let desks: serde_json::Map<_, _> = ["CRYPTO", "WEATHER", "SPORTS", "GENERAL"]
+            .iter()
+            .map(|d| (d.to_string(), serde_json::json!({ "type": "number" })))
+            .collect();
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "avg_calibration_error": { "type": "number" },
+                "insights": { "type": "array", "items": { "type": "string" } },
+                "bull_accuracy": { "type": "number" },
+                "bear_accuracy": { "type": "number" },
+                "desk_accuracy": { "type": "object", "properties": desks }
+            },
+            "required": ["avg_calibration_error", "insights", "bull_accuracy", "bear_accuracy"]
+        })
     }
 
-    let parsed: Resp = serde_json::from_str(&json_str)
-        .map_err(|e| anyhow::anyhow!("Audit JSON parse: {e}"))?;
-
-    Ok(AuditInsight {
-        timestamp: chrono::Utc::now().to_rfc3339(),
-        trade_count,
-        win_rate,
-        avg_calibration_error: parsed.avg_calibration_error,
-        insights: parsed.insights,
-        bull_accuracy: parsed.bull_accuracy,
-        bear_accuracy: parsed.bear_accuracy,
-        desk_accuracy: parsed.desk_accuracy,
-    })
-}
-
-fn extract_json(text: &str) -> String {
-    if let Some(start) = text.find('{') {
-        let mut depth = 0;
-        for (i, ch) in text[start..].char_indices() {
-            match ch {
-                '{' => depth += 1,
-                '}' => {
-                    depth -= 1;
-                    if depth == 0 {
-                        return text[start..=start + i].to_string();
-                    }
-                }
-                _ => {}
-            }
+    fn validate(&self) -> Result<()> {
+        check_probability("avg_calibration_error", self.avg_calibration_error)?;
+        check_probability("bull_accuracy", self.bull_accuracy)?;
+        check_probability("bear_accuracy", self.bear_accuracy)?;
+        for (desk, acc) in &self.desk_accuracy {
+            check_probability(&format!("desk_accuracy.{desk}"), *acc)?;
         }
+        Ok(())
     }
-    if let Some(s) = text.find("```json") {
-        let after = &text[s + 7..];
-        if let Some(e) = after.find("```") {
-            return after[..e].trim().to_string();
+}
+
+impl AuditResponse {
+    fn into_insight(self, trade_count: usize, win_rate: f64) -> AuditInsight {
+        AuditInsight {
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            trade_count,
+            win_rate,
+            avg_calibration_error: self.avg_calibration_error,
+            insights: self.insights,
+            bull_accuracy: self.bull_accuracy,
+            bear_accuracy: self.bear_accuracy,
+            desk_accuracy: self.desk_accuracy,
         }
     }
-    text.to_string()
 }
@@ -1,6 +1,7 @@
-use crate::analyzer::gemini::GeminiClient;
+use crate::analyzer::structured::{check_enum, check_probability};
+use crate::analyzer::{call_structured, LlmProvider, LlmUsage, StructuredOutput};
 use crate::team::data_analyst;
-use crate::team::types::{BearCase, DataPack, DeskReport, MarketCandidate, ResearchDossier};
+use crate::team::types::{BearCase, CASE_STRENGTHS, DataPack, DeskReport, MarketCandidate, ResearchDossier};
 use anyhow::Result;
 use rust_decimal::Decimal;
 use serde::Deserialize;
@@ -27,12 +28,12 @@ RULES:
 
 /// Agent 9: Bear Analyst — Builds the strongest NO case (Gemini AI)
 pub async fn analyze(
-    gemini: &GeminiClient,
+    llm: &dyn LlmProvider,
     candidate: &MarketCandidate,
     data_pack: &DataPack,
     dossier: &ResearchDossier,
     desk_report: &DeskReport,
-) -> Result<BearCase> {
+) -> Result<(BearCase, LlmUsage)> {
     let market = &candidate.market;
     let data_text = data_analyst::format_data_pack(data_pack);
 
@@ -87,65 +88,58 @@ pub async fn analyze(
         factors = dossier.key_factors.join(", "),
     );
 
-    let (text, cost) = gemini.call(BEAR_SYSTEM, &user_msg, 400).await?;
+    let (parsed, usage) = call_structured::<BearResponse>(llm, BEAR_SYSTEM, &user_msg, 400).await?;
 
     info!(
         "Bear: {} (${:.4})",
         &market.question[..market.question.len().min(40)],
-        cost
+        usage.cost
     );
 
-    parse_bear_case(&text, &market.id)
+    Ok((parsed.into_case(&market.id), usage))
 }
 
-fn parse_bear_case(text: &str, market_id: &str) -> Result<BearCase> {
-    let json_str = extract_json(text);
+#[derive(Deserialize)]
+struct BearResponse {
+    probability_no: f64,
+    case_strength: String,
+    #[serde(default)]
+    arguments: Vec<String>,
+    #[serde(default)]
+    evidence: Vec<String>,
+    reasoning: String,
+}
 
-    #[derive(Deserialize)]
-    struct Resp {
-        probability_no: f64,
-        case_strength: String,
-        #[serde(default)]
-        arguments: Vec<String>,
-        #[serde(default)]
-        evidence: Vec<String>,
-        reasoning: String,
+impl StructuredOutput for BearResponse {
+    fn schema() -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "probability_no": { "type": "number" },
+                "case_strength": { "type": "string", "enum": CASE_STRENGTHS },
+                "arguments": { "type": "array", "items": { "type": "string" } },
+                "evidence": { "type": "array", "items": { "type": "string" } },
+                "reasoning": { "type": "string" }
+            },
+            "required": ["probability_no", "case_strength", "arguments", "evidence", "reasoning"]
+        })
     }
 
-    let parsed: Resp = serde_json::from_str(&json_str)
-        .map_err(|e| anyhow::anyhow!("Bear JSON parse: {e} | {}", &json_str[..json_str.len().min(200)]))?;
-
-    Ok(BearCase {
-        market_id: market_id.to_string(),
-        probability_no: parsed.probability_no.clamp(0.05, 0.95),
-        case_strength: parsed.case_strength,
-        arguments: parsed.arguments,
-        evidence: parsed.evidence,
-        reasoning: parsed.reasoning,
-    })
+    fn validate(&self) -> Result<()> {
+        check_probability("probability_no", self.probability_no)?;
+        check_enum("case_strength", &self.case_strength, &CASE_STRENGTHS)
+    }
 }
 
-fn extract_json(text: &str) -> String {
-    if let Some(start) = text.find('{') {
-        let mut depth = 0;
-        for (i, ch) in text[start..].char_indices() {
-            match ch {
-                '{' => depth += 1,
-                '}' => {
-                    depth -= 1;
-                    if depth == 0 {
-                        return text[start..=start + i].to_string();
-                    }
-                }
-                _ => {}
-            }
-        }
-    }
-    if let Some(s) = text.find("```json") {
-        let after = &text[s + 7..];
-        if let Some(e) = after.find("```") {
-            return after[..e].trim().to_string();
+impl BearResponse {
+    fn into_case(self, market_id: &str) -> BearCase {
+        BearCase {
+            market_id: market_id.to_string(),
+            probability_no: self.probability_no.clamp(0.05, 0.95),
+            case_strength: self.case_strength.trim().to_uppercase(),
+            arguments: self.arguments,
+            evidence: self.evidence,
+            reasoning: self.reasoning,
         }
     }
-    text.to_string()
 }
@@ -1,6 +1,7 @@
-use crate::analyzer::gemini::GeminiClient;
+use crate::analyzer::structured::{check_enum, check_probability};
+use crate::analyzer::{call_structured, LlmProvider, LlmUsage, StructuredOutput};
 use crate::team::data_analyst;
-use crate::team::types::{BullCase, DataPack, DeskReport, MarketCandidate, ResearchDossier};
+use crate::team::types::{BullCase, CASE_STRENGTHS, DataPack, DeskReport, MarketCandidate, ResearchDossier};
 use anyhow::Result;
 use rust_decimal::Decimal;
 use serde::Deserialize;
@@ -27,12 +28,12 @@ RULES:
 
 /// Agent 8: Bull Analyst — Builds the strongest YES case (Gemini AI)
 pub async fn analyze(
-    gemini: &GeminiClient,
+    llm: &dyn LlmProvider,
     candidate: &MarketCandidate,
     data_pack: &DataPack,
     dossier: &ResearchDossier,
     desk_report: &DeskReport,
-) -> Result<BullCase> {
+) -> Result<(BullCase, LlmUsage)> {
     let market = &candidate.market;
     let data_text = data_analyst::format_data_pack(data_pack);
 
@@ -84,65 +85,58 @@ pub async fn analyze(
         factors = dossier.key_factors.join(", "),
     );
 
-    let (text, cost) = gemini.call(BULL_SYSTEM, &user_msg, 400).await?;
+    let (parsed, usage) = call_structured::<BullResponse>(llm, BULL_SYSTEM, &user_msg, 400).await?;
 
     info!(
         "Bull: {} (${:.4})",
         &market.question[..market.question.len().min(40)],
-        cost
+        usage.cost
     );
 
-    parse_bull_case(&text, &market.id)
+    Ok((parsed.into_case(&market.id), usage))
 }
 
-fn parse_bull_case(text: &str, market_id: &str) -> Result<BullCase> {
-    let json_str = extract_json(text);
+#[derive(Deserialize)]
+struct BullResponse {
+    probability_yes: f64,
+    case_strength: String,
+    #[serde(default)]
+    arguments: Vec<String>,
+    #[serde(default)]
+    evidence: Vec<String>,
+    reasoning: String,
+}
 
-    #[derive(Deserialize)]
-    struct Resp {
-        probability_yes: f64,
-        case_strength: String,
-        #[serde(default)]
-        arguments: Vec<String>,
-        #[serde(default)]
-        evidence: Vec<String>,
-        reasoning: String,
+impl StructuredOutput for BullResponse {
+    fn schema() -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "probability_yes": { "type": "number" },
+                "case_strength": { "type": "string", "enum": CASE_STRENGTHS },
+                "arguments": { "type": "array", "items": { "type": "string" } },
+                "evidence": { "type": "array", "items": { "type": "string" } },
+                "reasoning": { "type": "string" }
+            },
+            "required": ["probability_yes", "case_strength", "arguments", "evidence", "reasoning"]
+        })
     }
 
-    let parsed: Resp = serde_json::from_str(&json_str)
-        .map_err(|e| anyhow::anyhow!("Bull JSON parse: {e} | {}", &json_str[..json_str.len().min(200)]))?;
-
-    Ok(BullCase {
-        market_id: market_id.to_string(),
-        probability_yes: parsed.probability_yes.clamp(0.05, 0.95),
-        case_strength: parsed.case_strength,
-        arguments: parsed.arguments,
-        evidence: parsed.evidence,
-        reasoning: parsed.reasoning,
-    })
+    fn validate(&self) -> Result<()> {
+        check_probability("probability_yes", self.probability_yes)?;
+        check_enum("case_strength", &self.case_strength, &CASE_STRENGTHS)
+    }
 }
 
-fn extract_json(text: &str) -> String {
-    if let Some(start) = text.find('{') {
-        let mut depth = 0;
-        for (i, ch) in text[start..].char_indices() {
-            match ch {
-                '{' => depth += 1,
-                '}' => {
-                    depth -= 1;
-                    if depth == 0 {
-                        return text[start..=start + i].to_string();
-                    }
-                }
-                _ => {}
-            }
-        }
-    }
-    if let Some(s) = text.find("```json") {
-        let after = &text[s + 7..];
-        if let Some(e) = after.find("```") {
-            return after[..e].trim().to_string();
+impl BullResponse {
+    fn into_case(self, market_id: &str) -> BullCase {
+        BullCase {
+            market_id: market_id.to_string(),
+            probability_yes: self.probability_yes.clamp(0.05, 0.95),
+            case_strength: self.case_strength.trim().to_uppercase(),
+            arguments: self.arguments,
+            evidence: self.evidence,
+            reasoning: self.reasoning,
         }
     }
-    text.to_string()
 }
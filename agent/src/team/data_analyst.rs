@@ -1,67 +1,99 @@
+use crate::config::Config;
 use crate::data::Enricher;
+use crate::db::StateStore;
+use crate::http::Hosts;
 use crate::live::ClobClient;
 use crate::team::types::{DataPack, MarketCandidate};
+use futures::stream::{self, StreamExt};
 use tracing::{debug, warn};
 
 /// Agent 3: Data Analyst — Quantitative data collection (no AI)
 /// Wraps Enricher (CoinGecko, news) + ClobClient (order book).
+/// Up to `DATA_CONCURRENCY` candidates are fetched at once; each request is rate
+/// limited per host and bounded by `DATA_TIMEOUT_SECS`, so one slow source only
+/// costs that candidate its data. Packs come back in candidate order.
+/// Every book fetched is also sampled into price_log (see `StateStore::log_price`).
 pub async fn analyze(
     enricher: &Enricher,
     clob: &ClobClient,
+    store: &StateStore,
+    config: &Config,
+    hosts: &Hosts,
     candidates: &[MarketCandidate],
 ) -> Vec<DataPack> {
-    let mut packs = Vec::with_capacity(candidates.len());
+    stream::iter(candidates)
+        .map(|candidate| analyze_one(enricher, clob, store, config.price_log_sample_secs, hosts, candidate))
+        .buffered(config.data_concurrency.max(1))
+        .collect()
+        .await
+}
 
-    for candidate in candidates {
-        let market = &candidate.market;
+async fn analyze_one(
+    enricher: &Enricher,
+    clob: &ClobClient,
+    store: &StateStore,
+    price_log_sample_secs: u64,
+    hosts: &Hosts,
+    candidate: &MarketCandidate,
+) -> DataPack {
+    let market = &candidate.market;
 
-        // Fetch enrichment data (crypto prices, news)
-        let enrichment = enricher.enrich(market).await;
+    // Fetch enrichment data (crypto prices, news)
+    // The Enricher's sources aren't on the shared HTTP layer; pace them here
+    hosts.coingecko.acquire().await;
+    let enrichment = match tokio::time::timeout(hosts.data_timeout, enricher.enrich(market)).await {
+        Ok(e) => e,
+        Err(_) => {
+            warn!("Enrichment timed out for {}", &market.id[..8.min(market.id.len())]);
+            Default::default()
+        }
+    };
 
-        // Extract price trend from crypto signals
-        let price_trend_24h = enrichment
-            .crypto_signals
-            .as_ref()
-            .map(|s| s.price_24h_change_pct);
-        let volume_trend = enrichment
-            .crypto_signals
-            .as_ref()
-            .map(|s| s.volume_24h_change_pct);
+    // Extract price trend from crypto signals
+    let price_trend_24h = enrichment
+        .crypto_signals
+        .as_ref()
+        .map(|s| s.price_24h_change_pct);
+    let volume_trend = enrichment
+        .crypto_signals
+        .as_ref()
+        .map(|s| s.volume_24h_change_pct);
 
-        // Fetch order book data if tokens available
-        let (spread, bid_depth, ask_depth) = if !market.tokens.is_empty() {
-            match clob.get_order_book(&market.tokens[0].token_id).await {
-                Ok(book) => {
-                    debug!(
-                        "CLOB book for {}: spread={} bid_depth={} ask_depth={}",
-                        &market.question[..market.question.len().min(30)],
-                        book.spread,
-                        book.bid_depth,
-                        book.ask_depth,
-                    );
-                    (Some(book.spread), Some(book.bid_depth), Some(book.ask_depth))
-                }
-                Err(e) => {
-                    warn!("Order book fetch failed: {e}");
-                    (None, None, None)
+    // Fetch order book data if tokens available
+    let (spread, bid_depth, ask_depth) = if !market.tokens.is_empty() {
+        let token_id = &market.tokens[0].token_id;
+        match clob.get_order_book(token_id).await {
+            Ok(book) => {
+                if let Err(e) = store.log_price(&market.id, Some(token_id), &book, price_log_sample_secs) {
+                    warn!("price_log write failed for {}: {e}", market.id);
                 }
+                debug!(
+                    "CLOB book for {}: spread={} bid_depth={} ask_depth={}",
+                    &market.question[..market.question.len().min(30)],
+                    book.spread,
+                    book.bid_depth,
+                    book.ask_depth,
+                );
+                (Some(book.spread), Some(book.bid_depth), Some(book.ask_depth))
             }
-        } else {
-            (None, None, None)
-        };
+            Err(e) => {
+                warn!("Order book fetch failed: {e}");
+                (None, None, None)
+            }
+        }
+    } else {
+        (None, None, None)
+    };
 
-        packs.push(DataPack {
-            market_id: market.id.clone(),
-            enrichment,
-            price_trend_24h,
-            volume_trend,
-            order_book_spread: spread,
-            order_book_bid_depth: bid_depth,
-            order_book_ask_depth: ask_depth,
-        });
+    DataPack {
+        market_id: market.id.clone(),
+        enrichment,
+        price_trend_24h,
+        volume_trend,
+        order_book_spread: spread,
+        order_book_bid_depth: bid_depth,
+        order_book_ask_depth: ask_depth,
     }
-
-    packs
 }
 
 /// Format DataPack into a text summary for AI prompts
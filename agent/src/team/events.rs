@@ -0,0 +1,338 @@
+use crate::team::types::DevilsVerdict;
+use crate::types::{Market, Trade};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use rust_decimal::Decimal;
+use tracing::{info, warn};
+
+/// How the YES probabilities of one event's markets constrain each other
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Relation {
+    /// "above $X" ladder: P(YES) can only fall as the threshold rises
+    Above,
+    /// "below $X" ladder: P(YES) can only rise with the threshold
+    Below,
+    /// Mutually exclusive outcomes ("Will X win ...", "between $X and $Y"): P(YES) sums to at most 1
+    Exclusive,
+    /// Stand-alone market
+    Single,
+}
+
+impl std::fmt::Display for Relation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Relation::Above => write!(f, "ABOVE"),
+            Relation::Below => write!(f, "BELOW"),
+            Relation::Exclusive => write!(f, "EXCLUSIVE"),
+            Relation::Single => write!(f, "SINGLE"),
+        }
+    }
+}
+
+/// Parent event of a market. Gamma markets carry no event id, so siblings are
+/// found by question template: thresholds are masked ("will bitcoin be above $# on
+/// friday?") and "Will X win Y?" questions group on Y.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EventKey {
+    pub key: String,
+    pub relation: Relation,
+    /// Ladder / bucket threshold parsed from the question
+    pub threshold: Option<f64>,
+}
+
+impl EventKey {
+    pub fn of(market: &Market) -> Self {
+        classify(&market.question, &market.id)
+    }
+
+    /// Event of an open position. Categorical positions carry the outcome
+    /// label after the question, which is stripped to get back to the market.
+    pub fn of_trade(trade: &Trade) -> Self {
+        let question = match &trade.outcome {
+            Some(outcome) => trade
+                .question
+                .strip_suffix(outcome.as_str())
+                .and_then(|q| q.strip_suffix(" — "))
+                .unwrap_or(&trade.question),
+            None => &trade.question,
+        };
+        classify(question, &trade.market_id)
+    }
+
+    pub fn is_grouped(&self) -> bool {
+        self.relation != Relation::Single
+    }
+}
+
+// Matched as whole words, so "hit" doesn't fire on "white house"
+const ABOVE_WORDS: [&str; 14] = [
+    "above", "over", "exceed", "exceeds", "reach", "reaches", "hit", "hits",
+    "higher than", "more than", "greater than", "at least", "or more", "or higher",
+];
+const BELOW_WORDS: [&str; 11] = [
+    "below", "under", "less than", "lower than", "dip", "dips", "fall", "falls", "drop", "drops", "or less",
+];
+
+fn classify(question: &str, market_id: &str) -> EventKey {
+    let q = question.trim().to_lowercase();
+    let single = || EventKey { key: format!("market:{market_id}"), relation: Relation::Single, threshold: None };
+
+    let words: Vec<&str> = q.split_whitespace().collect();
+    let mut thresholds = Vec::new();
+    let mut template = Vec::with_capacity(words.len());
+    for (i, word) in words.iter().enumerate() {
+        let next = words.get(i + 1).copied().unwrap_or("");
+        match parse_threshold(word, next) {
+            Some((value, masked)) => {
+                thresholds.push(value);
+                template.push(masked);
+            }
+            None => template.push(word.to_string()),
+        }
+    }
+    let template = template.join(" ");
+    let padded = format!(" {} ", template.replace(['?', ','], " "));
+    let has = |list: &[&str]| list.iter().any(|w| padded.contains(&format!(" {w} ")));
+
+    match thresholds.as_slice() {
+        [] => match q.strip_prefix("will ").and_then(|rest| rest.split_once(" win ")) {
+            Some((_, contest)) => EventKey { key: format!("win {contest}"), relation: Relation::Exclusive, threshold: None },
+            None => single(),
+        },
+        [t] if has(&BELOW_WORDS) => EventKey { key: template, relation: Relation::Below, threshold: Some(*t) },
+        [t] if has(&ABOVE_WORDS) => EventKey { key: template, relation: Relation::Above, threshold: Some(*t) },
+        [lo, _] if q.contains("between") => EventKey { key: template, relation: Relation::Exclusive, threshold: Some(*lo) },
+        _ => single(),
+    }
+}
+
+/// A price-like number: "$105,000", "$105k", "105k", "3.5%", "90°f", "90 degrees".
+/// Bare numbers (years, days of the month) are not thresholds.
+/// Returns the value and the word with the number masked as `#`.
+fn parse_threshold(word: &str, next: &str) -> Option<(f64, String)> {
+    let body = word.trim_end_matches(['?', ',', '.', ';', ':', ')', '!']);
+    let tail = &word[body.len()..];
+    let (currency, rest) = match body.strip_prefix('$') {
+        Some(rest) => (true, rest),
+        None => (false, body),
+    };
+
+    let digits_end = rest.find(|c: char| !(c.is_ascii_digit() || c == ',' || c == '.')).unwrap_or(rest.len());
+    let (number, unit) = rest.split_at(digits_end);
+    if !number.starts_with(|c: char| c.is_ascii_digit()) {
+        return None;
+    }
+    let mut value: f64 = number.trim_end_matches('.').replace(',', "").parse().ok()?;
+
+    let multiplier = match unit {
+        "k" => Some(1e3),
+        "m" => Some(1e6),
+        "b" | "bn" => Some(1e9),
+        "" | "%" | "°" | "°f" | "°c" => Some(1.0),
+        _ => None,
+    }?;
+    value *= multiplier;
+
+    let unit_follows = matches!(next, "degrees" | "°f" | "°c" | "%" | "percent");
+    if !(currency || !unit.is_empty() || unit_follows) {
+        return None;
+    }
+    Some((value, format!("{}#{unit}{tail}", if currency { "$" } else { "" })))
+}
+
+/// One judged market of an event, as seen by the consistency pass
+pub struct Leg<'a> {
+    pub market_id: &'a str,
+    pub threshold: Option<f64>,
+    pub market_yes: f64,
+    pub verdict: &'a mut DevilsVerdict,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignalKind {
+    /// The judge's fair value broke the event constraint and was moved
+    JudgeAdjusted,
+    /// Market prices themselves break the constraint: a mispricing inside the event
+    PriceInconsistent,
+}
+
+impl std::fmt::Display for SignalKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SignalKind::JudgeAdjusted => write!(f, "JUDGE_ADJUSTED"),
+            SignalKind::PriceInconsistent => write!(f, "PRICE_INCONSISTENT"),
+        }
+    }
+}
+
+/// Inconsistency found inside one event, persisted to `event_signals`
+#[derive(Debug, Clone)]
+pub struct Signal {
+    pub event: String,
+    pub relation: Relation,
+    pub kind: SignalKind,
+    pub market_id: String,
+    /// Size of the violation in probability points
+    pub magnitude: f64,
+    pub detail: String,
+}
+
+/// Slack before a market-price violation counts as a mispricing (spread, tick size)
+const PRICE_TOLERANCE: f64 = 0.01;
+/// Judge moves smaller than this are not reported
+const ADJUST_EPSILON: f64 = 0.005;
+
+/// Make one event's judge fair values mutually consistent and flag violations.
+/// Ladders are projected onto the monotone sequence closest to the judge
+/// (isotonic regression weighted by confidence); exclusive sets summing over 1
+/// are scaled down proportionally. Directions that lose their edge become SKIP.
+pub fn reconcile(event: &str, relation: Relation, legs: &mut [Leg<'_>]) -> Vec<Signal> {
+    let mut signals = Vec::new();
+    if legs.len() < 2 {
+        return signals;
+    }
+    let signal = |kind, leg: &Leg<'_>, magnitude: f64, detail: String| Signal {
+        event: event.to_string(),
+        relation,
+        kind,
+        market_id: leg.market_id.to_string(),
+        magnitude,
+        detail,
+    };
+
+    let fitted: Vec<f64> = match relation {
+        Relation::Above | Relation::Below => {
+            legs.sort_by(|a, b| a.threshold.unwrap_or(0.0).total_cmp(&b.threshold.unwrap_or(0.0)));
+
+            // Adjacent market prices out of order are a mispricing on the leg that should be cheaper
+            for pair in legs.windows(2) {
+                let (lo, hi) = (&pair[0], &pair[1]);
+                let gap = match relation {
+                    Relation::Above => hi.market_yes - lo.market_yes,
+                    _ => lo.market_yes - hi.market_yes,
+                };
+                if gap > PRICE_TOLERANCE {
+                    let rich = if relation == Relation::Above { hi } else { lo };
+                    signals.push(signal(SignalKind::PriceInconsistent, rich, gap, format!(
+                        "YES at {:.2} for threshold {} vs {:.2} for {}",
+                        hi.market_yes, fmt_threshold(hi.threshold), lo.market_yes, fmt_threshold(lo.threshold),
+                    )));
+                }
+            }
+
+            let values: Vec<f64> = legs.iter().map(|l| l.verdict.fair_value_yes).collect();
+            let weights: Vec<f64> = legs.iter().map(|l| l.verdict.confidence.max(0.05)).collect();
+            match relation {
+                Relation::Below => isotonic(&values, &weights),
+                _ => {
+                    // Non-increasing fit = non-decreasing fit of the reversed sequence
+                    let rev = |v: &[f64]| v.iter().rev().copied().collect::<Vec<_>>();
+                    rev(&isotonic(&rev(&values), &rev(&weights)))
+                }
+            }
+        }
+        Relation::Exclusive => {
+            let market_sum: f64 = legs.iter().map(|l| l.market_yes).sum();
+            if market_sum > 1.0 + PRICE_TOLERANCE {
+                for leg in legs.iter() {
+                    signals.push(signal(SignalKind::PriceInconsistent, leg, market_sum - 1.0, format!(
+                        "exclusive YES prices sum to {market_sum:.2} across {} markets", legs.len(),
+                    )));
+                }
+            }
+
+            let judge_sum: f64 = legs.iter().map(|l| l.verdict.fair_value_yes).sum();
+            let scale = if judge_sum > 1.0 { 1.0 / judge_sum } else { 1.0 };
+            legs.iter().map(|l| l.verdict.fair_value_yes * scale).collect()
+        }
+        Relation::Single => return signals,
+    };
+
+    for (leg, fair) in legs.iter_mut().zip(fitted) {
+        let before = leg.verdict.fair_value_yes;
+        if (fair - before).abs() < ADJUST_EPSILON {
+            continue;
+        }
+        let detail = format!("fair {:.2} -> {:.2} ({relation} event of {})", before, fair, event);
+        info!("  Event[{}]: {detail}", &leg.market_id[..leg.market_id.len().min(12)]);
+        leg.verdict.fair_value_yes = fair.clamp(0.02, 0.98);
+        leg.verdict.reasoning.push_str(&format!(" [Event check: {detail}]"));
+
+        let edge = leg.verdict.fair_value_yes - leg.market_yes;
+        let direction = leg.verdict.direction.to_uppercase();
+        if (direction == "YES" && edge < 0.03) || (direction == "NO" && edge > -0.03) {
+            leg.verdict.direction = "SKIP".to_string();
+        }
+        signals.push(signal(SignalKind::JudgeAdjusted, leg, (fair - before).abs(), detail));
+    }
+
+    for s in signals.iter().filter(|s| s.kind == SignalKind::PriceInconsistent) {
+        warn!("  Event mispricing [{}]: {}", s.market_id, s.detail);
+    }
+    signals
+}
+
+fn fmt_threshold(t: Option<f64>) -> String {
+    t.map(|t| format!("{t}")).unwrap_or_else(|| "?".to_string())
+}
+
+/// Weighted pool-adjacent-violators: closest non-decreasing sequence to `values`
+fn isotonic(values: &[f64], weights: &[f64]) -> Vec<f64> {
+    // Blocks of (weighted sum, weight, count)
+    let mut blocks: Vec<(f64, f64, usize)> = Vec::with_capacity(values.len());
+    for (&v, &w) in values.iter().zip(weights) {
+        blocks.push((v * w, w, 1));
+        while blocks.len() >= 2 {
+            let b = blocks[blocks.len() - 1];
+            let a = blocks[blocks.len() - 2];
+            if a.0 / a.1 <= b.0 / b.1 {
+                break;
+            }
+            blocks.truncate(blocks.len() - 2);
+            blocks.push((a.0 + b.0, a.1 + b.1, a.2 + b.2));
+        }
+    }
+    blocks
+        .iter()
+        .flat_map(|&(sum, weight, n)| std::iter::repeat_n(sum / weight, n))
+        .collect()
+}
+
+/// Per-event exposure cap for one cycle: open positions plus what this cycle
+/// has already committed. Reservations are taken synchronously after sizing,
+/// so concurrently executing candidates can't overshoot together.
+pub struct EventBudget {
+    cap: Decimal,
+    used: Mutex<HashMap<String, Decimal>>,
+}
+
+impl EventBudget {
+    /// `max_pct` of capital (cash + open stakes) per event; 0 disables the cap
+    pub fn new(open_trades: &[Trade], balance: Decimal, max_pct: Decimal) -> Self {
+        let mut used: HashMap<String, Decimal> = HashMap::new();
+        for t in open_trades {
+            *used.entry(EventKey::of_trade(t).key).or_default() += t.bet_size;
+        }
+        let capital = balance + open_trades.iter().map(|t| t.bet_size).sum::<Decimal>();
+        Self {
+            cap: if max_pct > Decimal::ZERO { capital * max_pct } else { Decimal::MAX },
+            used: Mutex::new(used),
+        }
+    }
+
+    /// Reserve up to `amount` for `event` and return what was granted
+    pub fn reserve(&self, event: &str, amount: Decimal) -> Decimal {
+        let mut used = self.used.lock().unwrap();
+        let entry = used.entry(event.to_string()).or_default();
+        let granted = amount.min((self.cap - *entry).max(Decimal::ZERO));
+        *entry += granted;
+        granted
+    }
+
+    /// Hand back a reservation whose order never filled
+    pub fn release(&self, event: &str, amount: Decimal) {
+        if let Some(entry) = self.used.lock().unwrap().get_mut(event) {
+            *entry = (*entry - amount).max(Decimal::ZERO);
+        }
+    }
+}
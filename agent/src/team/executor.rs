@@ -1,70 +1,130 @@
 use crate::telegram::TelegramAlert;
-use crate::paper::{Portfolio, SimConfig};
 use crate::db::StateStore;
+use crate::live::clob::OrderBookSummary;
+use crate::paper::{RestingOrder, TimeInForce, Touch};
 use crate::team::types::TradePlan;
 use crate::types::{Analysis, Direction, Trade};
-use chrono::Utc;
-use rust_decimal::Decimal;
+use crate::venue::{ExecutionVenue, Placed};
+use chrono::{DateTime, Utc};
+use rust_decimal::{Decimal, RoundingStrategy};
 use rust_decimal::prelude::*;
 use tracing::{error, info};
 
 /// Agent 9: Executor — Trade execution + monitoring (no AI)
-/// Wraps Portfolio for trade execution, records to StateStore, sends alerts.
+/// Routes the order to the configured venue (paper or live), records to StateStore, sends alerts.
 /// Enriches trade with full agent trail for paper trading battle test.
 pub async fn execute(
     plan: &TradePlan,
-    portfolio: &Portfolio,
+    venue: &dyn ExecutionVenue,
     store: &StateStore,
     telegram: &TelegramAlert,
-    sim: &SimConfig,
 ) -> Option<Trade> {
     if plan.direction == Direction::Skip {
         return None;
     }
 
-    // Execute the trade via Portfolio
-    let mut trade = portfolio.execute_trade(
-        &plan.market.id,
-        &plan.market.question,
-        plan.direction,
-        plan.market.yes_price,
-        plan.fair_value_yes,
-        plan.edge,
-        plan.bet_size,
-        sim,
-        plan.market.volume,
-    )?;
+    // Execute the trade via the active venue
+    let mut trade = venue
+        .open_position(
+            &plan.market,
+            plan.direction,
+            plan.fair_value_yes,
+            plan.edge,
+            plan.bet_size,
+        )
+        .await?;
 
-    // Enrich with paper trading agent trail
-    trade.trade_mode = Some(format!("{}", plan.mode));
-    trade.category = Some(plan.market.category.clone());
-    trade.specialist_desk = plan.specialist_desk.clone();
-    trade.bull_probability = plan.bull_probability;
-    trade.bear_probability = plan.bear_probability;
-    trade.judge_fair_value = Some(plan.fair_value_yes.to_f64().unwrap_or(0.0));
-    trade.judge_confidence = Some(plan.confidence.to_f64().unwrap_or(0.0));
-    trade.judge_model = plan.judge_model.clone();
+    apply_plan(&mut trade, plan, Utc::now());
 
-    // Set TP/SL price levels from percentages
-    if plan.take_profit_pct > Decimal::ZERO {
-        trade.take_profit = Some(trade.entry_price * (Decimal::ONE + plan.take_profit_pct));
+    // Keep the venue's copy in sync so exit rules see mode, TP/SL and the judge trail
+    venue.ledger().update_trade(&trade);
+
+    record_fill(&trade, plan, store, telegram).await;
+    save_analysis(plan, store);
+    Some(trade)
+}
+
+/// Limit order for an entry on a binary market: GTC/GTD orders bid `improve` above
+/// the best bid but stay a tick under the ask, FOK/IOC orders take the best ask.
+/// The plan's trail rides along as the template of the trades its fills open.
+pub fn entry_order(
+    plan: &TradePlan,
+    book: &OrderBookSummary,
+    tif: TimeInForce,
+    improve: Decimal,
+    ttl_minutes: u64,
+    now: DateTime<Utc>,
+) -> Option<RestingOrder> {
+    if plan.direction == Direction::Skip || plan.outcome.is_some() {
+        return None;
     }
-    if plan.stop_loss_pct > Decimal::ZERO {
-        trade.stop_loss = Some(trade.entry_price * (Decimal::ONE - plan.stop_loss_pct));
+    let touch = Touch::of(plan.direction, book);
+    let tick = Decimal::new(1, 2);
+    let price = if tif.is_immediate() {
+        touch.ask
+    } else if touch.bid > Decimal::ZERO {
+        (touch.bid + improve).min(touch.ask - tick)
+    } else {
+        touch.ask - tick
     }
-    if plan.max_hold_hours > 0 {
-        trade.max_hold_until = Some(Utc::now() + chrono::Duration::hours(plan.max_hold_hours as i64));
+    .round_dp_with_strategy(2, RoundingStrategy::ToZero);
+    if price <= Decimal::ZERO || price >= Decimal::ONE {
+        return None;
+    }
+    let shares = (plan.bet_size / price).round_dp_with_strategy(2, RoundingStrategy::ToZero);
+    if shares <= Decimal::ZERO {
+        return None;
     }
 
-    // Set token_id for CLOB price tracking
-    let token_id = plan.market.tokens.iter()
-        .find(|t| {
-            (plan.direction == Direction::Yes && t.outcome == "Yes")
-            || (plan.direction == Direction::No && t.outcome == "No")
-        })
-        .map(|t| t.token_id.clone());
-    trade.token_id = token_id;
+    let mut order = RestingOrder::new(&plan.market, plan.direction, price, shares, plan.fair_value_yes);
+    order.tif = tif;
+    order.placed_at = now;
+    if tif == TimeInForce::Gtd {
+        order.expires_at = Some(now + chrono::Duration::minutes(ttl_minutes as i64));
+    }
+    let mut template = order.fill_trade(shares, price, Decimal::ZERO, now);
+    apply_plan(&mut template, plan, now);
+    order.trade_mode = template.trade_mode.clone();
+    order.strategy = template.strategy.clone();
+    order.template = Some(Box::new(template));
+    Some(order)
+}
+
+/// Patient entry: send the plan as a limit order instead of taking the price.
+/// A fill on arrival is recorded like `execute`'s; a remainder left working is
+/// saved as an open order and booked as it fills (see `market_making::sync_fills`).
+pub async fn place_entry(
+    plan: &TradePlan,
+    order: RestingOrder,
+    book: &OrderBookSummary,
+    venue: &dyn ExecutionVenue,
+    store: &StateStore,
+    telegram: &TelegramAlert,
+) -> Option<Placed> {
+    let placed = venue.place_limit(order, book).await?;
 
+    if placed.resting {
+        store.save_order(&placed.order, "OPEN").ok();
+        info!(
+            "RESTING [{}] {}: {} {} @ {} | {} shares | edge={:.1}%",
+            plan.mode,
+            placed.order.tif,
+            placed.order.direction,
+            &placed.order.question[..placed.order.question.len().min(35)],
+            placed.order.price,
+            placed.order.shares,
+            (plan.edge * Decimal::from(100)),
+        );
+    }
+    if let Some(trade) = &placed.fill {
+        record_fill(trade, plan, store, telegram).await;
+    }
+    save_analysis(plan, store);
+    Some(placed)
+}
+
+/// Log, save and alert a filled entry
+async fn record_fill(trade: &Trade, plan: &TradePlan, store: &StateStore, telegram: &TelegramAlert) {
     info!(
         "EXECUTE [{}]: {} {} @ {} | ${} | edge={:.1}% conf={:.2} | desk={} judge={}",
         plan.mode,
@@ -79,11 +139,16 @@ pub async fn execute(
     );
 
     // Save trade to database
-    if let Err(e) = store.save_trade(&trade) {
+    if let Err(e) = store.save_trade(trade) {
         error!("Failed to save trade: {e}");
     }
 
-    // Save analysis record
+    // Send Telegram alert with richer info
+    telegram.send_paper_trade_alert(trade).await.ok();
+}
+
+/// Save the analysis record behind an entry
+fn save_analysis(plan: &TradePlan, store: &StateStore) {
     let analysis = Analysis {
         market_id: plan.market.id.clone(),
         question: plan.market.question.clone(),
@@ -99,9 +164,40 @@ pub async fn execute(
         enrichment_data: None,
     };
     store.save_analysis(&analysis).ok();
+}
 
-    // Send Telegram alert with richer info
-    telegram.send_paper_trade_alert(&trade).await.ok();
+/// Copy the plan's agent trail and exit levels onto a freshly filled trade.
+/// `now` anchors max-hold (wall clock live, simulated clock in backtests).
+pub fn apply_plan(trade: &mut Trade, plan: &TradePlan, now: DateTime<Utc>) {
+    // Enrich with paper trading agent trail
+    trade.trade_mode = Some(format!("{}", plan.mode));
+    trade.category = Some(plan.market.category.clone());
+    trade.specialist_desk = plan.specialist_desk.clone();
+    trade.bull_probability = plan.bull_probability;
+    trade.bear_probability = plan.bear_probability;
+    let fair_value = plan.fair_value_yes.to_f64().unwrap_or(0.0);
+    let confidence = plan.confidence.to_f64().unwrap_or(0.0);
+    trade.judge_fair_value = Some(plan.raw_fair_value_yes.unwrap_or(fair_value));
+    trade.judge_confidence = Some(plan.raw_confidence.unwrap_or(confidence));
+    trade.judge_model = plan.judge_model.clone();
+    trade.calibrated_fair_value = Some(fair_value);
+    trade.calibrated_confidence = Some(confidence);
 
-    Some(trade)
+    // Set TP/SL price levels from percentages
+    if plan.take_profit_pct > Decimal::ZERO {
+        trade.take_profit = Some(trade.entry_price * (Decimal::ONE + plan.take_profit_pct));
+    }
+    if plan.stop_loss_pct > Decimal::ZERO {
+        trade.stop_loss = Some(trade.entry_price * (Decimal::ONE - plan.stop_loss_pct));
+    }
+    if plan.max_hold_hours > 0 {
+        trade.max_hold_until = Some(now + chrono::Duration::hours(plan.max_hold_hours as i64));
+    }
+
+    // Set token_id for CLOB price tracking
+    if trade.token_id.is_none() {
+        trade.token_id = plan.market.side_token(plan.direction).map(|t| t.token_id.clone());
+    }
+    trade.outcome = plan.outcome.clone();
+    trade.strategy = Some(super::NAME.to_string());
 }
@@ -1,9 +1,8 @@
-use crate::analyzer::gemini::GeminiClient;
+use crate::analyzer::{call_structured, LlmProvider, LlmUsage};
 use crate::team::data_analyst;
-use crate::team::types::{DataPack, DeskReport, DeskType, MarketCandidate, ResearchDossier};
+use crate::team::types::{DataPack, DeskReport, DeskResponse, DeskType, MarketCandidate, ResearchDossier};
 use anyhow::Result;
 use rust_decimal::Decimal;
-use serde::Deserialize;
 use tracing::info;
 
 const GENERAL_DESK_SYSTEM: &str = r#"You are a GENERAL ANALYST on a prediction market trading team. You handle politics, economics, culture, technology, and any market that doesn't fit crypto/weather/sports.
@@ -24,11 +23,11 @@ GENERAL EXPERTISE:
 
 /// Agent 7: General Desk — Catch-all specialist (Gemini AI)
 pub async fn analyze(
-    gemini: &GeminiClient,
+    llm: &dyn LlmProvider,
     candidate: &MarketCandidate,
     data_pack: &DataPack,
     dossier: &ResearchDossier,
-) -> Result<DeskReport> {
+) -> Result<(DeskReport, LlmUsage)> {
     let market = &candidate.market;
     let data_text = data_analyst::format_data_pack(data_pack);
 
@@ -67,67 +66,12 @@ pub async fn analyze(
         factors = dossier.key_factors.join(", "),
     );
 
-    let (text, cost) = gemini.call(GENERAL_DESK_SYSTEM, &user_msg, 400).await?;
+    let (parsed, usage) = call_structured::<DeskResponse>(llm, GENERAL_DESK_SYSTEM, &user_msg, 400).await?;
     info!(
         "GeneralDesk: {} (${:.4})",
         &market.question[..market.question.len().min(40)],
-        cost
+        usage.cost
     );
 
-    parse_desk_report(&text, &market.id, DeskType::General)
-}
-
-fn parse_desk_report(text: &str, market_id: &str, desk: DeskType) -> Result<DeskReport> {
-    let json_str = extract_json(text);
-
-    #[derive(Deserialize)]
-    struct Resp {
-        specialist_probability: f64,
-        #[serde(default)]
-        key_factors: Vec<String>,
-        #[serde(default)]
-        risk_assessment: String,
-        #[serde(default)]
-        data_summary: String,
-        #[serde(default)]
-        confidence_in_data: f64,
-    }
-
-    let parsed: Resp = serde_json::from_str(&json_str)
-        .map_err(|e| anyhow::anyhow!("GeneralDesk JSON parse: {e} | {}", &json_str[..json_str.len().min(200)]))?;
-
-    Ok(DeskReport {
-        market_id: market_id.to_string(),
-        desk,
-        specialist_probability: parsed.specialist_probability.clamp(0.05, 0.95),
-        key_factors: parsed.key_factors,
-        risk_assessment: parsed.risk_assessment,
-        data_summary: parsed.data_summary,
-        confidence_in_data: parsed.confidence_in_data.clamp(0.0, 1.0),
-    })
-}
-
-fn extract_json(text: &str) -> String {
-    if let Some(start) = text.find('{') {
-        let mut depth = 0;
-        for (i, ch) in text[start..].char_indices() {
-            match ch {
-                '{' => depth += 1,
-                '}' => {
-                    depth -= 1;
-                    if depth == 0 {
-                        return text[start..=start + i].to_string();
-                    }
-                }
-                _ => {}
-            }
-        }
-    }
-    if let Some(s) = text.find("```json") {
-        let after = &text[s + 7..];
-        if let Some(e) = after.find("```") {
-            return after[..e].trim().to_string();
-        }
-    }
-    text.to_string()
+    Ok((parsed.into_report(&market.id, DeskType::General), usage))
 }
@@ -1,9 +1,9 @@
-use crate::analyzer::claude::ClaudeClient;
-use crate::analyzer::gemini::GeminiClient;
+use crate::analyzer::structured::{check_enum, check_probability};
+use crate::analyzer::{call_structured, LlmProvider, LlmUsage, StructuredOutput};
 use crate::team::data_analyst;
 use crate::team::types::{
     BearCase, BullCase, CaseStrength, DataPack, DeskReport, DevilsVerdict, MarketCandidate,
-    ResearchDossier, TradePlan,
+    OutcomeVerdict, ResearchDossier, TradePlan,
 };
 use anyhow::Result;
 use rust_decimal::Decimal;
@@ -41,8 +41,8 @@ Do NOT wrap in markdown code blocks."#;
 /// Agent 10: Judge — Judges both sides, renders final verdict
 /// Uses Claude Sonnet for top candidates (use_sonnet=true), Gemini for rest
 pub async fn judge(
-    gemini: &GeminiClient,
-    _claude: &ClaudeClient,
+    llm: &dyn LlmProvider,
+    _claude: &dyn LlmProvider,
     _use_sonnet: bool,
     candidate: &MarketCandidate,
     bull: &BullCase,
@@ -50,7 +50,7 @@ pub async fn judge(
     data_pack: &DataPack,
     dossier: &ResearchDossier,
     desk_report: &DeskReport,
-) -> Result<DevilsVerdict> {
+) -> Result<(DevilsVerdict, LlmUsage)> {
     let market = &candidate.market;
 
     // AGGRESSIVE MODE: Removed pre-checks for weak cases - let judge decide
@@ -112,16 +112,17 @@ pub async fn judge(
         data = data_text,
     );
 
-    // Force Gemini-only (Sonnet disabled for cost optimization)
-    let (text, cost) = gemini.call(DEVILS_SYSTEM, &user_msg, 500).await?;
+    // Team provider only (Sonnet disabled for cost optimization)
+    let (parsed, usage) = call_structured::<JudgeResponse>(llm, DEVILS_SYSTEM, &user_msg, 500).await?;
 
     info!(
-        "Judge[Gemini]: {} (${:.4})",
+        "Judge[{}]: {} (${:.4})",
+        llm.name(),
         &market.question[..market.question.len().min(40)],
-        cost
+        usage.cost
     );
 
-    let mut verdict = parse_verdict(&text, &market.id)?;
+    let mut verdict = parsed.into_verdict(&market.id);
 
     // AGGRESSIVE MODE: Wider calibration bounds (±30%)
     let market_yes = market.yes_price.to_f64().unwrap_or(0.5);
@@ -154,61 +155,196 @@ pub async fn judge(
         };
     }
 
-    Ok(verdict)
+    Ok((verdict, usage))
 }
 
-fn parse_verdict(text: &str, market_id: &str) -> Result<DevilsVerdict> {
-    let json_str = extract_json(text);
-
-    #[derive(Deserialize)]
-    struct Resp {
-        fair_value_yes: f64,
-        confidence: f64,
-        direction: String,
-        reasoning: String,
-        #[serde(default)]
-        bull_flaws: String,
-        #[serde(default)]
-        bear_flaws: String,
+#[derive(Deserialize)]
+struct JudgeResponse {
+    fair_value_yes: f64,
+    confidence: f64,
+    direction: String,
+    reasoning: String,
+    #[serde(default)]
+    bull_flaws: String,
+    #[serde(default)]
+    bear_flaws: String,
+}
+
+impl StructuredOutput for JudgeResponse {
+    fn schema() -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "fair_value_yes": { "type": "number" },
+                "confidence": { "type": "number" },
+                "direction": { "type": "string", "enum": ["YES", "NO", "SKIP"] },
+                "reasoning": { "type": "string" },
+                "bull_flaws": { "type": "string" },
+                "bear_flaws": { "type": "string" }
+            },
+            "required": ["fair_value_yes", "confidence", "direction", "reasoning"]
+        })
     }
 
-    let parsed: Resp = serde_json::from_str(&json_str)
-        .map_err(|e| anyhow::anyhow!("Judge JSON parse: {e} | {}", &json_str[..json_str.len().min(200)]))?;
-
-    Ok(DevilsVerdict {
-        market_id: market_id.to_string(),
-        fair_value_yes: parsed.fair_value_yes.clamp(0.02, 0.98),
-        confidence: parsed.confidence.clamp(0.0, 1.0),
-        direction: parsed.direction,
-        reasoning: parsed.reasoning,
-        bull_flaws: parsed.bull_flaws,
-        bear_flaws: parsed.bear_flaws,
-    })
+    fn validate(&self) -> Result<()> {
+        check_probability("fair_value_yes", self.fair_value_yes)?;
+        check_probability("confidence", self.confidence)?;
+        check_enum("direction", &self.direction, &["YES", "NO", "SKIP"])
+    }
 }
 
-fn extract_json(text: &str) -> String {
-    if let Some(start) = text.find('{') {
-        let mut depth = 0;
-        for (i, ch) in text[start..].char_indices() {
-            match ch {
-                '{' => depth += 1,
-                '}' => {
-                    depth -= 1;
-                    if depth == 0 {
-                        return text[start..=start + i].to_string();
-                    }
-                }
-                _ => {}
-            }
+impl JudgeResponse {
+    fn into_verdict(self, market_id: &str) -> DevilsVerdict {
+        let fair_value_yes = self.fair_value_yes.clamp(0.02, 0.98);
+        let confidence = self.confidence.clamp(0.0, 1.0);
+        DevilsVerdict {
+            market_id: market_id.to_string(),
+            fair_value_yes,
+            confidence,
+            direction: self.direction.trim().to_uppercase(),
+            reasoning: self.reasoning,
+            bull_flaws: self.bull_flaws,
+            bear_flaws: self.bear_flaws,
+            raw_fair_value_yes: fair_value_yes,
+            raw_confidence: confidence,
         }
     }
-    if let Some(s) = text.find("```json") {
-        let after = &text[s + 7..];
-        if let Some(e) = after.find("```") {
-            return after[..e].trim().to_string();
+}
+
+const OUTCOMES_SYSTEM: &str = r#"You are the JUDGE on a prediction market trading team, pricing a market with several mutually exclusive outcomes. Exactly one outcome will resolve true.
+
+Output ONLY a JSON object:
+{"probabilities": [{"outcome": "<label exactly as given>", "probability": 0.XX}, ...], "confidence": 0.XX, "reasoning": "2-3 sentences"}
+
+RULES:
+1. Give a probability for EVERY listed outcome, using the labels exactly as given.
+2. Probabilities must sum to 1.0.
+3. Start from the market prices and the base rate; move away from them only on evidence.
+4. CONFIDENCE: 0.45-0.55 when uncertain, 0.60+ when the research clearly favours an outcome.
+
+Do NOT wrap in markdown code blocks."#;
+
+/// Judge for categorical markets: a probability for every outcome.
+/// Outcomes the model leaves out get zero; the vector is renormalised.
+pub async fn judge_outcomes(
+    llm: &dyn LlmProvider,
+    candidate: &MarketCandidate,
+    data_pack: &DataPack,
+    dossier: &ResearchDossier,
+) -> Result<(OutcomeVerdict, LlmUsage)> {
+    let market = &candidate.market;
+    let user_msg = format!(
+        "JUDGE THIS MARKET:\n\
+        Question: {question}\n\
+        Outcomes (current price = implied probability): {outcomes}\n\
+        End Date: {end}\n\
+        \n\
+        === RESEARCH ===\n\
+        News: {news}\n\
+        Facts: {facts}\n\
+        Counter-arguments: {counter}\n\
+        Key factors: {factors}\n\
+        \n\
+        === RAW DATA ===\n\
+        {data}\n\
+        \n\
+        Estimate the probability of each outcome.",
+        question = market.question,
+        outcomes = market.outcome_summary(),
+        end = market.end_date,
+        news = dossier.news_relevance,
+        facts = dossier.fact_check,
+        counter = dossier.counter_arguments,
+        factors = dossier.key_factors.join(", "),
+        data = data_analyst::format_data_pack(data_pack),
+    );
+
+    let (parsed, usage) = call_structured::<OutcomesResponse>(llm, OUTCOMES_SYSTEM, &user_msg, 500).await?;
+
+    info!(
+        "Judge[{}] outcomes: {} (${:.4})",
+        llm.name(),
+        &market.question[..market.question.len().min(40)],
+        usage.cost
+    );
+
+    let mut probabilities: Vec<(String, f64)> = market
+        .tokens
+        .iter()
+        .map(|t| {
+            let p = parsed
+                .probabilities
+                .iter()
+                .find(|o| o.outcome.trim().eq_ignore_ascii_case(&t.outcome))
+                .map_or(0.0, |o| o.probability);
+            (t.outcome.clone(), p)
+        })
+        .collect();
+
+    let total: f64 = probabilities.iter().map(|(_, p)| p).sum();
+    if total <= 0.0 {
+        anyhow::bail!("Judge gave no probability to any listed outcome");
+    }
+    for (_, p) in &mut probabilities {
+        *p /= total;
+    }
+
+    Ok((
+        OutcomeVerdict {
+            market_id: market.id.clone(),
+            probabilities,
+            confidence: parsed.confidence.clamp(0.0, 1.0),
+            reasoning: parsed.reasoning,
+        },
+        usage,
+    ))
+}
+
+#[derive(Deserialize)]
+struct OutcomesResponse {
+    probabilities: Vec<OutcomeProbability>,
+    confidence: f64,
+    reasoning: String,
+}
+
+#[derive(Deserialize)]
+struct OutcomeProbability {
+    outcome: String,
+    probability: f64,
+}
+
+impl StructuredOutput for OutcomesResponse {
+    fn schema() -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "probabilities": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "outcome": { "type": "string" },
+                            "probability": { "type": "number" }
+                        },
+                        "required": ["outcome", "probability"]
+                    }
+                },
+                "confidence": { "type": "number" },
+                "reasoning": { "type": "string" }
+            },
+            "required": ["probabilities", "confidence", "reasoning"]
+        })
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.probabilities.is_empty() {
+            anyhow::bail!("probabilities is empty");
+        }
+        for o in &self.probabilities {
+            check_probability(&format!("probability[{}]", o.outcome), o.probability)?;
         }
+        check_probability("confidence", self.confidence)
     }
-    text.to_string()
 }
 
 // ═══════════════════════════════════════════════════════════════════════════
@@ -224,6 +360,28 @@ pub struct ClaudeFinalVerdict {
     pub risk_level: String, // "LOW", "MEDIUM", "HIGH", "GAMBLING"
 }
 
+impl StructuredOutput for ClaudeFinalVerdict {
+    fn schema() -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "approved": { "type": "boolean" },
+                "win_probability": { "type": "number" },
+                "confidence": { "type": "number" },
+                "reasoning": { "type": "string" },
+                "risk_level": { "type": "string", "enum": ["LOW", "MEDIUM", "HIGH", "GAMBLING"] }
+            },
+            "required": ["approved", "win_probability", "confidence", "reasoning", "risk_level"]
+        })
+    }
+
+    fn validate(&self) -> Result<()> {
+        check_probability("win_probability", self.win_probability)?;
+        check_probability("confidence", self.confidence)?;
+        check_enum("risk_level", &self.risk_level, &["LOW", "MEDIUM", "HIGH", "GAMBLING"])
+    }
+}
+
 const CLAUDE_FINAL_SYSTEM: &str = r#"Anda adalah VALIDATOR AGRESIF untuk sistem trading prediction market.
 
 PERAN ANDA: Maksimalkan profit dengan mengambil peluang yang menguntungkan.
@@ -249,9 +407,9 @@ Jangan pakai markdown code blocks, langsung JSON."#;
 /// Claude Final Validator: Validasi akhir sebelum execute trade
 /// Threshold: 60% win rate minimum
 pub async fn claude_final_validator(
-    claude: &ClaudeClient,
+    claude: &dyn LlmProvider,
     plan: &TradePlan,
-) -> Result<ClaudeFinalVerdict> {
+) -> Result<(ClaudeFinalVerdict, LlmUsage)> {
     let user_msg = format!(
         r#"Evaluasi Trade Plan Ini:
 
@@ -293,18 +451,8 @@ Berikan penilaian dalam JSON format."#,
         plan.reasoning,
     );
 
-    let (response, cost) = claude.call(CLAUDE_FINAL_SYSTEM, &user_msg, 600).await?;
-
-    // Parse JSON response
-    let json_str = extract_json(&response);
-    let verdict: ClaudeFinalVerdict = serde_json::from_str(&json_str)
-        .map_err(|e| {
-            anyhow::anyhow!(
-                "Failed to parse Claude final verdict: {} | Response: {}",
-                e,
-                &json_str[..json_str.len().min(300)]
-            )
-        })?;
+    let (verdict, usage) =
+        call_structured::<ClaudeFinalVerdict>(claude, CLAUDE_FINAL_SYSTEM, &user_msg, 600).await?;
 
     // Log decision
     if verdict.approved {
@@ -312,7 +460,7 @@ Berikan penilaian dalam JSON format."#,
             "✅ CLAUDE APPROVED: {:.0}% win | {} risk | ${:.4} cost | {}",
             verdict.win_probability * 100.0,
             verdict.risk_level,
-            cost,
+            usage.cost,
             &verdict.reasoning[..verdict.reasoning.len().min(60)]
         );
     } else {
@@ -320,9 +468,9 @@ Berikan penilaian dalam JSON format."#,
             "❌ CLAUDE REJECTED: {} | {} | ${:.4} cost",
             verdict.risk_level,
             verdict.reasoning,
-            cost
+            usage.cost
         );
     }
 
-    Ok(verdict)
+    Ok((verdict, usage))
 }
@@ -3,6 +3,7 @@ pub mod bear_analyst;
 pub mod bull_analyst;
 pub mod crypto_desk;
 pub mod data_analyst;
+pub mod events;
 pub mod judge;
 pub mod executor;
 pub mod general_desk;
@@ -14,174 +15,337 @@ pub mod strategist;
 pub mod types;
 pub mod weather_desk;
 
-use crate::telegram::TelegramAlert;
-use crate::analyzer::claude::ClaudeClient;
-use crate::analyzer::gemini::GeminiClient;
-use crate::config::Config;
-use crate::paper::{Portfolio, SimConfig};
+use crate::analyzer::structured::StructuredError;
+use crate::analyzer::transcript::{PromptRecord, Transcript};
+use crate::analyzer::{LlmProvider, LlmUsage};
+use crate::calibration::Calibrator;
 use crate::data::Enricher;
-use crate::live::ClobClient;
-use crate::data::polymarket::GammaScanner;
 use crate::db::StateStore;
-use crate::types::Direction;
+use crate::forecast;
+use crate::paper::TimeInForce;
+use crate::resolution::{self, Decision};
+use crate::strategy::{self, Capital, Cycle, Strategy, TradeIntent};
+use crate::types::{Direction, Trade};
+use async_trait::async_trait;
+use chrono::Utc;
 use futures::future::join_all;
 use rust_decimal::Decimal;
-use std::str::FromStr;
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+use std::sync::Mutex;
 use tracing::{error, info, warn};
-use types::{detect_desk, DeskType, TeamCycleStats};
-
-/// Run one full v2.0 team cycle with parallel analysis
-/// 14-Agent Company: Scout -> Data Analyst + Researcher -> Specialist Desk -> Bull/Bear -> Judge -> Risk -> Strategist -> Execute
-pub async fn run_cycle(
-    config: &Config,
-    gemini: &GeminiClient,
-    claude: &ClaudeClient,
-    enricher: &Enricher,
-    scanner: &GammaScanner,
-    clob: &ClobClient,
-    portfolio: &Portfolio,
-    store: &StateStore,
-    telegram: &TelegramAlert,
-    effective_max_pct: Decimal,
+use types::{detect_desk, CandidateOutcome, DeskType, RejectReason, Stage, TeamCycleStats};
+
+/// Attribution label of trades opened by the team (`Strategy::name`)
+pub const NAME: &str = "team";
+
+/// The 14-agent company as a `Strategy`:
+/// Scout -> Data Analyst + Researcher -> Specialist Desk -> Bull/Bear -> Judge -> Event consistency -> Risk -> Strategist -> Execute.
+/// `propose` is the Scout, `decide` runs the parallel debate up to the event pass and
+/// `execute` the Risk -> Strategist -> Validator -> Executor half. Debated candidates wait
+/// in between, and the cycle's stats are collected until `finish`.
+pub struct TeamStrategy<'a> {
+    llm: &'a dyn LlmProvider,
+    claude: &'a dyn LlmProvider,
+    use_validator: bool,
+    enricher: &'a Enricher,
     max_candidates: usize,
     max_deep_analysis: usize,
-) -> TeamCycleStats {
-    let mut stats = TeamCycleStats::default();
-    let sim = SimConfig::from_config(config);
-
-    // ═══════════════════════════════════════════
-    // PHASE 1: INTELLIGENCE (parallel)
-    // ═══════════════════════════════════════════
-    // ═══════════════════════════════════════════
-    // PHASE 1: INTELLIGENCE (parallel)
-    // ═══════════════════════════════════════════
-    info!("═══ PHASE 1: SCOUT + RESEARCH ═══");
-    store.update_status("scanning", "Scanning markets for opportunities...").ok();
-
-    // Scout: scan + filter + score (all categories)
-    let scout_report = match scout::scan(scanner, config, max_candidates).await {
-        Ok(report) => report,
-        Err(e) => {
-            error!("Scout failed: {e}");
-            return stats;
+    stats: Mutex<TeamCycleStats>,
+    debated: Mutex<Vec<Debated>>,
+}
+
+impl<'a> TeamStrategy<'a> {
+    pub fn new(
+        llm: &'a dyn LlmProvider,
+        claude: &'a dyn LlmProvider,
+        use_validator: bool,
+        enricher: &'a Enricher,
+        max_candidates: usize,
+        max_deep_analysis: usize,
+    ) -> Self {
+        Self {
+            llm,
+            claude,
+            use_validator,
+            enricher,
+            max_candidates,
+            max_deep_analysis,
+            stats: Mutex::new(TeamCycleStats::default()),
+            debated: Mutex::new(Vec::new()),
         }
-    };
-    stats.markets_scanned = scout_report.total_scanned;
-    stats.markets_passed_quality = scout_report.total_passed_quality;
+    }
+
+    /// Close the cycle: status back to idle, funnel logged, stats handed to the main loop
+    pub fn finish(self, store: &StateStore) -> TeamCycleStats {
+        let stats = self.stats.into_inner().unwrap();
+        store.update_status("idle", "Cycle complete. Waiting for next run...").ok();
+
+        info!(
+            "═══ CYCLE COMPLETE: scanned={} researched={} analyzed={} approved={} traded={} (API cost: ${:.4}) ═══",
+            stats.markets_scanned,
+            stats.markets_researched,
+            stats.markets_analyzed,
+            stats.markets_approved,
+            stats.trades_placed,
+            stats.api_cost,
+        );
+
+        let funnel: Vec<String> = stats.funnel().iter().map(|(stage, n)| format!("{stage} {n}")).collect();
+        let drops: Vec<String> = stats.rejections().iter().map(|(reason, n)| format!("{reason}={n}")).collect();
+        info!("Funnel: {} | drops: {}", funnel.join(" -> "), drops.join(", "));
 
-    if scout_report.candidates.is_empty() {
-        warn!("Scout: no candidates found (scanned={}, passed_quality={}) — check min_edge/min_confidence thresholds",
-            scout_report.total_scanned, scout_report.total_passed_quality);
-        return stats;
+        stats
     }
 
-    info!(
-        "Scout: {} candidates from {} scanned ({} passed quality)",
-        scout_report.candidates.len(),
-        scout_report.total_scanned,
-        scout_report.total_passed_quality,
-    );
+    fn add_result(&self, result: CandidateResult) {
+        let mut stats = self.stats.lock().unwrap();
+        stats.markets_analyzed += result.analyzed;
+        stats.markets_approved += result.approved;
+        stats.trades_placed += result.traded;
+        stats.api_cost += result.api_cost;
+        stats.outcomes.push(result.outcome);
+    }
+}
 
-    // Filter out recently analyzed markets
-    let candidates: Vec<_> = scout_report
-        .candidates
-        .into_iter()
-        .filter(|c| !store.was_recently_analyzed(&c.market.id, 4))
-        .collect();
+#[async_trait(?Send)]
+impl Strategy for TeamStrategy<'_> {
+    type Candidate = types::MarketCandidate;
 
-    if candidates.is_empty() {
-        info!("All candidates recently analyzed, skipping");
-        return stats;
+    fn name(&self) -> &'static str {
+        NAME
     }
 
-    // Data Analyst + Researcher (parallel)
-    let (data_packs, research_results) = tokio::join!(
-        data_analyst::analyze(enricher, clob, &candidates),
-        researcher::research(gemini, &candidates),
-    );
+    /// Scout: scan + filter + score (all categories), minus recently analyzed markets
+    /// and markets with an entry order still working
+    async fn propose(&self, cycle: &Cycle<'_>) -> Vec<types::MarketCandidate> {
+        let store = cycle.store;
 
-    stats.markets_researched = research_results.len();
+        // ═══════════════════════════════════════════
+        // PHASE 1: INTELLIGENCE (parallel)
+        // ═══════════════════════════════════════════
+        info!("═══ PHASE 1: SCOUT + RESEARCH ═══");
+        store.update_status("scanning", "Scanning markets for opportunities...").ok();
 
-    // Estimate Researcher API cost: ~$0.00015 per market researched
-    let research_cost = Decimal::from_str("0.00015").unwrap() * Decimal::from(research_results.len());
-    stats.api_cost += research_cost;
+        let scout_report = match scout::scan(cycle.scanner, cycle.config, self.max_candidates).await {
+            Ok(report) => report,
+            Err(e) => {
+                error!("Scout failed: {e}");
+                return Vec::new();
+            }
+        };
+        let mut stats = self.stats.lock().unwrap();
+        stats.markets_scanned = scout_report.total_scanned;
+        stats.markets_passed_quality = scout_report.total_passed_quality;
+
+        if scout_report.candidates.is_empty() {
+            warn!("Scout: no candidates found (scanned={}, passed_quality={}) — check min_edge/min_confidence thresholds",
+                scout_report.total_scanned, scout_report.total_passed_quality);
+            return Vec::new();
+        }
 
-    info!(
-        "Phase 1 complete: {} data packs, {} research dossiers (API cost: ${:.4})",
-        data_packs.len(),
-        research_results.len(),
-        research_cost
-    );
+        info!(
+            "Scout: {} candidates from {} scanned ({} passed quality, {} multi-market events)",
+            scout_report.candidates.len(),
+            scout_report.total_scanned,
+            scout_report.total_passed_quality,
+            scout_report.grouped_events,
+        );
 
-    // ═══════════════════════════════════════════
-    // PHASE 2-4: PARALLEL PER CANDIDATE
-    // Specialist Desk -> Bull/Bear -> Judge -> Risk -> Strategist -> Execute
-    // ═══════════════════════════════════════════
-    // ═══════════════════════════════════════════
-    // PHASE 2-4: PARALLEL PER CANDIDATE
-    // Specialist Desk -> Bull/Bear -> Judge -> Risk -> Strategist -> Execute
-    // ═══════════════════════════════════════════
-    info!("═══ PHASE 2-4: PARALLEL ANALYSIS ({} candidates) ═══", candidates.len().min(max_deep_analysis));
-    store.update_status("analyzing", &format!("Analyzing {} candidates...", candidates.len())).ok();
-
-    let analysis_limit = max_deep_analysis.min(candidates.len());
-
-    // Build futures for parallel candidate analysis
-    let futures: Vec<_> = candidates
-        .iter()
-        .take(analysis_limit)
-        .enumerate()
-        .map(|(i, candidate)| {
-            let market_id = candidate.market.id.clone();
-            let data_pack = data_packs.iter().find(|p| p.market_id == market_id).cloned();
-            let dossier = research_results
+        // Filter out recently analyzed markets (a working entry order counts as recent)
+        let working: Vec<String> = cycle
+            .venue
+            .resting_orders()
+            .into_iter()
+            .filter(|o| o.strategy.as_deref() == Some(NAME))
+            .map(|o| o.market_id)
+            .collect();
+        let (candidates, recent): (Vec<_>, Vec<_>) = scout_report
+            .candidates
+            .into_iter()
+            .partition(|c| !store.was_recently_analyzed(&c.market.id, 4) && !working.contains(&c.market.id));
+        stats.outcomes.extend(
+            recent
                 .iter()
-                .find(|(id, _)| *id == market_id)
-                .and_then(|(_, r)| r.as_ref().ok())
-                .cloned();
-
-            let sim_clone = sim.clone();
-            analyze_candidate(
-                i,
-                analysis_limit,
-                candidate,
-                data_pack,
-                dossier,
-                gemini,
-                claude,
-                portfolio,
-                config,
-                store,
-                telegram,
-                effective_max_pct,
-                sim_clone,
-            )
-        })
-        .collect();
-
-    let results = join_all(futures).await;
-
-    // Aggregate stats
-    for result in results {
-        stats.markets_analyzed += result.analyzed;
-        stats.markets_approved += result.approved;
-        stats.trades_placed += result.traded;
-        stats.api_cost += result.api_cost;
+                .map(|c| CandidateOutcome::dropped(&c.market, RejectReason::RecentlyAnalyzed)),
+        );
+
+        if candidates.is_empty() {
+            info!("All candidates recently analyzed, skipping");
+        }
+        candidates
     }
 
-    store.update_status("idle", "Cycle complete. Waiting for next run...").ok();
+    /// Data + research, then the per-candidate debate in parallel and the event
+    /// consistency pass. Every judge verdict that wants a position becomes an intent.
+    async fn decide(&self, cycle: &Cycle<'_>, candidates: Vec<types::MarketCandidate>) -> Vec<TradeIntent> {
+        let (config, store, llm) = (cycle.config, cycle.store, self.llm);
 
-    info!(
-        "═══ CYCLE COMPLETE: scanned={} researched={} analyzed={} approved={} traded={} (API cost: ${:.4}) ═══",
-        stats.markets_scanned,
-        stats.markets_researched,
-        stats.markets_analyzed,
-        stats.markets_approved,
-        stats.trades_placed,
-        stats.api_cost,
-    );
+        // Data Analyst + Researcher (parallel); timed as each candidate's Research stage
+        let research_started = std::time::Instant::now();
+        let (data_packs, research_results) = tokio::join!(
+            data_analyst::analyze(self.enricher, cycle.clob, store, config, cycle.hosts, &candidates),
+            researcher::research(llm, &candidates, config.research_concurrency),
+        );
+
+        let research_cost: Decimal = research_results
+            .iter()
+            .map(|(id, r, _)| match r {
+                Ok((_, usage)) => record_usage(store, "researcher", Some(id), usage),
+                Err(e) => record_failure(store, "researcher", Some(id), e),
+            })
+            .sum();
+        {
+            let mut stats = self.stats.lock().unwrap();
+            stats.markets_researched = research_results.len();
+            stats.api_cost += research_cost;
+        }
+
+        // Researcher base rates are forecasts too (YES/NO markets only — scores are on P(YES))
+        for (id, r, _) in &research_results {
+            let binary = candidates.iter().find(|c| c.market.id == *id && c.market.is_binary());
+            if let (Ok((dossier, _)), Some(c)) = (r, binary) {
+                let desk = detect_desk(&c.market.question, &c.market.category).to_string();
+                forecast::record(store, id, "researcher", &desk, llm.name(), dossier.base_rate);
+            }
+        }
+
+        info!(
+            "Phase 1 complete: {} data packs, {} research dossiers (API cost: ${:.4})",
+            data_packs.len(),
+            research_results.len(),
+            research_cost
+        );
+
+        // ═══════════════════════════════════════════
+        // PHASE 2-3: PARALLEL PER CANDIDATE
+        // Specialist Desk -> Bull/Bear -> Judge -> Event consistency
+        // ═══════════════════════════════════════════
+        info!("═══ PHASE 2-4: PARALLEL ANALYSIS ({} candidates) ═══", candidates.len().min(self.max_deep_analysis));
+        store.update_status("analyzing", &format!("Analyzing {} candidates...", candidates.len())).ok();
+
+        let analysis_limit = self.max_deep_analysis.min(candidates.len());
+        self.stats.lock().unwrap().outcomes.extend(
+            candidates
+                .iter()
+                .skip(analysis_limit)
+                .map(|c| CandidateOutcome::dropped(&c.market, RejectReason::OverAnalysisLimit)),
+        );
+
+        // Refit probability calibration from resolved trades once per cycle
+        let calibrator = if config.calibration_enabled {
+            match Calibrator::from_store(store, config.calibration_min_samples) {
+                Ok(c) if c.is_empty() => None,
+                Ok(c) => Some(c),
+                Err(e) => {
+                    warn!("Calibration fit failed: {e}");
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        // Build futures for parallel candidate analysis
+        let futures: Vec<_> = candidates
+            .iter()
+            .take(analysis_limit)
+            .enumerate()
+            .map(|(i, candidate)| {
+                let market_id = candidate.market.id.clone();
+                let data_pack = data_packs.iter().find(|p| p.market_id == market_id).cloned();
+                let research = research_results
+                    .iter()
+                    .find(|(id, _, _)| *id == market_id)
+                    .and_then(|(_, r, prompts)| r.as_ref().ok().map(|(dossier, _)| (dossier.clone(), prompts.clone())));
+
+                let input = DebateInput {
+                    index: i,
+                    total: analysis_limit,
+                    data_pack,
+                    research,
+                    calibrator: calibrator.as_ref(),
+                    research_started,
+                };
+                debate_candidate(candidate, input, llm, self.claude, cycle)
+            })
+            .collect();
+
+        let mut debated = Vec::new();
+        for r in join_all(futures).await {
+            match r {
+                Ok(d) => debated.push(d),
+                Err(done) => self.add_result(done),
+            }
+        }
+
+        // Sibling markets of one event must agree before anything is sized
+        let signals = reconcile_events(&mut debated);
+        self.stats.lock().unwrap().event_signals = signals;
 
-    stats
+        let mut intents = Vec::new();
+        let mut waiting = Vec::new();
+        for mut d in debated {
+            if !wants_position(&mut d, store) {
+                self.add_result(d.result);
+                continue;
+            }
+            intents.push(TradeIntent {
+                market_id: d.candidate.market.id.clone(),
+                question: d.candidate.market.question.clone(),
+                direction: d.verdict.direction_enum(),
+                outcome: d.outcome.clone(),
+                probability: d.verdict.fair_value_yes,
+                confidence: d.verdict.confidence,
+                edge: Decimal::from_f64((d.verdict.fair_value_yes - d.candidate.market.yes_price.to_f64().unwrap_or(0.5)).abs())
+                    .unwrap_or_default(),
+            });
+            waiting.push(d);
+        }
+        *self.debated.lock().unwrap() = waiting;
+        intents
+    }
+
+    /// Risk -> Strategist -> event exposure -> Validator -> Execute, in parallel per intent
+    async fn execute(&self, cycle: &Cycle<'_>, intents: Vec<TradeIntent>, capital: &Capital) -> Vec<Trade> {
+        let debated: Vec<Debated> = std::mem::take(&mut *self.debated.lock().unwrap())
+            .into_iter()
+            .filter(|d| intents.iter().any(|i| i.market_id == d.candidate.market.id))
+            .collect();
+
+        let budget = events::EventBudget::new(
+            &cycle.venue.ledger().open_trades(),
+            cycle.venue.balance(),
+            cycle.config.max_event_exposure_pct,
+        );
+        let decisions: Vec<_> = debated
+            .into_iter()
+            .map(|d| decide_candidate(d, self.claude, self.use_validator, cycle, &budget, capital))
+            .collect();
+
+        let mut trades = Vec::new();
+        for (result, trade) in join_all(decisions).await {
+            self.add_result(result);
+            trades.extend(trade);
+        }
+        trades
+    }
+}
+
+/// Persist one agent call's token usage (per role + model) and return its cost
+pub fn record_usage(store: &StateStore, role: &str, market_id: Option<&str>, usage: &LlmUsage) -> Decimal {
+    if let Err(e) = store.log_llm_usage(role, market_id, usage) {
+        warn!("llm_usage write failed for {role}: {e}");
+    }
+    usage.cost
+}
+
+/// Bill a call that never produced a usable reply: when structured output gave up
+/// after its repair retries, the spend and failure count are still recorded
+pub fn record_failure(store: &StateStore, role: &str, market_id: Option<&str>, err: &anyhow::Error) -> Decimal {
+    match err.downcast_ref::<StructuredError>() {
+        Some(failed) => record_usage(store, role, market_id, &failed.usage),
+        None => Decimal::ZERO,
+    }
 }
 
 /// Result from analyzing a single candidate
@@ -190,30 +354,39 @@ struct CandidateResult {
     approved: usize,
     traded: usize,
     api_cost: Decimal,
+    outcome: CandidateOutcome,
 }
 
-/// Analyze a single candidate through the full pipeline:
-/// Specialist Desk -> Bull + Bear -> Judge -> Risk -> Strategist -> Execute
-async fn analyze_candidate(
+/// What phase 1 gathered for one candidate, handed to `debate_candidate`
+struct DebateInput<'a> {
+    /// Position in this cycle's analysis batch, for progress logs
     index: usize,
     total: usize,
-    candidate: &types::MarketCandidate,
     data_pack: Option<types::DataPack>,
-    dossier: Option<types::ResearchDossier>,
-    gemini: &GeminiClient,
-    claude: &ClaudeClient,
-    portfolio: &Portfolio,
-    config: &Config,
-    store: &StateStore,
-    telegram: &TelegramAlert,
-    effective_max_pct: Decimal,
-    sim: SimConfig,
-) -> CandidateResult {
+    research: Option<(types::ResearchDossier, Vec<PromptRecord>)>,
+    calibrator: Option<&'a Calibrator>,
+    /// Start of the data + research phase, which the RESEARCH stage latency covers
+    research_started: std::time::Instant,
+}
+
+/// Analyze a single candidate through the first half of the pipeline:
+/// Specialist Desk -> Bull + Bear -> Judge (+ calibration).
+/// Candidates that drop out on the way come back as a finished `CandidateResult`.
+async fn debate_candidate(
+    candidate: &types::MarketCandidate,
+    input: DebateInput<'_>,
+    llm: &dyn LlmProvider,
+    claude: &dyn LlmProvider,
+    cycle: &Cycle<'_>,
+) -> Result<Debated, CandidateResult> {
+    let DebateInput { index, total, data_pack, research, calibrator, research_started } = input;
+    let (venue, config, store, effective_max_pct) = (cycle.venue, cycle.config, cycle.store, cycle.max_position_pct);
     let mut result = CandidateResult {
         analyzed: 0,
         approved: 0,
         traded: 0,
         api_cost: Decimal::ZERO,
+        outcome: CandidateOutcome::new(&candidate.market, Stage::Research).since(research_started),
     };
 
     let market_id = &candidate.market.id;
@@ -222,15 +395,17 @@ async fn analyze_candidate(
         Some(p) => p,
         None => {
             warn!("No data pack for {}", market_id);
-            return result;
+            result.outcome.reject(RejectReason::NoDataPack);
+            return Err(result);
         }
     };
 
-    let dossier = match dossier {
-        Some(d) => d,
+    let (dossier, research_prompts) = match research {
+        Some(r) => r,
         None => {
             warn!("No research dossier for {}", market_id);
-            return result;
+            result.outcome.reject(RejectReason::NoDossier);
+            return Err(result);
         }
     };
 
@@ -241,21 +416,81 @@ async fn analyze_candidate(
         &candidate.market.question[..candidate.market.question.len().min(50)]
     );
 
+    // Every prompt of this candidate's debate, kept with the trade for post-mortems
+    let transcript = Transcript::new();
+    transcript.extend(research_prompts);
+
+    // ── Categorical markets: the judge prices every outcome, Kelly picks one,
+    // and the rest of the pipeline debates and trades it as a YES bet ──
+    let focused: types::MarketCandidate;
+    let mut outcome_verdict = None;
+    let mut outcome = None;
+    let candidate = if candidate.market.is_binary() {
+        candidate
+    } else {
+        let outcomes_llm = transcript.recorder("judge_outcomes", llm);
+        let verdict = match judge::judge_outcomes(&outcomes_llm, candidate, &data_pack, &dossier).await {
+            Ok((v, usage)) => {
+                result.api_cost += record_usage(store, "judge_outcomes", Some(market_id), &usage);
+                v
+            }
+            Err(e) => {
+                result.api_cost += record_failure(store, "judge_outcomes", Some(market_id), &e);
+                warn!("Outcome judge failed: {e}");
+                result.outcome.reject(RejectReason::JudgeFailed);
+                return Err(result);
+            }
+        };
+
+        let probabilities: Vec<f64> = verdict.probabilities.iter().map(|(_, p)| *p).collect();
+        let prices: Vec<Decimal> = candidate.market.tokens.iter().map(|t| t.price).collect();
+        let pick = strategy::kelly_outcome(venue.balance(), &probabilities, &prices, effective_max_pct, config.kelly_fraction);
+        let Some((view, (k, kelly))) = pick.and_then(|(k, kelly)| {
+            Some((candidate.market.outcome_view(&verdict.probabilities[k].0)?, (k, kelly)))
+        }) else {
+            info!("  -> SKIP: no outcome priced below its probability ({})", candidate.market.outcome_summary());
+            result.outcome.reject(RejectReason::NoOutcomeEdge);
+            return Err(result);
+        };
+
+        info!(
+            "  Outcome: {} p={:.2} @ {} (full Kelly {:.1}%)",
+            verdict.probabilities[k].0,
+            probabilities[k],
+            prices[k],
+            kelly.full_kelly * Decimal::from(100),
+        );
+        focused = types::MarketCandidate { market: view, ..candidate.clone() };
+        outcome = Some(verdict.probabilities[k].0.clone());
+        outcome_verdict = Some(verdict);
+        &focused
+    };
+    // Forecast and decision scoring are on P(YES), which a categorical market doesn't have
+    let binary = outcome.is_none();
+
     // ── Specialist Desk ──
     let desk_type = detect_desk(&candidate.market.question, &candidate.market.category);
+    let desk = desk_type.to_string();
+    result.outcome.desk = Some(desk.clone());
+    let record_forecast = |role: &str, model: &str, probability_yes: f64| {
+        if binary {
+            forecast::record(store, market_id, role, &desk, model, probability_yes);
+        }
+    };
+    result.outcome.enter(Stage::Desk);
+    let desk_role = format!("{}_desk", desk_type).to_lowercase();
+    let desk_llm = transcript.recorder(&desk_role, llm);
     let desk_report = match desk_type {
-        DeskType::Crypto => crypto_desk::analyze(gemini, candidate, &data_pack, &dossier).await,
-        DeskType::Weather => weather_desk::analyze(gemini, candidate, &data_pack, &dossier).await,
-        DeskType::Sports => sports_desk::analyze(gemini, candidate, &data_pack, &dossier).await,
-        DeskType::General => general_desk::analyze(gemini, candidate, &data_pack, &dossier).await,
+        DeskType::Crypto => crypto_desk::analyze(&desk_llm, candidate, &data_pack, &dossier).await,
+        DeskType::Weather => weather_desk::analyze(&desk_llm, candidate, &data_pack, &dossier).await,
+        DeskType::Sports => sports_desk::analyze(&desk_llm, candidate, &data_pack, &dossier).await,
+        DeskType::General => general_desk::analyze(&desk_llm, candidate, &data_pack, &dossier).await,
     };
 
     let desk_report = match desk_report {
-        Ok(r) => {
-            // Estimate Gemini API cost: ~500 input + ~300 output tokens
-            // Tier 1: $0.10/1M input, $0.40/1M output
-            let est_cost = Decimal::from_str("0.00017").unwrap(); // ~$0.00017 per desk call
-            result.api_cost += est_cost;
+        Ok((r, usage)) => {
+            result.api_cost += record_usage(store, &desk_role, Some(market_id), &usage);
+            record_forecast(&desk_role, llm.name(), r.specialist_probability);
 
             info!(
                 "  Desk[{}]: prob={:.0}% conf={:.0}%",
@@ -266,21 +501,25 @@ async fn analyze_candidate(
             r
         }
         Err(e) => {
+            result.api_cost += record_failure(store, &desk_role, Some(market_id), &e);
             warn!("Desk analysis failed: {e}");
-            return result;
+            result.outcome.reject(RejectReason::DeskFailed);
+            return Err(result);
         }
     };
 
     // ── Bull + Bear (parallel) ──
+    result.outcome.enter(Stage::Debate);
+    let (bull_llm, bear_llm) = (transcript.recorder("bull", llm), transcript.recorder("bear", llm));
     let (bull_result, bear_result) = tokio::join!(
-        bull_analyst::analyze(gemini, candidate, &data_pack, &dossier, &desk_report),
-        bear_analyst::analyze(gemini, candidate, &data_pack, &dossier, &desk_report),
+        bull_analyst::analyze(&bull_llm, candidate, &data_pack, &dossier, &desk_report),
+        bear_analyst::analyze(&bear_llm, candidate, &data_pack, &dossier, &desk_report),
     );
 
     let bull = match bull_result {
-        Ok(b) => {
-            // Estimate cost for Bull analyst
-            result.api_cost += Decimal::from_str("0.00015").unwrap();
+        Ok((b, usage)) => {
+            result.api_cost += record_usage(store, "bull", Some(market_id), &usage);
+            record_forecast("bull", llm.name(), b.probability_yes);
             info!(
                 "  Bull: {:.0}% YES ({})",
                 b.probability_yes * 100.0,
@@ -289,15 +528,17 @@ async fn analyze_candidate(
             b
         }
         Err(e) => {
+            result.api_cost += record_failure(store, "bull", Some(market_id), &e);
             warn!("Bull analysis failed: {e}");
-            return result;
+            result.outcome.reject(RejectReason::BullFailed);
+            return Err(result);
         }
     };
 
     let bear = match bear_result {
-        Ok(b) => {
-            // Estimate cost for Bear analyst
-            result.api_cost += Decimal::from_str("0.00015").unwrap();
+        Ok((b, usage)) => {
+            result.api_cost += record_usage(store, "bear", Some(market_id), &usage);
+            record_forecast("bear", llm.name(), 1.0 - b.probability_no);
             info!(
                 "  Bear: {:.0}% NO ({})",
                 b.probability_no * 100.0,
@@ -306,25 +547,29 @@ async fn analyze_candidate(
             b
         }
         Err(e) => {
+            result.api_cost += record_failure(store, "bear", Some(market_id), &e);
             warn!("Bear analysis failed: {e}");
-            return result;
+            result.outcome.reject(RejectReason::BearFailed);
+            return Err(result);
         }
     };
 
     // ── Judge ──
+    result.outcome.enter(Stage::Judge);
     // Force Gemini-only (cost optimization)
-    let verdict = match judge::judge(
-        gemini, claude, false, candidate, &bull, &bear, &data_pack, &dossier, &desk_report,
+    let (judge_llm, judge_claude) = (transcript.recorder("judge", llm), transcript.recorder("judge", claude));
+    let mut verdict = match judge::judge(
+        &judge_llm, &judge_claude, false, candidate, &bull, &bear, &data_pack, &dossier, &desk_report,
     )
     .await
     {
-        Ok(v) => {
-            // Estimate cost: Gemini ~$0.10/1M in + $0.40/1M out
-            let judge_cost = Decimal::from_str("0.0002").unwrap(); // Gemini
-            result.api_cost += judge_cost;
+        Ok((v, usage)) => {
+            result.api_cost += record_usage(store, "judge", Some(market_id), &usage);
+            record_forecast("judge", llm.name(), v.fair_value_yes);
 
             info!(
-                "  Judge[Gemini]: fair={:.2} conf={:.2} -> {}",
+                "  Judge[{}]: fair={:.2} conf={:.2} -> {}",
+                llm.name(),
                 v.fair_value_yes,
                 v.confidence,
                 v.direction
@@ -332,34 +577,176 @@ async fn analyze_candidate(
             v
         }
         Err(e) => {
+            result.api_cost += record_failure(store, "judge", Some(market_id), &e);
             warn!("Judge failed: {e}");
-            return result;
+            result.outcome.reject(RejectReason::JudgeFailed);
+            return Err(result);
         }
     };
 
     result.analyzed = 1;
 
-    if verdict.direction_enum() == Direction::Skip {
-        info!("  -> SKIP: {}", verdict.reasoning);
-        return result;
+    // The market price is the baseline every agent has to beat
+    let market_yes = candidate.market.yes_price.to_f64().unwrap_or(0.5);
+    record_forecast("market", "market", market_yes);
+
+    // ── Calibration ──
+    if let Some(calibrator) = calibrator {
+        calibrator.apply(&mut verdict, &desk, llm.name(), market_yes);
+        record_forecast("judge_calibrated", llm.name(), verdict.fair_value_yes);
+    }
+    result.outcome.confidence = Some(verdict.confidence);
+
+    Ok(Debated {
+        result,
+        candidate: candidate.clone(),
+        outcome,
+        outcome_verdict,
+        desk_type,
+        dossier,
+        desk_report,
+        bull,
+        bear,
+        verdict,
+        transcript,
+        judge_model: llm.name().to_string(),
+    })
+}
+
+/// A candidate that made it through the judge, waiting for its event's consistency pass
+struct Debated {
+    result: CandidateResult,
+    /// The candidate as traded (a categorical market's chosen-outcome view)
+    candidate: types::MarketCandidate,
+    outcome: Option<String>,
+    outcome_verdict: Option<types::OutcomeVerdict>,
+    desk_type: DeskType,
+    dossier: types::ResearchDossier,
+    desk_report: types::DeskReport,
+    bull: types::BullCase,
+    bear: types::BearCase,
+    verdict: types::DevilsVerdict,
+    transcript: Transcript,
+    judge_model: String,
+}
+
+/// Event consistency pass over every debated candidate: fair values of sibling
+/// markets are reconciled per event (YES/NO markets only) before any sizing
+fn reconcile_events(debated: &mut [Debated]) -> Vec<events::Signal> {
+    let mut groups: Vec<(String, events::Relation)> = Vec::new();
+    for d in debated.iter().filter(|d| d.outcome.is_none() && d.candidate.event.is_grouped()) {
+        let event = &d.candidate.event;
+        if !groups.iter().any(|(key, _)| *key == event.key) {
+            groups.push((event.key.clone(), event.relation));
+        }
+    }
+
+    let mut signals = Vec::new();
+    for (key, relation) in groups {
+        let mut legs: Vec<events::Leg<'_>> = debated
+            .iter_mut()
+            .filter(|d| d.outcome.is_none() && d.candidate.event.key == key)
+            .map(|d| {
+                d.result.outcome.enter(Stage::Event);
+                events::Leg {
+                    market_id: &d.candidate.market.id,
+                    threshold: d.candidate.event.threshold,
+                    market_yes: d.candidate.market.yes_price.to_f64().unwrap_or(0.5),
+                    verdict: &mut d.verdict,
+                }
+            })
+            .collect();
+        if legs.len() > 1 {
+            info!("Event [{relation}] {} markets: {key}", legs.len());
+        }
+        signals.extend(events::reconcile(&key, relation, &mut legs));
     }
+    signals
+}
+
+/// Settle verdicts that don't want a position (SKIP, or a categorical outcome the
+/// debate turned down) and return false for them; the rest go on to be decided
+fn wants_position(d: &mut Debated, store: &StateStore) -> bool {
+    let market_yes = d.candidate.market.yes_price.to_f64().unwrap_or(0.5);
+    d.result.outcome.edge = Some((d.verdict.fair_value_yes - market_yes).abs());
+
+    if d.verdict.direction_enum() == Direction::Skip {
+        info!("  -> SKIP: {}", d.verdict.reasoning);
+        if d.outcome.is_none() {
+            resolution::track(store, &d.candidate.market, Decision::Skip, Direction::Skip, d.verdict.fair_value_yes);
+        }
+        d.result.outcome.reject(RejectReason::JudgeSkip);
+        return false;
+    }
+    // The debate can only confirm the picked outcome; its view has no NO token to buy
+    if d.outcome.is_some() && d.verdict.direction_enum() != Direction::Yes {
+        info!("  -> SKIP: debate rejected outcome {}", d.outcome.as_deref().unwrap_or("?"));
+        d.result.outcome.reject(RejectReason::NoOutcomeEdge);
+        return false;
+    }
+    true
+}
+
+/// Risk -> Strategist -> event exposure -> strategy capital -> Validator -> Execute
+/// for one debated candidate that wants a position
+async fn decide_candidate(
+    d: Debated,
+    claude: &dyn LlmProvider,
+    use_validator: bool,
+    cycle: &Cycle<'_>,
+    budget: &events::EventBudget,
+    capital: &Capital,
+) -> (CandidateResult, Option<Trade>) {
+    let (venue, config, store, telegram, effective_max_pct) =
+        (cycle.venue, cycle.config, cycle.store, cycle.telegram, cycle.max_position_pct);
+    let Debated {
+        mut result,
+        candidate,
+        outcome,
+        outcome_verdict,
+        desk_type,
+        dossier,
+        desk_report,
+        bull,
+        bear,
+        verdict,
+        transcript,
+        judge_model,
+    } = d;
+    let market_id = &candidate.market.id;
+
+    let binary = outcome.is_none();
+    let track = |decision: Decision, direction: Direction, fair_value_yes: f64| {
+        if binary {
+            resolution::track(store, &candidate.market, decision, direction, fair_value_yes);
+        }
+    };
 
     // ── Risk Manager ──
-    let risk = risk_manager::check(&verdict, portfolio, config, effective_max_pct, candidate.market.yes_price);
+    result.outcome.enter(Stage::Risk);
+    let risk = risk_manager::check(&verdict, venue.ledger(), config, effective_max_pct, candidate.market.yes_price);
     if !risk.approved {
         info!("  -> REJECTED by Risk Manager: {}", risk.reason);
-        return result;
+        track(Decision::RiskRejected, verdict.direction_enum(), verdict.fair_value_yes);
+        result.outcome.reject(RejectReason::RiskRejected);
+        return (result, None);
     }
 
     // ── Strategist ──
+    result.outcome.enter(Stage::Strategist);
     let mut plan = strategist::plan(&verdict, &risk, &candidate.market);
     result.approved = 1;
+    result.outcome.edge = plan.edge.to_f64();
+    result.outcome.bet_size = Some(plan.bet_size);
 
     // Enrich TradePlan with agent trail for paper trading
     plan.specialist_desk = Some(format!("{}", desk_type));
     plan.bull_probability = Some(bull.probability_yes);
     plan.bear_probability = Some(bear.probability_no);
-    plan.judge_model = Some("gemini".to_string());
+    plan.judge_model = Some(judge_model);
+    plan.raw_fair_value_yes = Some(verdict.raw_fair_value_yes);
+    plan.raw_confidence = Some(verdict.raw_confidence);
+    plan.outcome = outcome;
 
     // Edge vs SL filter
     if plan.stop_loss_pct > Decimal::ZERO && plan.edge < plan.stop_loss_pct {
@@ -368,17 +755,54 @@ async fn analyze_candidate(
             plan.edge * Decimal::from(100),
             plan.stop_loss_pct * Decimal::from(100),
         );
-        return result;
+        track(Decision::EdgeBelowStop, plan.direction, verdict.fair_value_yes);
+        result.outcome.reject(RejectReason::EdgeBelowStop);
+        return (result, None);
+    }
+
+    // ── Event exposure: siblings share one MAX_EVENT_EXPOSURE_PCT budget ──
+    let event = &candidate.event.key;
+    let granted = budget.reserve(event, plan.bet_size);
+    if granted < plan.bet_size {
+        if granted < Decimal::ONE {
+            info!("  -> SKIP: event exposure cap reached ({})", &event[..event.len().min(50)]);
+            budget.release(event, granted);
+            track(Decision::RiskRejected, plan.direction, verdict.fair_value_yes);
+            result.outcome.reject(RejectReason::EventExposure);
+            return (result, None);
+        }
+        info!("  Event cap: bet ${} -> ${granted}", plan.bet_size);
+        plan.bet_size = granted;
+        result.outcome.bet_size = Some(granted);
+    }
+
+    // ── Strategy capital: the team's share under STRATEGY_ALLOCATIONS ──
+    let funded = capital.reserve(plan.bet_size);
+    if funded < plan.bet_size {
+        budget.release(event, plan.bet_size - funded);
+        if funded < Decimal::ONE {
+            info!("  -> SKIP: {NAME} capital allocation used up");
+            budget.release(event, funded);
+            capital.release(funded);
+            track(Decision::RiskRejected, plan.direction, verdict.fair_value_yes);
+            result.outcome.reject(RejectReason::StrategyCapital);
+            return (result, None);
+        }
+        info!("  Strategy cap: bet ${} -> ${funded}", plan.bet_size);
+        plan.bet_size = funded;
+        result.outcome.bet_size = Some(funded);
     }
 
     // ══ CLAUDE FINAL VALIDATOR (Hakim Akhir - Threshold 60%) ══
-    if claude.is_configured() {
+    result.outcome.enter(Stage::Validator);
+    let mut final_verdict = None;
+    if !use_validator {
+        warn!("  ⚠️  Claude Final Validator SKIPPED (AI budget)");
+    } else if claude.is_configured() {
         info!("  ⚖️  Validasi Hakim Akhir (Claude Sonnet)...");
-        match judge::claude_final_validator(claude, &plan).await {
-            Ok(claude_verdict) => {
-                // Tambahkan API cost Claude (Sonnet: $3/1M in + $15/1M out)
-                // Estimasi: ~500 input + ~400 output tokens = ~$0.007
-                result.api_cost += Decimal::from_str("0.007").unwrap();
+        match judge::claude_final_validator(&transcript.recorder("final_validator", claude), &plan).await {
+            Ok((claude_verdict, usage)) => {
+                result.api_cost += record_usage(store, "final_validator", Some(market_id), &usage);
 
                 if !claude_verdict.approved {
                     warn!(
@@ -387,7 +811,11 @@ async fn analyze_candidate(
                         claude_verdict.win_probability * 100.0,
                         claude_verdict.reasoning
                     );
-                    return result;
+                    track(Decision::ValidatorRejected, plan.direction, verdict.fair_value_yes);
+                    budget.release(event, plan.bet_size);
+                    capital.release(plan.bet_size);
+                    result.outcome.reject(RejectReason::ValidatorRejected);
+                    return (result, None);
                 }
 
                 info!(
@@ -396,8 +824,10 @@ async fn analyze_candidate(
                     claude_verdict.confidence * 100.0,
                     claude_verdict.risk_level
                 );
+                final_verdict = Some(claude_verdict);
             }
             Err(e) => {
+                result.api_cost += record_failure(store, "final_validator", Some(market_id), &e);
                 error!("  ⚠️  Claude validation failed: {e} — proceeding without validation");
             }
         }
@@ -406,10 +836,56 @@ async fn analyze_candidate(
     }
 
     // ── Executor ──
+    result.outcome.enter(Stage::Execute);
     store.update_status("trading", &format!("Executing {} trade...", verdict.direction)).ok();
-    if let Some(_trade) = executor::execute(&plan, portfolio, store, telegram, &sim).await {
-        result.traded = 1;
-    }
+    let (trade, resting) = match TimeInForce::parse(&config.entry_order_type).filter(|_| binary) {
+        Some(tif) => {
+            let entry = strategy::yes_book(&candidate.market, cycle.clob).await.and_then(|book| {
+                executor::entry_order(&plan, &book, tif, config.entry_improve, config.entry_order_ttl_minutes, Utc::now())
+                    .map(|order| (order, book))
+            });
+            match entry {
+                Some((order, book)) => match executor::place_entry(&plan, order, &book, venue, store, telegram).await {
+                    Some(placed) => (placed.fill, placed.resting.then_some(placed.order)),
+                    None => (None, None),
+                },
+                None => {
+                    warn!("  -> No usable book for a {tif} entry");
+                    (None, None)
+                }
+            }
+        }
+        None => (executor::execute(&plan, venue, store, telegram).await, None),
+    };
+    // A patient entry still working files its dossier under the order id
+    let filed_under = trade.as_ref().map(|t| t.id.clone()).or_else(|| resting.as_ref().map(|o| o.id.clone()));
+    let decision = match filed_under {
+        Some(id) => {
+            result.traded = usize::from(trade.is_some());
+            result.outcome.finish();
+            let dossier = types::TradeDossier {
+                research: dossier,
+                desk_report,
+                bull,
+                bear,
+                verdict: verdict.clone(),
+                final_verdict,
+                prompts: transcript.calls(),
+                outcome_verdict,
+            };
+            if let Err(e) = store.save_trade_dossier(&id, market_id, &dossier) {
+                warn!("trade_dossiers write failed for {id}: {e}");
+            }
+            if trade.is_some() { Decision::Traded } else { Decision::Resting }
+        }
+        None => {
+            budget.release(event, plan.bet_size);
+            capital.release(plan.bet_size);
+            result.outcome.reject(RejectReason::NotFilled);
+            Decision::NotFilled
+        }
+    };
+    track(decision, plan.direction, verdict.fair_value_yes);
 
-    result
+    (result, trade)
 }
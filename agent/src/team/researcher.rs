@@ -1,6 +1,9 @@
-use crate::analyzer::gemini::GeminiClient;
+use crate::analyzer::structured::check_probability;
+use crate::analyzer::transcript::{PromptRecord, Transcript};
+use crate::analyzer::{call_structured, LlmProvider, LlmUsage, StructuredOutput};
 use crate::team::types::{MarketCandidate, ResearchDossier};
 use anyhow::Result;
+use futures::stream::{self, StreamExt};
 use rust_decimal::Decimal;
 use serde::Deserialize;
 use tracing::{info, warn};
@@ -21,107 +24,110 @@ RULES:
 
 /// Agent 2: Researcher — Gemini AI research per candidate
 /// Gathers news relevance, fact checks, base rates, and counter-arguments.
+/// Up to `concurrency` candidates are researched at once (rate limits and
+/// timeouts live on the provider); results keep candidate order.
+/// Each result carries the prompts sent for that market.
 pub async fn research(
-    gemini: &GeminiClient,
+    llm: &dyn LlmProvider,
     candidates: &[MarketCandidate],
-) -> Vec<(String, Result<ResearchDossier>)> {
-    let mut results = Vec::with_capacity(candidates.len());
+    concurrency: usize,
+) -> Vec<(String, Result<(ResearchDossier, LlmUsage)>, Vec<PromptRecord>)> {
+    stream::iter(candidates)
+        .map(|candidate| research_one(llm, candidate))
+        .buffered(concurrency.max(1))
+        .collect()
+        .await
+}
+
+async fn research_one(
+    llm: &dyn LlmProvider,
+    candidate: &MarketCandidate,
+) -> (String, Result<(ResearchDossier, LlmUsage)>, Vec<PromptRecord>) {
+    let market = &candidate.market;
+    // Categorical markets have no single YES price: show every outcome instead
+    let pricing = if market.is_binary() {
+        format!("Current YES price: {} ({}% implied)", market.yes_price, (market.yes_price * Decimal::from(100)).round())
+    } else {
+        format!("Outcomes (implied): {}", market.outcome_summary())
+    };
+    let user_msg = format!(
+        "Research this prediction market:\n\
+        Question: {}\n\
+        Description: {}\n\
+        Category: {}\n\
+        End Date: {}\n\
+        {}\n\
+        Volume: ${}\n\
+        \n\
+        Provide factual research to help estimate the true probability.",
+        market.question,
+        &market.description[..market.description.len().min(400)],
+        market.category,
+        market.end_date,
+        pricing,
+        market.volume.round(),
+    );
 
-    for candidate in candidates {
-        let market = &candidate.market;
-        let user_msg = format!(
-            "Research this prediction market:\n\
-            Question: {}\n\
-            Description: {}\n\
-            Category: {}\n\
-            End Date: {}\n\
-            Current YES price: {} ({}% implied)\n\
-            Volume: ${}\n\
-            \n\
-            Provide factual research to help estimate the true probability.",
-            market.question,
-            &market.description[..market.description.len().min(400)],
-            market.category,
-            market.end_date,
-            market.yes_price,
-            (market.yes_price * Decimal::from(100)).round(),
-            market.volume.round(),
-        );
+    let transcript = Transcript::new();
+    let recorder = transcript.recorder("researcher", llm);
+    let result = call_structured::<ResearchResponse>(&recorder, RESEARCH_SYSTEM, &user_msg, 400).await;
+    let prompts = transcript.calls();
 
-        match gemini.call(RESEARCH_SYSTEM, &user_msg, 400).await {
-            Ok((text, cost)) => {
-                info!(
-                    "Researcher: {} (${:.4})",
-                    &market.question[..market.question.len().min(40)],
-                    cost
-                );
-                match parse_research(&text, &market.id) {
-                    Ok(dossier) => results.push((market.id.clone(), Ok(dossier))),
-                    Err(e) => {
-                        warn!("Research parse failed for {}: {e}", &market.id[..8.min(market.id.len())]);
-                        results.push((market.id.clone(), Err(e)));
-                    }
-                }
-            }
-            Err(e) => {
-                warn!("Research call failed: {e}");
-                results.push((market.id.clone(), Err(e)));
-            }
+    match result {
+        Ok((parsed, usage)) => {
+            info!(
+                "Researcher: {} (${:.4})",
+                &market.question[..market.question.len().min(40)],
+                usage.cost
+            );
+            (market.id.clone(), Ok((parsed.into_dossier(&market.id), usage)), prompts)
+        }
+        Err(e) => {
+            warn!("Research failed for {}: {e}", &market.id[..8.min(market.id.len())]);
+            (market.id.clone(), Err(e), prompts)
         }
     }
-
-    results
 }
 
-fn parse_research(text: &str, market_id: &str) -> Result<ResearchDossier> {
-    let json_str = extract_json(text);
+#[derive(Deserialize)]
+struct ResearchResponse {
+    news_relevance: String,
+    fact_check: String,
+    base_rate: f64,
+    counter_arguments: String,
+    #[serde(default)]
+    key_factors: Vec<String>,
+}
 
-    #[derive(Deserialize)]
-    struct Resp {
-        news_relevance: String,
-        fact_check: String,
-        base_rate: f64,
-        counter_arguments: String,
-        #[serde(default)]
-        key_factors: Vec<String>,
+impl StructuredOutput for ResearchResponse {
+    fn schema() -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "news_relevance": { "type": "string" },
+                "fact_check": { "type": "string" },
+                "base_rate": { "type": "number" },
+                "counter_arguments": { "type": "string" },
+                "key_factors": { "type": "array", "items": { "type": "string" } }
+            },
+            "required": ["news_relevance", "fact_check", "base_rate", "counter_arguments", "key_factors"]
+        })
     }
 
-    let parsed: Resp = serde_json::from_str(&json_str)
-        .map_err(|e| anyhow::anyhow!("Research JSON parse: {e} | text: {}", &json_str[..json_str.len().min(200)]))?;
-
-    Ok(ResearchDossier {
-        market_id: market_id.to_string(),
-        news_relevance: parsed.news_relevance,
-        fact_check: parsed.fact_check,
-        base_rate: parsed.base_rate.clamp(0.01, 0.99),
-        counter_arguments: parsed.counter_arguments,
-        key_factors: parsed.key_factors,
-    })
+    fn validate(&self) -> Result<()> {
+        check_probability("base_rate", self.base_rate)
+    }
 }
 
-fn extract_json(text: &str) -> String {
-    // Find JSON object
-    if let Some(start) = text.find('{') {
-        let mut depth = 0;
-        for (i, ch) in text[start..].char_indices() {
-            match ch {
-                '{' => depth += 1,
-                '}' => {
-                    depth -= 1;
-                    if depth == 0 {
-                        return text[start..=start + i].to_string();
-                    }
-                }
-                _ => {}
-            }
-        }
-    }
-    // Try code block
-    if let Some(s) = text.find("```json") {
-        let after = &text[s + 7..];
-        if let Some(e) = after.find("```") {
-            return after[..e].trim().to_string();
+impl ResearchResponse {
+    fn into_dossier(self, market_id: &str) -> ResearchDossier {
+        ResearchDossier {
+            market_id: market_id.to_string(),
+            news_relevance: self.news_relevance,
+            fact_check: self.fact_check,
+            base_rate: self.base_rate.clamp(0.01, 0.99),
+            counter_arguments: self.counter_arguments,
+            key_factors: self.key_factors,
         }
     }
-    text.to_string()
 }
@@ -1,10 +1,12 @@
 use crate::config::Config;
 use crate::data::polymarket::GammaScanner;
-use crate::team::types::{MarketCandidate, ScoutReport};
+use crate::team::events::EventKey;
+use crate::team::types::{detect_desk, DeskType, MarketCandidate, ScoutReport};
 use crate::types::Market;
 use anyhow::Result;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use rust_decimal::prelude::*;
+use std::collections::HashMap;
 use tracing::info;
 
 /// Agent 1: Scout — Market discovery (no AI)
@@ -16,12 +18,100 @@ pub async fn scan(
     max_candidates: usize,
 ) -> Result<ScoutReport> {
     let markets = scanner.scan(config.max_markets_to_scan).await?;
+    let filter = CategoryFilter::parse(&config.category_filter);
+    Ok(select_candidates(markets, &filter, max_candidates, Utc::now()))
+}
+
+/// Parsed `CATEGORY_FILTER`: comma-separated terms, e.g. `all`, `crypto`,
+/// `crypto,weather`, `no_politics`, `sports,!nba`.
+///
+/// - `all` (or empty) allows everything
+/// - `no_X`, `!X` or `-X` excludes X; any other term includes it
+/// - desk names (`crypto`, `weather`, `sports`, `general`) match via `detect_desk`,
+///   `politics` matches the category or election/president questions,
+///   anything else matches as a substring of the market category
+///
+/// A market passes if it matches at least one include (or there are none)
+/// and no exclude.
+#[derive(Debug, Clone, Default)]
+pub struct CategoryFilter {
+    include: Vec<String>,
+    exclude: Vec<String>,
+}
+
+impl CategoryFilter {
+    pub fn parse(spec: &str) -> Self {
+        let mut filter = Self::default();
+        for term in spec.split(',').map(|t| t.trim().to_lowercase()) {
+            if term.is_empty() || term == "all" {
+                continue;
+            }
+            let negated = ["no_", "!", "-"]
+                .iter()
+                .find_map(|p| term.strip_prefix(p));
+            match negated {
+                Some(t) if !t.is_empty() => filter.exclude.push(t.to_string()),
+                Some(_) => {}
+                None => filter.include.push(term),
+            }
+        }
+        filter
+    }
+
+    pub fn is_all(&self) -> bool {
+        self.include.is_empty() && self.exclude.is_empty()
+    }
+
+    pub fn matches(&self, m: &Market) -> bool {
+        let desk = detect_desk(&m.question, &m.category);
+        let hit = |term: &String| term_matches(term, m, desk);
+
+        (self.include.is_empty() || self.include.iter().any(hit))
+            && !self.exclude.iter().any(hit)
+    }
+}
+
+impl std::fmt::Display for CategoryFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let terms: Vec<String> = self
+            .include
+            .iter()
+            .cloned()
+            .chain(self.exclude.iter().map(|t| format!("no_{t}")))
+            .collect();
+        write!(f, "{}", terms.join(","))
+    }
+}
+
+fn term_matches(term: &str, m: &Market, desk: DeskType) -> bool {
+    let c = m.category.to_lowercase();
+    match term {
+        "crypto" => desk == DeskType::Crypto,
+        "weather" => desk == DeskType::Weather,
+        "sports" => desk == DeskType::Sports,
+        "general" => desk == DeskType::General,
+        "politics" => {
+            let q = m.question.to_lowercase();
+            c.contains("politics") || q.contains("election") || q.contains("president")
+        }
+        other => c.contains(other),
+    }
+}
+
+/// Quality filter + heuristic ranking over an already-fetched market list.
+/// Shared by the live scan and the backtest replay (`now` drives the timing heuristics).
+pub fn select_candidates(
+    markets: Vec<Market>,
+    filter: &CategoryFilter,
+    max_candidates: usize,
+    now: DateTime<Utc>,
+) -> ScoutReport {
     let total_scanned = markets.len();
 
-    // Apply basic quality filters (no category restriction)
+    // Apply basic quality filters + CATEGORY_FILTER
     let filtered: Vec<Market> = markets
         .into_iter()
-        .filter(|m| passes_quality_filter(m))
+        .filter(|m| passes_quality_filter(m) && filter.matches(m))
         .collect();
 
     let total_passed_quality = filtered.len();
@@ -30,8 +120,9 @@ pub async fn scan(
     let mut candidates: Vec<MarketCandidate> = filtered
         .into_iter()
         .map(|m| {
-            let (score, reason) = score_candidate(&m);
+            let (score, reason) = score_candidate(&m, now);
             MarketCandidate {
+                event: EventKey::of(&m),
                 market: m,
                 quality_score: score,
                 reason,
@@ -42,20 +133,35 @@ pub async fn scan(
 
     candidates.sort_by(|a, b| b.quality_score.partial_cmp(&a.quality_score).unwrap_or(std::cmp::Ordering::Equal));
     candidates.truncate(max_candidates);
+    let grouped_events = group_by_event(&mut candidates);
 
+    let scope = if filter.is_all() { String::new() } else { format!(" [{filter}]") };
     info!(
-        "Scout: {} scanned -> {} quality -> {} candidates (top {})",
-        total_scanned, total_passed_quality, candidates.len(), max_candidates
+        "Scout: {} scanned -> {} quality{} -> {} candidates (top {})",
+        total_scanned, total_passed_quality, scope, candidates.len(), max_candidates
     );
 
-    Ok(ScoutReport {
+    ScoutReport {
         candidates,
         total_scanned,
         total_passed_quality,
-    })
+        grouped_events,
+    }
+}
+
+/// Keep each event's markets next to each other, ranked by the event's best
+/// candidate, so an analysis limit cuts whole events rather than splitting ladders.
+/// Returns how many events have more than one candidate.
+fn group_by_event(candidates: &mut [MarketCandidate]) -> usize {
+    let mut best: HashMap<String, (usize, usize)> = HashMap::new(); // key -> (first rank, members)
+    for (rank, c) in candidates.iter().enumerate() {
+        best.entry(c.event.key.clone()).or_insert((rank, 0)).1 += 1;
+    }
+    candidates.sort_by_key(|c| best[&c.event.key].0);
+    best.values().filter(|&&(_, n)| n > 1).count()
 }
 
-/// Basic quality filter — accepts all categories
+/// Basic quality filter (category restriction is applied separately)
 fn passes_quality_filter(m: &Market) -> bool {
     let yes = m.yes_price.to_f64().unwrap_or(0.5);
     // Skip extreme prices (almost certainly resolved or illiquid)
@@ -71,7 +177,7 @@ fn passes_quality_filter(m: &Market) -> bool {
 }
 
 /// Category-agnostic scoring with domain bonuses
-fn score_candidate(m: &Market) -> (f64, String) {
+fn score_candidate(m: &Market, now: DateTime<Utc>) -> (f64, String) {
     let mut score = 0.0_f64;
     let mut reasons = Vec::new();
     let q = m.question.to_lowercase();
@@ -115,7 +221,7 @@ fn score_candidate(m: &Market) -> (f64, String) {
 
     // ── Time Pressure (closer = more actionable, strongly prefer short-term) ──
     if !m.end_date.is_empty() {
-        if let Some(days) = parse_days_remaining(&m.end_date) {
+        if let Some(days) = parse_days_remaining(&m.end_date, now) {
             if days <= 3 {
                 score += 30.0; // Strong preference for urgent markets
                 reasons.push("urgent_<3d");
@@ -185,7 +291,6 @@ fn score_candidate(m: &Market) -> (f64, String) {
     }
 
     // ── Timing: US Market Hours bonus (14:00-22:00 UTC = 9AM-5PM EST) ──
-    let now = Utc::now();
     let hour = now.format("%H").to_string().parse::<u32>().unwrap_or(12);
     if hour >= 14 && hour < 22 {
         score += 10.0;
@@ -202,8 +307,8 @@ fn score_candidate(m: &Market) -> (f64, String) {
     (score, reasons.join(", "))
 }
 
-fn parse_days_remaining(end_date: &str) -> Option<i64> {
-    let now = chrono::Utc::now().date_naive();
+fn parse_days_remaining(end_date: &str, now: DateTime<Utc>) -> Option<i64> {
+    let now = now.date_naive();
 
     // Try ISO datetime
     let cleaned = end_date.replace('Z', "+00:00");
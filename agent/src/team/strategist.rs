@@ -1,5 +1,6 @@
 use crate::team::types::{DevilsVerdict, RiskDecision, TradeMode, TradePlan};
 use crate::types::{Direction, Market};
+use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
 use rust_decimal::prelude::*;
 use tracing::info;
@@ -10,6 +11,16 @@ pub fn plan(
     verdict: &DevilsVerdict,
     risk: &RiskDecision,
     market: &Market,
+) -> TradePlan {
+    plan_at(verdict, risk, market, Utc::now())
+}
+
+/// Same as `plan`, with days-to-expiry measured from `now` (backtest clock)
+pub fn plan_at(
+    verdict: &DevilsVerdict,
+    risk: &RiskDecision,
+    market: &Market,
+    now: DateTime<Utc>,
 ) -> TradePlan {
     let direction = verdict.direction_enum();
     let fair_value = Decimal::from_f64(verdict.fair_value_yes).unwrap_or(Decimal::new(50, 2));
@@ -24,7 +35,7 @@ pub fn plan(
     let edge = (fair_value - market.yes_price).abs();
 
     // Determine days until expiry
-    let days_left = parse_days_remaining(&market.end_date).unwrap_or(30);
+    let days_left = parse_days_remaining(&market.end_date, now).unwrap_or(30);
 
     // Classify trade mode
     let mode = classify_mode(edge, confidence, days_left);
@@ -103,6 +114,9 @@ pub fn plan(
         bull_probability: None,
         bear_probability: None,
         judge_model: None,
+        raw_fair_value_yes: None,
+        raw_confidence: None,
+        outcome: None,
     }
 }
 
@@ -125,8 +139,8 @@ fn classify_mode(edge: Decimal, confidence: Decimal, days_left: i64) -> TradeMod
     TradeMode::Swing
 }
 
-fn parse_days_remaining(end_date: &str) -> Option<i64> {
-    let now = chrono::Utc::now().date_naive();
+fn parse_days_remaining(end_date: &str, now: DateTime<Utc>) -> Option<i64> {
+    let now = now.date_naive();
 
     let cleaned = end_date.replace('Z', "+00:00");
     if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(&cleaned) {
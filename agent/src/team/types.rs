@@ -1,3 +1,8 @@
+use crate::analyzer::structured::check_probability;
+use crate::analyzer::transcript::PromptRecord;
+use crate::analyzer::StructuredOutput;
+use crate::team::events::{EventKey, Signal};
+use crate::team::judge::ClaudeFinalVerdict;
 use crate::types::{Direction, EnrichmentData, Market};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
@@ -30,6 +35,9 @@ pub enum CaseStrength {
     Overwhelming,
 }
 
+/// Spellings the Bull/Bear analysts may use for `case_strength`
+pub const CASE_STRENGTHS: [&str; 4] = ["WEAK", "MODERATE", "STRONG", "OVERWHELMING"];
+
 impl std::fmt::Display for CaseStrength {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -48,6 +56,8 @@ pub struct MarketCandidate {
     pub quality_score: f64,
     #[allow(dead_code)]
     pub reason: String,
+    /// Parent event, shared with sibling markets (threshold ladders, "who wins" sets)
+    pub event: EventKey,
 }
 
 /// Scout output: filtered and scored market candidates
@@ -56,6 +66,8 @@ pub struct ScoutReport {
     pub candidates: Vec<MarketCandidate>,
     pub total_scanned: usize,
     pub total_passed_quality: usize,
+    /// Events with more than one candidate
+    pub grouped_events: usize,
 }
 
 /// Data Analyst output: quantitative data per candidate
@@ -127,6 +139,16 @@ impl BearCase {
     }
 }
 
+/// Judge's probability vector over the outcomes of a categorical market
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutcomeVerdict {
+    pub market_id: String,
+    /// (outcome label, probability) in token order, normalised to sum to 1
+    pub probabilities: Vec<(String, f64)>,
+    pub confidence: f64,
+    pub reasoning: String,
+}
+
 /// Devil's Advocate final verdict
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DevilsVerdict {
@@ -137,6 +159,9 @@ pub struct DevilsVerdict {
     pub reasoning: String,
     pub bull_flaws: String,
     pub bear_flaws: String,
+    /// Judge's own numbers before calibration (equal to the fields above when uncalibrated)
+    pub raw_fair_value_yes: f64,
+    pub raw_confidence: f64,
 }
 
 impl DevilsVerdict {
@@ -182,6 +207,25 @@ pub struct TradePlan {
     pub bull_probability: Option<f64>,
     pub bear_probability: Option<f64>,
     pub judge_model: Option<String>,
+    pub raw_fair_value_yes: Option<f64>,
+    pub raw_confidence: Option<f64>,
+    /// Outcome bought in a categorical market; `market` is then its binary view
+    pub outcome: Option<String>,
+}
+
+/// Every artefact of the debate behind one trade, plus the exact prompts sent
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TradeDossier {
+    pub research: ResearchDossier,
+    pub desk_report: DeskReport,
+    pub bull: BullCase,
+    pub bear: BearCase,
+    pub verdict: DevilsVerdict,
+    pub final_verdict: Option<ClaudeFinalVerdict>,
+    pub prompts: Vec<PromptRecord>,
+    /// Probability vector that picked the outcome, for categorical markets
+    #[serde(default)]
+    pub outcome_verdict: Option<OutcomeVerdict>,
 }
 
 /// Cycle statistics for the team pipeline
@@ -194,6 +238,189 @@ pub struct TeamCycleStats {
     pub markets_approved: usize,
     pub trades_placed: usize,
     pub api_cost: Decimal,
+    pub outcomes: Vec<CandidateOutcome>, // one per Scout candidate
+    pub event_signals: Vec<Signal>,      // cross-market inconsistencies found this cycle
+}
+
+impl TeamCycleStats {
+    /// Candidates that reached each stage, in pipeline order
+    pub fn funnel(&self) -> Vec<(Stage, usize)> {
+        Stage::ALL
+            .iter()
+            .map(|&stage| (stage, self.outcomes.iter().filter(|o| o.stage >= stage).count()))
+            .collect()
+    }
+
+    /// Drop count per reject reason, most frequent first
+    pub fn rejections(&self) -> Vec<(RejectReason, usize)> {
+        let mut counts: HashMap<RejectReason, usize> = HashMap::new();
+        for reason in self.outcomes.iter().filter_map(|o| o.reject_reason) {
+            *counts.entry(reason).or_default() += 1;
+        }
+        let mut out: Vec<_> = counts.into_iter().collect();
+        out.sort_by_key(|&(reason, n)| (std::cmp::Reverse(n), reason));
+        out
+    }
+}
+
+/// Pipeline stages a candidate passes through, in order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Stage {
+    Scout,
+    Research, // data pack + dossier
+    Desk,
+    Debate, // Bull + Bear
+    Judge,
+    Event, // cross-market consistency within the parent event
+    Risk,
+    Strategist,
+    Validator,
+    Execute,
+}
+
+impl Stage {
+    pub const ALL: [Stage; 10] = [
+        Stage::Scout,
+        Stage::Research,
+        Stage::Desk,
+        Stage::Debate,
+        Stage::Judge,
+        Stage::Event,
+        Stage::Risk,
+        Stage::Strategist,
+        Stage::Validator,
+        Stage::Execute,
+    ];
+}
+
+impl std::fmt::Display for Stage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Stage::Scout => write!(f, "SCOUT"),
+            Stage::Research => write!(f, "RESEARCH"),
+            Stage::Desk => write!(f, "DESK"),
+            Stage::Debate => write!(f, "DEBATE"),
+            Stage::Judge => write!(f, "JUDGE"),
+            Stage::Event => write!(f, "EVENT"),
+            Stage::Risk => write!(f, "RISK"),
+            Stage::Strategist => write!(f, "STRATEGIST"),
+            Stage::Validator => write!(f, "VALIDATOR"),
+            Stage::Execute => write!(f, "EXECUTE"),
+        }
+    }
+}
+
+/// Why a candidate left the pipeline without a trade
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum RejectReason {
+    RecentlyAnalyzed,
+    OverAnalysisLimit, // beyond MAX_DEEP_ANALYSIS this cycle
+    NoDataPack,
+    NoDossier,
+    NoOutcomeEdge, // categorical market: no outcome priced below the judge's probability
+    DeskFailed,
+    BullFailed,
+    BearFailed,
+    JudgeFailed,
+    JudgeSkip, // SKIP from the judge or after calibration
+    RiskRejected,
+    EdgeBelowStop,
+    EventExposure, // parent event already at MAX_EVENT_EXPOSURE_PCT
+    StrategyCapital, // team's STRATEGY_ALLOCATIONS share already committed
+    ValidatorRejected,
+    NotFilled,
+}
+
+impl std::fmt::Display for RejectReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RejectReason::RecentlyAnalyzed => write!(f, "RECENTLY_ANALYZED"),
+            RejectReason::OverAnalysisLimit => write!(f, "OVER_ANALYSIS_LIMIT"),
+            RejectReason::NoDataPack => write!(f, "NO_DATA_PACK"),
+            RejectReason::NoDossier => write!(f, "NO_DOSSIER"),
+            RejectReason::NoOutcomeEdge => write!(f, "NO_OUTCOME_EDGE"),
+            RejectReason::DeskFailed => write!(f, "DESK_FAILED"),
+            RejectReason::BullFailed => write!(f, "BULL_FAILED"),
+            RejectReason::BearFailed => write!(f, "BEAR_FAILED"),
+            RejectReason::JudgeFailed => write!(f, "JUDGE_FAILED"),
+            RejectReason::JudgeSkip => write!(f, "JUDGE_SKIP"),
+            RejectReason::RiskRejected => write!(f, "RISK_REJECTED"),
+            RejectReason::EdgeBelowStop => write!(f, "EDGE_BELOW_SL"),
+            RejectReason::EventExposure => write!(f, "EVENT_EXPOSURE"),
+            RejectReason::StrategyCapital => write!(f, "STRATEGY_CAPITAL"),
+            RejectReason::ValidatorRejected => write!(f, "VALIDATOR_REJECTED"),
+            RejectReason::NotFilled => write!(f, "NOT_FILLED"),
+        }
+    }
+}
+
+/// One candidate's path through the pipeline in one cycle: how far it got,
+/// why it stopped (None = traded), the numbers behind the call and time per stage
+#[derive(Debug, Clone)]
+pub struct CandidateOutcome {
+    pub market_id: String,
+    pub question: String,
+    pub desk: Option<String>,
+    pub stage: Stage,
+    pub reject_reason: Option<RejectReason>,
+    pub edge: Option<f64>,
+    pub confidence: Option<f64>,
+    pub bet_size: Option<Decimal>,
+    pub latency_ms: Vec<(Stage, u64)>,
+    stage_started: std::time::Instant,
+}
+
+impl CandidateOutcome {
+    pub fn new(market: &Market, stage: Stage) -> Self {
+        Self {
+            market_id: market.id.clone(),
+            question: market.question.clone(),
+            desk: None,
+            stage,
+            reject_reason: None,
+            edge: None,
+            confidence: None,
+            bet_size: None,
+            latency_ms: Vec::new(),
+            stage_started: std::time::Instant::now(),
+        }
+    }
+
+    /// Start the current stage's timer at `started` instead of now, for work that
+    /// ran batched across candidates before the outcome existed
+    pub fn since(mut self, started: std::time::Instant) -> Self {
+        self.stage_started = started;
+        self
+    }
+
+    /// Dropped before any per-candidate work (not timed)
+    pub fn dropped(market: &Market, reason: RejectReason) -> Self {
+        let mut outcome = Self::new(market, Stage::Scout);
+        outcome.reject_reason = Some(reason);
+        outcome
+    }
+
+    /// Close the current stage's timer and move on to `stage`
+    pub fn enter(&mut self, stage: Stage) {
+        self.close_stage();
+        self.stage = stage;
+    }
+
+    /// Stop at the current stage
+    pub fn reject(&mut self, reason: RejectReason) {
+        self.close_stage();
+        self.reject_reason = Some(reason);
+    }
+
+    /// Made it through execution
+    pub fn finish(&mut self) {
+        self.close_stage();
+    }
+
+    fn close_stage(&mut self) {
+        self.latency_ms.push((self.stage, self.stage_started.elapsed().as_millis() as u64));
+        self.stage_started = std::time::Instant::now();
+    }
 }
 
 /// Auditor insight from post-trade learning
@@ -297,3 +524,52 @@ pub struct DeskReport {
     pub data_summary: String,
     pub confidence_in_data: f64,
 }
+
+/// Reply shape shared by the four specialist desks
+#[derive(Debug, Deserialize)]
+pub struct DeskResponse {
+    pub specialist_probability: f64,
+    #[serde(default)]
+    pub key_factors: Vec<String>,
+    #[serde(default)]
+    pub risk_assessment: String,
+    #[serde(default)]
+    pub data_summary: String,
+    #[serde(default)]
+    pub confidence_in_data: f64,
+}
+
+impl StructuredOutput for DeskResponse {
+    fn schema() -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "specialist_probability": { "type": "number" },
+                "key_factors": { "type": "array", "items": { "type": "string" } },
+                "risk_assessment": { "type": "string" },
+                "data_summary": { "type": "string" },
+                "confidence_in_data": { "type": "number" }
+            },
+            "required": ["specialist_probability", "key_factors", "risk_assessment", "confidence_in_data"]
+        })
+    }
+
+    fn validate(&self) -> anyhow::Result<()> {
+        check_probability("specialist_probability", self.specialist_probability)?;
+        check_probability("confidence_in_data", self.confidence_in_data)
+    }
+}
+
+impl DeskResponse {
+    pub fn into_report(self, market_id: &str, desk: DeskType) -> DeskReport {
+        DeskReport {
+            market_id: market_id.to_string(),
+            desk,
+            specialist_probability: self.specialist_probability.clamp(0.05, 0.95),
+            key_factors: self.key_factors,
+            risk_assessment: self.risk_assessment,
+            data_summary: self.data_summary,
+            confidence_in_data: self.confidence_in_data.clamp(0.0, 1.0),
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use crate::http::HttpClient;
 use crate::paper::PortfolioStats;
 use crate::types::Trade;
 use anyhow::Result;
@@ -8,18 +9,15 @@ use tracing::{debug, info, warn};
 pub struct TelegramAlert {
     bot_token: String,
     chat_id: String,
-    client: reqwest::Client,
+    http: HttpClient,
 }
 
 impl TelegramAlert {
-    pub fn new(bot_token: &str, chat_id: &str) -> Self {
+    pub fn new(bot_token: &str, chat_id: &str, http: HttpClient) -> Self {
         Self {
             bot_token: bot_token.to_string(),
             chat_id: chat_id.to_string(),
-            client: reqwest::Client::builder()
-                .timeout(std::time::Duration::from_secs(10))
-                .build()
-                .expect("HTTP client"),
+            http,
         }
     }
 
@@ -45,7 +43,7 @@ impl TelegramAlert {
             "disable_web_page_preview": true,
         });
 
-        let resp = self.client.post(&url).json(&body).send().await?;
+        let resp = self.http.send(self.http.post(&url).json(&body)).await?;
 
         if resp.status().is_success() {
             debug!("Telegram message sent");
@@ -179,6 +177,10 @@ impl TelegramAlert {
                 format!("<b>[MARKET RESOLVED] TRADE CLOSED</b>"),
                 String::new(),
             ),
+            Some(crate::types::ExitReason::ArbUnwind) => (
+                "<b>[ARB UNWIND] TRADE CLOSED</b>".to_string(),
+                String::new(),
+            ),
             _ => {
                 let emoji = if trade.pnl > rust_decimal::Decimal::ZERO { "WIN" } else { "LOSS" };
                 (format!("<b>[{emoji}] TRADE CLOSED</b>"), String::new())
@@ -330,7 +332,7 @@ impl TelegramAlert {
             id: i64,
         }
 
-        let resp = match self.client.get(&url).send().await {
+        let resp = match self.http.send(self.http.get(&url)).await {
             Ok(r) => r,
             Err(_) => return vec![],
         };
@@ -28,6 +28,56 @@ pub struct Market {
     pub fetched_at: DateTime<Utc>,
 }
 
+impl Market {
+    /// Plain YES/NO market. Anything else (elections, "who will win", price
+    /// buckets, team-vs-team) is categorical: one token per outcome.
+    pub fn is_binary(&self) -> bool {
+        self.tokens.len() <= 2
+            && self.tokens.iter().all(|t| t.outcome.eq_ignore_ascii_case("yes") || t.outcome.eq_ignore_ascii_case("no"))
+    }
+
+    /// Token for an outcome label (case-insensitive)
+    pub fn token_for(&self, outcome: &str) -> Option<&TokenInfo> {
+        self.tokens.iter().find(|t| t.outcome.eq_ignore_ascii_case(outcome))
+    }
+
+    /// Token bought for a direction in a binary market
+    pub fn side_token(&self, direction: Direction) -> Option<&TokenInfo> {
+        match direction {
+            Direction::Yes => self.token_for("Yes"),
+            Direction::No => self.token_for("No"),
+            Direction::Skip => None,
+        }
+    }
+
+    /// "A 45% | B 30% | ..." for prompts
+    pub fn outcome_summary(&self) -> String {
+        self.tokens
+            .iter()
+            .map(|t| format!("{} {}%", t.outcome, (t.price * Decimal::from(100)).round()))
+            .collect::<Vec<_>>()
+            .join(" | ")
+    }
+
+    /// Binary view of one outcome of a categorical market: YES = "this outcome
+    /// wins", priced at its token. Keeps the market id so positions still match
+    /// the scanned market; there is no NO token to buy.
+    pub fn outcome_view(&self, outcome: &str) -> Option<Market> {
+        let token = self.token_for(outcome)?;
+        Some(Market {
+            question: format!("{} — {}", self.question, token.outcome),
+            yes_price: token.price,
+            no_price: Decimal::ONE - token.price,
+            tokens: vec![TokenInfo {
+                token_id: token.token_id.clone(),
+                outcome: "Yes".to_string(),
+                price: token.price,
+            }],
+            ..self.clone()
+        })
+    }
+}
+
 /// Pre-filter score for Tier 0 heuristic ranking (Legacy - not used in v2.0)
 #[allow(dead_code)]
 #[derive(Debug, Clone)]
@@ -82,6 +132,20 @@ impl std::fmt::Display for Direction {
     }
 }
 
+impl std::str::FromStr for Direction {
+    type Err = anyhow::Error;
+
+    /// Parse the `Display` form stored in the `trades.direction` column
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_uppercase().as_str() {
+            "YES" => Ok(Direction::Yes),
+            "NO" => Ok(Direction::No),
+            "SKIP" => Ok(Direction::Skip),
+            other => anyhow::bail!("Unknown direction: {other}"),
+        }
+    }
+}
+
 /// Trade record
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Trade {
@@ -112,9 +176,20 @@ pub struct Trade {
     pub judge_fair_value: Option<f64>,
     pub judge_confidence: Option<f64>,
     pub judge_model: Option<String>,       // "sonnet" or "gemini"
+    // Judge output after the calibration layer (judge_* above keep the raw values)
+    #[serde(default)]
+    pub calibrated_fair_value: Option<f64>,
+    #[serde(default)]
+    pub calibrated_confidence: Option<f64>,
     pub exit_reason: Option<ExitReason>,
     pub hold_duration_hours: Option<f64>,
     pub token_id: Option<String>,          // YES/NO token ID for CLOB pricing
+    /// Outcome held in a categorical market (None for YES/NO markets)
+    #[serde(default)]
+    pub outcome: Option<String>,
+    /// Strategy that opened the trade (`Strategy::name`); None = team (older rows)
+    #[serde(default)]
+    pub strategy: Option<String>,
     // Paper Trading Plus — simulation tracking
     pub raw_entry_price: Option<Decimal>,
     pub raw_exit_price: Option<Decimal>,
@@ -134,6 +209,21 @@ pub enum TradeStatus {
     Cancelled,
 }
 
+impl std::str::FromStr for TradeStatus {
+    type Err = anyhow::Error;
+
+    /// Parse the `Debug` form stored in the `trades.status` column
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Open" => Ok(TradeStatus::Open),
+            "Won" => Ok(TradeStatus::Won),
+            "Lost" => Ok(TradeStatus::Lost),
+            "Cancelled" => Ok(TradeStatus::Cancelled),
+            other => anyhow::bail!("Unknown trade status: {other}"),
+        }
+    }
+}
+
 /// Exit reason for closed trades (paper trading)
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
 pub enum ExitReason {
@@ -144,6 +234,7 @@ pub enum ExitReason {
     ManualStop,
     SafetyValve,    // Conviction trade, loss > 50%
     EdgeCaptured,   // Swing trade, price moved 50%+ toward fair value
+    ArbUnwind,      // Arbitrage leg sold back after another leg failed to fill
 }
 
 impl fmt::Display for ExitReason {
@@ -156,6 +247,26 @@ impl fmt::Display for ExitReason {
             ExitReason::ManualStop => write!(f, "MANUAL"),
             ExitReason::SafetyValve => write!(f, "SAFETY"),
             ExitReason::EdgeCaptured => write!(f, "EDGE"),
+            ExitReason::ArbUnwind => write!(f, "UNWIND"),
+        }
+    }
+}
+
+impl std::str::FromStr for ExitReason {
+    type Err = anyhow::Error;
+
+    /// Parse the `Display` form stored in the `trades.exit_reason` column
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "TP" => Ok(ExitReason::TakeProfit),
+            "SL" => Ok(ExitReason::StopLoss),
+            "TIME" => Ok(ExitReason::TimeExpiry),
+            "RESOLVED" => Ok(ExitReason::MarketResolved),
+            "MANUAL" => Ok(ExitReason::ManualStop),
+            "SAFETY" => Ok(ExitReason::SafetyValve),
+            "EDGE" => Ok(ExitReason::EdgeCaptured),
+            "UNWIND" => Ok(ExitReason::ArbUnwind),
+            other => anyhow::bail!("Unknown exit reason: {other}"),
         }
     }
 }
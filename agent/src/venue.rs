@@ -0,0 +1,138 @@
+use crate::live::clob::OrderBookSummary;
+use crate::paper::portfolio::{exit_signal, position_price, settlement_price};
+use crate::paper::{Portfolio, RestingOrder};
+use crate::types::{Direction, ExitReason, Market, Trade};
+use async_trait::async_trait;
+use chrono::Utc;
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+
+/// A limit order the venue accepted
+#[derive(Debug, Clone)]
+pub struct Placed {
+    /// The order as booked: venue id, shares still working
+    pub order: RestingOrder,
+    /// The marketable part, filled on arrival as a taker
+    pub fill: Option<Trade>,
+    /// Left working on the book (GTC/GTD remainder)
+    pub resting: bool,
+}
+
+/// Where orders go: the paper simulator or the live CLOB.
+/// Both keep their books in a `Portfolio` ledger, so risk checks, stats and reports
+/// read the same numbers whichever venue is active.
+#[async_trait]
+pub trait ExecutionVenue: Send + Sync {
+    /// Short label for logs and alerts ("PAPER" / "LIVE")
+    fn name(&self) -> &'static str;
+
+    /// Position and P&L accounting for this venue
+    fn ledger(&self) -> &Portfolio;
+
+    /// Buy `bet_size` USDC of the `direction` side. None = rejected / not filled.
+    async fn open_position(
+        &self,
+        market: &Market,
+        direction: Direction,
+        fair_value: Decimal,
+        edge: Decimal,
+        bet_size: Decimal,
+    ) -> Option<Trade>;
+
+    /// Exit one open trade; `current_price` is the held side's market price.
+    /// None = exit not completed (trade stays open).
+    async fn close_position(&self, trade: &Trade, current_price: Decimal, reason: ExitReason) -> Option<Trade>;
+
+    /// Send a limit buy with the order's time-in-force. `book` is the market's YES book
+    /// at submission (the paper venue matches against it). None = rejected or killed.
+    async fn place_limit(&self, order: RestingOrder, book: &OrderBookSummary) -> Option<Placed>;
+
+    /// Pull a resting order. Returns what was still working (None = unknown or already done).
+    async fn cancel_order(&self, order_id: &str) -> Option<RestingOrder>;
+
+    /// Book fills of resting orders since the last sync and return the trades they opened.
+    /// `books` (market id -> YES book) drives the paper simulation; the live venue asks the exchange.
+    async fn sync_orders(&self, books: &HashMap<String, OrderBookSummary>) -> Vec<Trade>;
+
+    fn resting_orders(&self) -> Vec<RestingOrder> {
+        self.ledger().resting_orders()
+    }
+
+    /// Pull every resting order (shutdown). Returns the ids cancelled.
+    async fn cancel_all_orders(&self) -> Vec<String> {
+        let mut cancelled = Vec::new();
+        for order in self.resting_orders() {
+            if self.cancel_order(&order.id).await.is_some() {
+                cancelled.push(order.id);
+            }
+        }
+        cancelled
+    }
+
+    fn balance(&self) -> Decimal {
+        self.ledger().balance()
+    }
+
+    fn open_positions(&self) -> Vec<Trade> {
+        self.ledger().open_trades()
+    }
+
+    /// Apply exit rules to every open trade and close the ones that trigger.
+    /// Positions in markets `winners` holds (market id -> winning outcome, closed markets
+    /// only) settle at 1/0. Markets no longer listed but not settled yet stay open.
+    async fn close_positions(
+        &self,
+        markets: &[Market],
+        winners: &HashMap<String, String>,
+        exit_tp_pct: Decimal,
+        exit_sl_pct: Decimal,
+    ) -> Vec<Trade> {
+        let mut closed = Vec::new();
+
+        for trade in self.open_positions() {
+            if trade.direction == Direction::Skip {
+                continue;
+            }
+
+            let market = markets.iter().find(|m| m.id == trade.market_id);
+            let payout = winners.get(&trade.market_id).map(|w| settlement_price(&trade, w));
+            let (price, reason) = match (market, payout) {
+                // A closed market settles every outcome held in it (categorical positions, arb legs)
+                (_, Some(payout)) => (payout, ExitReason::MarketResolved),
+                (Some(market), None) => {
+                    let price = position_price(&trade, market);
+                    match exit_signal(&trade, price, exit_tp_pct, exit_sl_pct, Utc::now()) {
+                        Some(reason) => (price, reason),
+                        None => continue,
+                    }
+                }
+                (None, None) => continue,
+            };
+
+            if let Some(t) = self.close_position(&trade, price, reason).await {
+                closed.push(t);
+            }
+        }
+
+        closed
+    }
+
+    /// Close every open position at current prices (graceful shutdown)
+    async fn close_all_positions(&self, markets: &[Market]) -> Vec<Trade> {
+        let mut closed = Vec::new();
+
+        for trade in self.open_positions() {
+            let price = markets
+                .iter()
+                .find(|m| m.id == trade.market_id)
+                .map(|m| position_price(&trade, m))
+                .unwrap_or(trade.entry_price);
+
+            if let Some(t) = self.close_position(&trade, price, ExitReason::ManualStop).await {
+                closed.push(t);
+            }
+        }
+
+        closed
+    }
+}